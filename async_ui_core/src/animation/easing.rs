@@ -0,0 +1,51 @@
+//! Easing curves for [Tween][super::Tween]s.
+//!
+//! Each maps the fraction of the duration that has passed (from 0 to 1)
+//! to the fraction of the distance to cover (0 at the start, 1 at the end).
+
+pub type Easing = fn(f64) -> f64;
+
+pub fn linear(t: f64) -> f64 {
+    t
+}
+pub fn ease_in_quad(t: f64) -> f64 {
+    t * t
+}
+pub fn ease_out_quad(t: f64) -> f64 {
+    t * (2.0 - t)
+}
+pub fn ease_in_out_quad(t: f64) -> f64 {
+    if t < 0.5 {
+        2.0 * t * t
+    } else {
+        -1.0 + (4.0 - 2.0 * t) * t
+    }
+}
+pub fn ease_in_cubic(t: f64) -> f64 {
+    t * t * t
+}
+pub fn ease_out_cubic(t: f64) -> f64 {
+    1.0 - (1.0 - t).powi(3)
+}
+pub fn ease_in_out_cubic(t: f64) -> f64 {
+    if t < 0.5 {
+        4.0 * t * t * t
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+    }
+}
+pub fn ease_in_sine(t: f64) -> f64 {
+    1.0 - (t * std::f64::consts::FRAC_PI_2).cos()
+}
+pub fn ease_out_sine(t: f64) -> f64 {
+    (t * std::f64::consts::FRAC_PI_2).sin()
+}
+pub fn ease_in_out_sine(t: f64) -> f64 {
+    (1.0 - (t * std::f64::consts::PI).cos()) / 2.0
+}
+/// Overshoots the end a little, then comes back.
+pub fn ease_out_back(t: f64) -> f64 {
+    const C1: f64 = 1.70158;
+    const C3: f64 = C1 + 1.0;
+    1.0 + C3 * (t - 1.0).powi(3) + C1 * (t - 1.0).powi(2)
+}
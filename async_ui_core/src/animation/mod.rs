@@ -0,0 +1,138 @@
+//! Values that move over time: [Tween]s and [Spring]s.
+//!
+//! Both are observable, so they can be given straight to components and style bindings.
+//! Stepping them is plain math on timestamps.
+//! The timestamps come from a [Clock]: the browser's animation frames, or a [ManualClock] in tests.
+use std::{
+    future::{pending, Future},
+    pin::Pin,
+};
+
+use futures_lite::FutureExt;
+use observables::{cell::ReactiveCell, ObservableAs, ObservableAsExt};
+
+pub mod easing;
+mod spring;
+mod tween;
+
+pub use spring::{Spring, SpringConfig};
+pub use tween::Tween;
+
+/// A source of animation frames.
+pub trait Clock {
+    /// Wait for the next frame, then return its timestamp in milliseconds.
+    fn next_frame(&self) -> Pin<Box<dyn Future<Output = f64> + '_>>;
+}
+
+/// A [Clock] that only moves when told to, for tests.
+pub struct ManualClock {
+    now: ReactiveCell<f64>,
+}
+impl ManualClock {
+    pub fn new(start: f64) -> Self {
+        Self {
+            now: ReactiveCell::new(start),
+        }
+    }
+    pub fn now(&self) -> f64 {
+        *self.now.as_observable().borrow_observable_as()
+    }
+    /// Move time forward by `ms`, giving a frame to everything waiting for one.
+    pub fn advance(&self, ms: f64) {
+        *self.now.borrow_mut() += ms;
+    }
+}
+impl Clock for ManualClock {
+    fn next_frame(&self) -> Pin<Box<dyn Future<Output = f64> + '_>> {
+        Box::pin(async {
+            self.now.as_observable().until_change().await;
+            self.now()
+        })
+    }
+}
+
+/// A value that can be animated: something with a distance between two values.
+pub trait Animatable: Clone + 'static {
+    fn add(&self, other: &Self) -> Self;
+    fn sub(&self, other: &Self) -> Self;
+    fn scale(&self, factor: f64) -> Self;
+    /// The size of the value, to tell when a [Spring] has come to rest.
+    fn magnitude(&self) -> f64;
+    /// The value `t` of the way from `self` to `to`.
+    fn lerp(&self, to: &Self, t: f64) -> Self {
+        self.add(&to.sub(self).scale(t))
+    }
+}
+impl Animatable for f64 {
+    fn add(&self, other: &Self) -> Self {
+        self + other
+    }
+    fn sub(&self, other: &Self) -> Self {
+        self - other
+    }
+    fn scale(&self, factor: f64) -> Self {
+        self * factor
+    }
+    fn magnitude(&self) -> f64 {
+        self.abs()
+    }
+}
+impl Animatable for f32 {
+    fn add(&self, other: &Self) -> Self {
+        self + other
+    }
+    fn sub(&self, other: &Self) -> Self {
+        self - other
+    }
+    fn scale(&self, factor: f64) -> Self {
+        (*self as f64 * factor) as f32
+    }
+    fn magnitude(&self) -> f64 {
+        self.abs() as f64
+    }
+}
+impl Animatable for (f64, f64) {
+    fn add(&self, other: &Self) -> Self {
+        (self.0 + other.0, self.1 + other.1)
+    }
+    fn sub(&self, other: &Self) -> Self {
+        (self.0 - other.0, self.1 - other.1)
+    }
+    fn scale(&self, factor: f64) -> Self {
+        (self.0 * factor, self.1 * factor)
+    }
+    fn magnitude(&self) -> f64 {
+        self.0.hypot(self.1)
+    }
+}
+impl<const N: usize> Animatable for [f64; N] {
+    fn add(&self, other: &Self) -> Self {
+        std::array::from_fn(|i| self[i] + other[i])
+    }
+    fn sub(&self, other: &Self) -> Self {
+        std::array::from_fn(|i| self[i] - other[i])
+    }
+    fn scale(&self, factor: f64) -> Self {
+        self.map(|x| x * factor)
+    }
+    fn magnitude(&self) -> f64 {
+        self.iter().map(|x| x * x).sum::<f64>().sqrt()
+    }
+}
+
+/// Call `step` on each frame of `clock` while `animating` is `true`. Never completes.
+async fn drive(animating: &ReactiveCell<bool>, clock: &dyn Clock, step: impl Fn(f64)) {
+    loop {
+        while !*animating.as_observable().borrow_observable_as() {
+            animating.as_observable().until_change().await;
+        }
+        // Stop waiting for the frame if the animation is stopped in the meantime.
+        let frame = clock.next_frame().or(async {
+            while *animating.as_observable().borrow_observable_as() {
+                animating.as_observable().until_change().await;
+            }
+            pending().await
+        });
+        step(frame.await);
+    }
+}
@@ -0,0 +1,187 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    task::Waker,
+};
+
+use observables::{
+    cell::{ReactiveCell, ReactiveCellObservable},
+    Listenable, Observable, ObservableAs, ObservableBorrow, Version,
+};
+
+use super::{drive, Animatable, Clock};
+
+/// How a [Spring] moves.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpringConfig {
+    pub stiffness: f64,
+    pub damping: f64,
+    pub mass: f64,
+    /// The spring comes to rest on its target once both the distance to the target
+    /// and the speed (per second) are below this.
+    pub precision: f64,
+}
+impl SpringConfig {
+    pub const GENTLE: Self = Self::new(120.0, 14.0);
+    pub const WOBBLY: Self = Self::new(180.0, 12.0);
+    pub const STIFF: Self = Self::new(210.0, 20.0);
+    pub const fn new(stiffness: f64, damping: f64) -> Self {
+        Self {
+            stiffness,
+            damping,
+            mass: 1.0,
+            precision: 0.01,
+        }
+    }
+}
+impl Default for SpringConfig {
+    fn default() -> Self {
+        Self::new(170.0, 26.0)
+    }
+}
+
+/// The longest time integrated in one go. Frames further apart are split into substeps.
+const MAX_SUBSTEP: f64 = 1.0 / 120.0;
+/// The longest time a frame can account for, so a spring doesn't jump after the page was hidden.
+const MAX_FRAME: f64 = 0.1;
+
+struct Motion<T> {
+    target: T,
+    /// Per second.
+    velocity: T,
+    /// Timestamp of the last step.
+    last: Option<f64>,
+}
+
+/// A value pulled towards its target by a damped spring.
+///
+/// Unlike a [Tween][super::Tween], a spring keeps its velocity when the target changes,
+/// so interrupted animations stay smooth.
+/// Observing it gives the current value. Nothing moves until [run][Self::run] is polled
+/// (or [step][Self::step] is called).
+pub struct Spring<T: Animatable> {
+    value: Rc<ReactiveCell<T>>,
+    observable: ReactiveCellObservable<T, Rc<ReactiveCell<T>>>,
+    motion: RefCell<Motion<T>>,
+    animating: ReactiveCell<bool>,
+    config: Cell<SpringConfig>,
+}
+
+impl<T: Animatable> Spring<T> {
+    /// A spring resting at `initial`.
+    pub fn new(initial: T, config: SpringConfig) -> Self {
+        let value = Rc::new(ReactiveCell::new(initial.clone()));
+        Self {
+            observable: value.as_observable_rc(),
+            value,
+            motion: RefCell::new(Motion {
+                velocity: initial.scale(0.0),
+                target: initial,
+                last: None,
+            }),
+            animating: ReactiveCell::new(false),
+            config: Cell::new(config),
+        }
+    }
+    /// Start pulling towards `to`, keeping the current velocity.
+    pub fn set_target(&self, to: T) {
+        self.motion.borrow_mut().target = to;
+        self.set_animating(true);
+    }
+    /// Go to `value` right away and rest there.
+    pub fn jump_to(&self, value: T) {
+        *self.motion.borrow_mut() = Motion {
+            velocity: value.scale(0.0),
+            target: value.clone(),
+            last: None,
+        };
+        self.value.set(value);
+        self.set_animating(false);
+    }
+    /// Stop where the value is now.
+    pub fn stop(&self) {
+        self.jump_to(self.get());
+    }
+    /// The value now.
+    pub fn get(&self) -> T {
+        self.observable.borrow_observable().clone()
+    }
+    pub fn target(&self) -> T {
+        self.motion.borrow().target.clone()
+    }
+    /// The velocity now, per second.
+    pub fn velocity(&self) -> T {
+        self.motion.borrow().velocity.clone()
+    }
+    pub fn set_config(&self, config: SpringConfig) {
+        self.config.set(config);
+    }
+    /// Whether the value is moving.
+    pub fn is_animating(&self) -> impl ObservableAs<bool> + '_ {
+        self.animating.as_observable()
+    }
+    /// Move the value to where it should be at `now` (in milliseconds).
+    ///
+    /// The first step after the spring starts moving only records the time.
+    pub fn step(&self, now: f64) {
+        if !*self.animating.as_observable().borrow_observable() {
+            return;
+        }
+        let SpringConfig {
+            stiffness,
+            damping,
+            mass,
+            precision,
+        } = self.config.get();
+        let mut motion = self.motion.borrow_mut();
+        let elapsed = match motion.last.replace(now) {
+            Some(last) => ((now - last) / 1000.0).clamp(0.0, MAX_FRAME),
+            None => 0.0,
+        };
+        let mut value = self.get();
+        let substeps = (elapsed / MAX_SUBSTEP).ceil().max(1.0);
+        let dt = elapsed / substeps;
+        for _ in 0..substeps as usize {
+            let displacement = value.sub(&motion.target);
+            let force = displacement
+                .scale(-stiffness)
+                .sub(&motion.velocity.scale(damping));
+            motion.velocity = motion.velocity.add(&force.scale(dt / mass));
+            value = value.add(&motion.velocity.scale(dt));
+        }
+        let settled = value.sub(&motion.target).magnitude() < precision
+            && motion.velocity.magnitude() < precision;
+        if settled {
+            let target = motion.target.clone();
+            drop(motion);
+            self.jump_to(target);
+        } else {
+            drop(motion);
+            self.value.set(value);
+        }
+    }
+    /// Step on each frame of `clock` while animating. Never completes.
+    pub async fn run(&self, clock: &dyn Clock) {
+        drive(&self.animating, clock, |now| self.step(now)).await
+    }
+    fn set_animating(&self, animating: bool) {
+        if *self.animating.as_observable().borrow_observable() != animating {
+            self.animating.set(animating);
+        }
+    }
+}
+
+impl<T: Animatable> Listenable for Spring<T> {
+    fn add_waker(&self, waker: Waker) {
+        self.observable.add_waker(waker)
+    }
+    fn get_version(&self) -> Version {
+        self.observable.get_version()
+    }
+}
+impl<T: Animatable> Observable for Spring<T> {
+    type Data = T;
+    fn borrow_observable<'b>(&'b self) -> ObservableBorrow<'b, T> {
+        self.observable.borrow_observable()
+    }
+}
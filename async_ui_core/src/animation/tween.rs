@@ -0,0 +1,144 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    task::Waker,
+};
+
+use observables::{
+    cell::{ReactiveCell, ReactiveCellObservable},
+    Listenable, Observable, ObservableAs, ObservableBorrow, Version,
+};
+
+use super::{drive, easing::Easing, Animatable, Clock};
+
+struct Segment<T> {
+    from: T,
+    to: T,
+    /// Timestamp of the first frame since the segment started.
+    start: Option<f64>,
+}
+
+/// A value that moves to its target over a fixed duration, along an easing curve.
+///
+/// Observing it gives the current value. Nothing moves until [run][Self::run] is polled
+/// (or [step][Self::step] is called).
+pub struct Tween<T: Animatable> {
+    value: Rc<ReactiveCell<T>>,
+    observable: ReactiveCellObservable<T, Rc<ReactiveCell<T>>>,
+    segment: RefCell<Segment<T>>,
+    animating: ReactiveCell<bool>,
+    duration: Cell<f64>,
+    easing: Cell<Easing>,
+}
+
+impl<T: Animatable> Tween<T> {
+    /// A tween resting at `initial`, that takes `duration` milliseconds to reach each new target.
+    pub fn new(initial: T, duration: f64, easing: Easing) -> Self {
+        let value = Rc::new(ReactiveCell::new(initial.clone()));
+        Self {
+            observable: value.as_observable_rc(),
+            value,
+            segment: RefCell::new(Segment {
+                from: initial.clone(),
+                to: initial,
+                start: None,
+            }),
+            animating: ReactiveCell::new(false),
+            duration: Cell::new(duration),
+            easing: Cell::new(easing),
+        }
+    }
+    /// Start moving to `to`.
+    ///
+    /// If an animation is running, it is interrupted: the new one starts from wherever the value is now
+    /// and takes the full duration.
+    pub fn set_target(&self, to: T) {
+        *self.segment.borrow_mut() = Segment {
+            from: self.get(),
+            to,
+            start: None,
+        };
+        self.set_animating(true);
+    }
+    /// Go to `value` right away, without animating.
+    pub fn jump_to(&self, value: T) {
+        *self.segment.borrow_mut() = Segment {
+            from: value.clone(),
+            to: value.clone(),
+            start: None,
+        };
+        self.value.set(value);
+        self.set_animating(false);
+    }
+    /// Stop where the value is now.
+    pub fn stop(&self) {
+        self.jump_to(self.get());
+    }
+    /// The value now.
+    pub fn get(&self) -> T {
+        self.observable.borrow_observable().clone()
+    }
+    /// Where the value is going.
+    pub fn target(&self) -> T {
+        self.segment.borrow().to.clone()
+    }
+    /// Used from the next [set_target][Self::set_target] on.
+    pub fn set_duration(&self, duration: f64) {
+        self.duration.set(duration);
+    }
+    /// Used from the next [set_target][Self::set_target] on.
+    pub fn set_easing(&self, easing: Easing) {
+        self.easing.set(easing);
+    }
+    /// Whether the value is moving.
+    pub fn is_animating(&self) -> impl ObservableAs<bool> + '_ {
+        self.animating.as_observable()
+    }
+    /// Move the value to where it should be at `now` (in milliseconds).
+    ///
+    /// The first step after [set_target][Self::set_target] marks the start of the animation.
+    pub fn step(&self, now: f64) {
+        if !*self.animating.as_observable().borrow_observable() {
+            return;
+        }
+        let mut segment = self.segment.borrow_mut();
+        let start = *segment.start.get_or_insert(now);
+        let duration = self.duration.get();
+        let t = match duration > 0.0 {
+            true => ((now - start) / duration).clamp(0.0, 1.0),
+            false => 1.0,
+        };
+        if t < 1.0 {
+            self.value
+                .set(segment.from.lerp(&segment.to, (self.easing.get())(t)));
+        } else {
+            self.value.set(segment.to.clone());
+            drop(segment);
+            self.set_animating(false);
+        }
+    }
+    /// Step on each frame of `clock` while animating. Never completes.
+    pub async fn run(&self, clock: &dyn Clock) {
+        drive(&self.animating, clock, |now| self.step(now)).await
+    }
+    fn set_animating(&self, animating: bool) {
+        if *self.animating.as_observable().borrow_observable() != animating {
+            self.animating.set(animating);
+        }
+    }
+}
+
+impl<T: Animatable> Listenable for Tween<T> {
+    fn add_waker(&self, waker: Waker) {
+        self.observable.add_waker(waker)
+    }
+    fn get_version(&self) -> Version {
+        self.observable.get_version()
+    }
+}
+impl<T: Animatable> Observable for Tween<T> {
+    type Data = T;
+    fn borrow_observable<'b>(&'b self) -> ObservableBorrow<'b, T> {
+        self.observable.borrow_observable()
+    }
+}
@@ -1,4 +1,4 @@
-use std::{future::Future, rc::Rc};
+use std::{future::Future, pin::Pin, rc::Rc, time::Duration};
 
 use scoped_tls::ScopedKey;
 
@@ -14,5 +14,7 @@ pub trait BackendTrait: 'static + Sized {
     fn del_child_node(parent: &mut Self::Node, child: &mut Self::Node);
     fn drive_executor<F: Future<Output = ()> + 'static>(fut: F);
     fn initialize();
+    /// A future that completes after `duration`, woken by the backend's timers.
+    fn sleep(duration: Duration) -> Pin<Box<dyn Future<Output = ()>>>;
     fn get_vnode_key() -> &'static ScopedKey<Rc<VNode<Self>>>;
 }
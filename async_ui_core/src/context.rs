@@ -1,10 +1,106 @@
 use std::{
     any::{Any, TypeId},
+    marker::PhantomData,
     rc::Rc,
+    task::Waker,
 };
 
 use im_rc::HashMap;
+use observables::{
+    cell::{ReactiveCell, ReactiveCellObservable},
+    Listenable, Observable, ObservableAs, ObservableAsExt, ObservableBorrow, Version,
+};
+use smallvec::SmallVec;
 #[derive(Clone, Default)]
 pub struct ContextMap {
     pub(crate) inner: HashMap<TypeId, Rc<dyn Any>>,
 }
+impl ContextMap {
+    pub(crate) fn extend(&self, values: Contexts) -> Self {
+        let mut inner = self.inner.clone();
+        inner.extend(values.0);
+        Self { inner }
+    }
+    pub(crate) fn get<T: 'static>(&self) -> Option<Rc<T>> {
+        let entry = self.inner.get(&TypeId::of::<T>())?.to_owned();
+        entry.downcast::<T>().ok()
+    }
+}
+
+/// Several context values to provide together, without nesting one `with_context` per value.
+#[derive(Default)]
+pub struct Contexts(SmallVec<[(TypeId, Rc<dyn Any>); 2]>);
+impl Contexts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Add `value`. If a value of the same type was already added, it is replaced.
+    pub fn with<T: 'static>(mut self, value: Rc<T>) -> Self {
+        self.0.push((TypeId::of::<T>(), value));
+        self
+    }
+}
+
+/// A context value that its provider can swap out.
+///
+/// Provide an `Rc<ContextCell<T>>` as the context. Consumers look up `ContextCell<T>`,
+/// read it with [get][Self::get], and wait for the provider's next [swap][Self::swap]
+/// with [until_change][Self::until_change].
+pub struct ContextCell<T> {
+    value: ReactiveCell<Rc<T>>,
+}
+impl<T> ContextCell<T> {
+    pub fn new(value: Rc<T>) -> Self {
+        Self {
+            value: ReactiveCell::new(value),
+        }
+    }
+    pub fn get(&self) -> Rc<T> {
+        self.value.as_observable().borrow_observable().clone()
+    }
+    /// Replace the value, waking everyone waiting in [until_change][Self::until_change].
+    pub fn swap(&self, value: Rc<T>) {
+        self.value.set(value);
+    }
+    pub async fn until_change(&self) {
+        let observable = self.value.as_observable();
+        ObservableAsExt::<Rc<T>>::until_change(&observable).await
+    }
+    pub fn as_observable(&self) -> ReactiveCellObservable<Rc<T>, &ReactiveCell<Rc<T>>> {
+        self.value.as_observable()
+    }
+}
+
+/// A context value that follows an observable of its provider.
+///
+/// Consumers can [borrow_observable_as][ObservableAs::borrow_observable_as] it
+/// and wait for the provider's next change with [until_change][ObservableAsExt::until_change].
+///
+/// Contexts are looked up by type, so providers of the same `T` shadow each other.
+/// `K` is a key type to tell them apart: a component can key its context with a private type
+/// so that nothing else can provide or read it.
+pub struct ObservableContext<T: ToOwned + ?Sized + 'static, K: 'static = ()> {
+    observable: ReactiveCellObservable<T::Owned, Rc<ReactiveCell<T::Owned>>>,
+    _key: PhantomData<fn() -> K>,
+}
+impl<T: ToOwned + ?Sized + 'static, K: 'static> ObservableContext<T, K> {
+    pub(crate) fn new(cell: &Rc<ReactiveCell<T::Owned>>) -> Self {
+        Self {
+            observable: cell.as_observable_rc(),
+            _key: PhantomData,
+        }
+    }
+}
+impl<T: ToOwned + ?Sized + 'static, K: 'static> Listenable for ObservableContext<T, K> {
+    fn add_waker(&self, waker: Waker) {
+        self.observable.add_waker(waker)
+    }
+    fn get_version(&self) -> Version {
+        self.observable.get_version()
+    }
+}
+impl<T: ToOwned + ?Sized + 'static, K: 'static> ObservableAs<T> for ObservableContext<T, K> {
+    fn borrow_observable_as<'b>(&'b self) -> ObservableBorrow<'b, T> {
+        ObservableAs::<T>::borrow_observable_as(&self.observable)
+    }
+}
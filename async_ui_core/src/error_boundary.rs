@@ -0,0 +1,202 @@
+//! Replacing a failed part of the UI with a fallback; see [ErrorBoundary].
+//!
+//! Returned errors are always caught. Panics are caught only where they unwind:
+//! on `wasm32-unknown-unknown`, which aborts on panic, a panicking component takes down the whole app.
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    fmt,
+    future::{Future, IntoFuture},
+    panic::{catch_unwind, AssertUnwindSafe},
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+use pin_project_lite::pin_project;
+
+use crate::{
+    backend::BackendTrait,
+    vnode::{node_context::WithContext, VNode, VNodeTrait},
+};
+
+/// Why the children of an [ErrorBoundary] failed.
+pub enum ComponentError {
+    /// A component panicked. This holds the panic payload.
+    Panic(Box<dyn Any + Send>),
+    /// A component returned `Err`.
+    Returned(Box<dyn std::error::Error>),
+}
+impl fmt::Display for ComponentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComponentError::Panic(payload) => {
+                if let Some(message) = payload.downcast_ref::<&str>() {
+                    write!(f, "component panicked: {message}")
+                } else if let Some(message) = payload.downcast_ref::<String>() {
+                    write!(f, "component panicked: {message}")
+                } else {
+                    f.write_str("component panicked")
+                }
+            }
+            ComponentError::Returned(error) => fmt::Display::fmt(error, f),
+        }
+    }
+}
+impl fmt::Debug for ComponentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Output of a component that can be put in an [ErrorBoundary].
+pub trait ComponentResult {
+    fn into_result(self) -> Result<(), ComponentError>;
+}
+impl ComponentResult for () {
+    fn into_result(self) -> Result<(), ComponentError> {
+        Ok(())
+    }
+}
+impl<E: Into<Box<dyn std::error::Error>>> ComponentResult for Result<(), E> {
+    fn into_result(self) -> Result<(), ComponentError> {
+        self.map_err(|e| ComponentError::Returned(e.into()))
+    }
+}
+
+/// Put in the context of everything inside a boundary, so that tasks spawned in there can report their panics.
+pub(crate) struct BoundaryHandle {
+    error: RefCell<Option<ComponentError>>,
+    waker: RefCell<Option<Waker>>,
+}
+impl BoundaryHandle {
+    fn report(&self, error: ComponentError) {
+        let mut slot = self.error.borrow_mut();
+        // Only the first error is shown; the rest come from the same failing subtree.
+        if slot.is_none() {
+            *slot = Some(error);
+            if let Some(waker) = self.waker.borrow().as_ref() {
+                waker.wake_by_ref();
+            }
+        }
+    }
+}
+
+/// The [ErrorBoundary] `vnode` is inside, if any.
+///
+/// Look it up once, when the vnode is created, and pass it to [poll_in_vnode].
+pub(crate) fn boundary_of<B: BackendTrait>(vnode: &VNode<B>) -> Option<Rc<BoundaryHandle>> {
+    vnode
+        .get_context_map()
+        .inner
+        .get(&TypeId::of::<BoundaryHandle>())
+        .cloned()
+        .and_then(|handle| handle.downcast::<BoundaryHandle>().ok())
+}
+
+/// Poll a component in `vnode`, catching its panic if it is inside the [ErrorBoundary] of `boundary`.
+///
+/// `panicked` is set when the component panics. From then on it is never polled again,
+/// even if a waker it registered before panicking fires before the boundary drops it.
+pub(crate) fn poll_in_vnode<B: BackendTrait, T>(
+    vnode: &Rc<VNode<B>>,
+    boundary: Option<&BoundaryHandle>,
+    panicked: &mut bool,
+    poll: impl FnOnce() -> Poll<T>,
+) -> Poll<T> {
+    if *panicked {
+        return Poll::Pending;
+    }
+    B::get_vnode_key().set(vnode, || match boundary {
+        None => poll(),
+        Some(boundary) => match catch_unwind(AssertUnwindSafe(poll)) {
+            Ok(res) => res,
+            Err(payload) => {
+                *panicked = true;
+                boundary.report(ComponentError::Panic(payload));
+                Poll::Pending
+            }
+        },
+    })
+}
+
+pin_project! {
+    /// Renders `children`, or `fallback` if any of them panics or the children return an error.
+    ///
+    /// Panics can only be caught when the crate is built with `panic = "unwind"`.
+    /// On targets that abort on panic, such as `wasm32-unknown-unknown`, only returned errors are caught.
+    pub struct ErrorBoundary<B, F, G, FB>
+    where
+        B: BackendTrait,
+        F: Future,
+        G: IntoFuture<Output = ()>,
+        FB: FnOnce(ComponentError) -> G,
+    {
+        #[pin]
+        children: Option<WithContext<B, F>>,
+        #[pin]
+        fallback_future: Option<G::IntoFuture>,
+        fallback: Option<FB>,
+        handle: Rc<BoundaryHandle>,
+    }
+}
+
+impl<B, F, G, FB> ErrorBoundary<B, F, G, FB>
+where
+    B: BackendTrait,
+    F: Future,
+    F::Output: ComponentResult,
+    G: IntoFuture<Output = ()>,
+    FB: FnOnce(ComponentError) -> G,
+{
+    pub fn new<I: IntoFuture<IntoFuture = F>>(children: I, fallback: FB) -> Self {
+        let handle = Rc::new(BoundaryHandle {
+            error: RefCell::new(None),
+            waker: RefCell::new(None),
+        });
+        Self {
+            children: Some(WithContext::new(children, handle.clone())),
+            fallback_future: None,
+            fallback: Some(fallback),
+            handle,
+        }
+    }
+}
+
+impl<B, F, G, FB> Future for ErrorBoundary<B, F, G, FB>
+where
+    B: BackendTrait,
+    F: Future,
+    F::Output: ComponentResult,
+    G: IntoFuture<Output = ()>,
+    FB: FnOnce(ComponentError) -> G,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        if let Some(children) = this.children.as_mut().as_pin_mut() {
+            *this.handle.waker.borrow_mut() = Some(cx.waker().to_owned());
+            let error = match catch_unwind(AssertUnwindSafe(|| children.poll(cx))) {
+                Ok(Poll::Ready(output)) => match output.into_result() {
+                    Ok(()) => return Poll::Ready(()),
+                    Err(error) => error,
+                },
+                Ok(Poll::Pending) => match this.handle.error.borrow_mut().take() {
+                    Some(error) => error,
+                    None => return Poll::Pending,
+                },
+                Err(payload) => ComponentError::Panic(payload),
+            };
+            // Dropping the children removes their nodes.
+            this.children.set(None);
+            let fallback = this.fallback.take().expect("fallback already used");
+            this.fallback_future
+                .set(Some(fallback(error).into_future()));
+        }
+        this.fallback_future
+            .as_pin_mut()
+            .expect("error boundary has no content")
+            .poll(cx)
+    }
+}
@@ -1,22 +1,132 @@
+//! The executor that runs all components, with one lane per [Priority].
+//!
+//! The driving future (see [get_driving_future]) always runs the ready tasks of the most urgent lane first,
+//! so a keystroke handler doesn't wait behind a long list re-rendering.
 use std::{
-    future::{pending, Future},
+    future::Future,
     rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{Context, Wake, Waker},
+    thread::{self, Thread},
+    time::Instant,
 };
 
 use async_executor::{LocalExecutor, Task};
+use futures_lite::{future::yield_now, FutureExt};
+
+use crate::context::ContextMap;
+
+/// How urgently a task should run.
+///
+/// Each [Child][crate::fragment::Child] is spawned with the priority given to it
+/// with [with_priority][crate::fragment::Child::with_priority], or else with the `Priority`
+/// provided as context to its subtree (`with_context(children, Rc::new(Priority::Idle))`),
+/// or else [Normal][Priority::Normal].
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub enum Priority {
+    /// Direct responses to input, like typing and clicking.
+    UserBlocking,
+    #[default]
+    Normal,
+    /// Work that can wait until nothing else is ready, like prefetching or offscreen content.
+    Idle,
+}
+impl Priority {
+    const ALL: [Priority; 3] = [Priority::UserBlocking, Priority::Normal, Priority::Idle];
+    /// The priority provided in `context`, or `Normal`.
+    pub fn of_context(context: &ContextMap) -> Self {
+        context.get::<Priority>().map(|p| *p).unwrap_or_default()
+    }
+}
+
+/// How many tasks are run before the driving future returns to the backend's event loop.
+const TICKS_PER_YIELD: u32 = 64;
+
 thread_local! {
-    pub(crate) static EXECUTOR: Rc<LocalExecutor<'static>> = {
-        let exe= LocalExecutor::new();
-        Rc::new(exe)
+    pub(crate) static EXECUTOR: Rc<[LocalExecutor<'static>; 3]> = {
+        Rc::new(Priority::ALL.map(|_| LocalExecutor::new()))
     }
 }
+
+/// Spawn with [Normal][Priority::Normal] priority.
 pub fn spawn_local<F: Future + 'static>(fut: F) -> Task<F::Output> {
-    EXECUTOR.with(|exe| exe.spawn(fut))
+    spawn_local_with_priority(Priority::Normal, fut)
+}
+
+pub fn spawn_local_with_priority<F: Future + 'static>(
+    priority: Priority,
+    fut: F,
+) -> Task<F::Output> {
+    EXECUTOR.with(|exe| exe[priority as usize].spawn(fut))
+}
+
+/// Spawn with the priority of the subtree `context` belongs to.
+pub fn spawn_local_in<F: Future + 'static>(context: &ContextMap, fut: F) -> Task<F::Output> {
+    spawn_local_with_priority(Priority::of_context(context), fut)
 }
 
 pub(crate) fn get_driving_future() -> impl Future<Output = ()> + 'static {
     EXECUTOR.with(|exe| {
-        let exe_cpy = exe.clone();
-        async move { exe_cpy.run(pending()).await }
+        let lanes = exe.clone();
+        async move {
+            let mut ticks = 0;
+            loop {
+                // Run one task from the most urgent lane that has one ready.
+                if lanes.iter().any(LocalExecutor::try_tick) {
+                    ticks += 1;
+                    if ticks == TICKS_PER_YIELD {
+                        ticks = 0;
+                        yield_now().await;
+                    }
+                    continue;
+                }
+                ticks = 0;
+                // Nothing is ready. Run whichever task wakes first.
+                let [user_blocking, normal, idle] = &*lanes;
+                user_blocking.tick().or(normal.tick()).or(idle.tick()).await;
+            }
+        }
     })
 }
+
+/// Wakes a thread parked in [run_on_this_thread].
+struct ThreadWaker {
+    woken: AtomicBool,
+    thread: Thread,
+}
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref()
+    }
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.store(true, Ordering::SeqCst);
+        self.thread.unpark();
+    }
+}
+
+/// Run the spawned tasks on this thread, for rendering without an event loop.
+///
+/// Runs until no task is ready. Then, until `deadline` (if any), parks the thread
+/// while waiting for tasks to be woken (by timers, finished requests, ...) and runs them too.
+pub fn run_on_this_thread(deadline: Option<Instant>) {
+    let driver = get_driving_future();
+    futures_lite::pin!(driver);
+    let thread_waker = Arc::new(ThreadWaker {
+        woken: AtomicBool::new(true),
+        thread: thread::current(),
+    });
+    let waker = Waker::from(thread_waker.clone());
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        while thread_waker.woken.swap(false, Ordering::SeqCst) {
+            let _ = driver.as_mut().poll(&mut cx);
+        }
+        match deadline.and_then(|deadline| deadline.checked_duration_since(Instant::now())) {
+            Some(remaining) => thread::park_timeout(remaining),
+            None => break,
+        }
+    }
+}
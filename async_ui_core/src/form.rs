@@ -0,0 +1,235 @@
+//! Form state: fields with validation, dirty and touched flags, and submission.
+//!
+//! A [Field] reads and writes its value through an observable and a setter,
+//! so it can be backed by a `ReactiveCell` ([Field::from_cell]) or an x-bow `Tracked` ([Field::new]).
+//! A [Form] groups fields, runs their validation as the values change, and submits.
+//! The backends bind fields to their input components.
+use std::{
+    borrow::Borrow,
+    future::{pending, poll_fn, Future},
+    pin::Pin,
+    task::Poll,
+};
+
+use futures_lite::FutureExt;
+use observables::{cell::ReactiveCell, ObservableAs, ObservableAsExt};
+
+type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + 'a>>;
+type Validator<'s, T> = Box<dyn Fn(&T) -> Result<(), String> + 's>;
+type AsyncValidator<'s, V> = Box<dyn Fn(V) -> BoxFuture<'s, Result<(), String>> + 's>;
+
+/// One input of a [Form].
+///
+/// `T` is the type the value is borrowed as (`str` for text), and `T::Owned` the type it is set as.
+pub struct Field<'s, T: ToOwned + ?Sized + 's> {
+    value: Box<dyn ObservableAs<T> + 's>,
+    set_value: Box<dyn Fn(T::Owned) + 's>,
+    validators: Vec<Validator<'s, T>>,
+    async_validators: Vec<AsyncValidator<'s, T::Owned>>,
+    errors: ReactiveCell<Vec<String>>,
+    valid: ReactiveCell<bool>,
+    dirty: ReactiveCell<bool>,
+    touched: ReactiveCell<bool>,
+}
+
+impl<'s, T: ToOwned + ?Sized + 's> Field<'s, T> {
+    /// A field that reads `value` and writes with `set_value`.
+    ///
+    /// For an x-bow store, `Field::new(store.name.as_observable(), |v| *store.name.borrow_mut() = v)`.
+    pub fn new<O, S>(value: O, set_value: S) -> Self
+    where
+        O: ObservableAs<T> + 's,
+        S: Fn(T::Owned) + 's,
+    {
+        Self {
+            value: Box::new(value),
+            set_value: Box::new(set_value),
+            validators: Vec::new(),
+            async_validators: Vec::new(),
+            errors: ReactiveCell::new(Vec::new()),
+            valid: ReactiveCell::new(true),
+            dirty: ReactiveCell::new(false),
+            touched: ReactiveCell::new(false),
+        }
+    }
+    pub fn from_cell(cell: &'s ReactiveCell<T::Owned>) -> Self {
+        Self::new(cell.as_observable(), |v| cell.set(v))
+    }
+    /// Add a check. `Err` holds the message to show.
+    pub fn validate<V: Fn(&T) -> Result<(), String> + 's>(mut self, validator: V) -> Self {
+        self.validators.push(Box::new(validator));
+        self
+    }
+    /// Add a check that needs to wait, like asking a server whether a name is taken.
+    ///
+    /// Async checks run after all the sync checks pass.
+    /// A check still running when the value changes is dropped.
+    pub fn validate_async<V, F>(mut self, validator: V) -> Self
+    where
+        V: Fn(T::Owned) -> F + 's,
+        F: Future<Output = Result<(), String>> + 's,
+    {
+        self.async_validators
+            .push(Box::new(move |value| Box::pin(validator(value))));
+        self
+    }
+    pub fn value(&self) -> &(dyn ObservableAs<T> + 's) {
+        &*self.value
+    }
+    /// Set the value as the user would, making the field dirty.
+    pub fn input(&self, value: T::Owned) {
+        (self.set_value)(value);
+        set_if_changed(&self.dirty, true);
+    }
+    /// Mark that the user has been to this field, usually when it loses focus.
+    pub fn touch(&self) {
+        set_if_changed(&self.touched, true);
+    }
+    /// Make the field pristine again, as if the user never changed or visited it.
+    pub fn reset_state(&self) {
+        set_if_changed(&self.dirty, false);
+        set_if_changed(&self.touched, false);
+    }
+    /// The messages of the failed checks.
+    pub fn errors(&self) -> impl ObservableAs<[String]> + '_ {
+        self.errors.as_observable()
+    }
+    /// Whether all checks passed. This is `false` while async checks are running.
+    pub fn is_valid(&self) -> impl ObservableAs<bool> + '_ {
+        self.valid.as_observable()
+    }
+    /// Whether the user has changed the value.
+    pub fn dirty(&self) -> impl ObservableAs<bool> + '_ {
+        self.dirty.as_observable()
+    }
+    /// Whether the user has been to this field.
+    pub fn touched(&self) -> impl ObservableAs<bool> + '_ {
+        self.touched.as_observable()
+    }
+
+    fn sync_errors(&self, value: &T) -> Vec<String> {
+        self.validators
+            .iter()
+            .filter_map(|validator| validator(value).err())
+            .collect()
+    }
+    async fn async_errors(&self, value: T::Owned) -> Vec<String> {
+        let mut errors = Vec::new();
+        for validator in self.async_validators.iter() {
+            if let Err(error) = validator(value.borrow().to_owned()).await {
+                errors.push(error);
+            }
+        }
+        errors
+    }
+    fn set_errors(&self, errors: Vec<String>, pending: bool) {
+        set_if_changed(&self.valid, errors.is_empty() && !pending);
+        self.errors.set(errors);
+    }
+    async fn check(&self) -> bool {
+        let value = self.value.borrow_observable_as().to_owned();
+        let mut errors = self.sync_errors(value.borrow());
+        if errors.is_empty() && !self.async_validators.is_empty() {
+            self.set_errors(Vec::new(), true);
+            errors = self.async_errors(value).await;
+        }
+        let valid = errors.is_empty();
+        self.set_errors(errors, false);
+        valid
+    }
+}
+
+fn set_if_changed<V: PartialEq + Copy>(cell: &ReactiveCell<V>, value: V) {
+    if *cell.as_observable().borrow_observable_as() != value {
+        cell.set(value);
+    }
+}
+
+/// What a [Form] needs from its fields, whatever their value type.
+pub trait FormField {
+    fn touch(&self);
+    /// Run all checks on the current value now.
+    fn check(&self) -> BoxFuture<'_, bool>;
+    /// Run the checks each time the value changes. Never completes.
+    fn run(&self) -> BoxFuture<'_, ()>;
+}
+impl<'s, T: ToOwned + ?Sized + 's> FormField for Field<'s, T> {
+    fn touch(&self) {
+        Field::touch(self)
+    }
+    fn check(&self) -> BoxFuture<'_, bool> {
+        Box::pin(Field::check(self))
+    }
+    fn run(&self) -> BoxFuture<'_, ()> {
+        Box::pin(async {
+            loop {
+                let check = async {
+                    Field::check(self).await;
+                    pending().await
+                };
+                check.or(self.value.until_change()).await;
+            }
+        })
+    }
+}
+
+/// A group of fields that are submitted together.
+pub struct Form<'f> {
+    fields: Vec<&'f dyn FormField>,
+    submitting: ReactiveCell<bool>,
+}
+impl<'f> Form<'f> {
+    pub fn new(fields: &[&'f dyn FormField]) -> Self {
+        Self {
+            fields: fields.to_vec(),
+            submitting: ReactiveCell::new(false),
+        }
+    }
+    /// Whether a [submit][Self::submit] is in flight. The backends disable the form while it is.
+    pub fn submitting(&self) -> impl ObservableAs<bool> + '_ {
+        self.submitting.as_observable()
+    }
+    /// Check the fields as their values change, so their errors stay up to date. Never completes.
+    ///
+    /// The backends' form components run this.
+    pub async fn run(&self) {
+        let mut loops: Vec<_> = self.fields.iter().map(|field| field.run()).collect();
+        poll_fn(|cx| {
+            for run in loops.iter_mut() {
+                let _ = run.as_mut().poll(cx);
+            }
+            Poll::<()>::Pending
+        })
+        .await
+    }
+    /// Touch and check all fields, then run `action` if they are all valid.
+    ///
+    /// Returns `None` without running `action` if a field is invalid
+    /// or another submit is in flight.
+    pub async fn submit<F: Future>(&self, action: F) -> Option<F::Output> {
+        if *self.submitting.as_observable().borrow_observable_as() {
+            return None;
+        }
+        let mut valid = true;
+        for field in self.fields.iter() {
+            field.touch();
+            valid &= field.check().await;
+        }
+        // Another submit may have started while the validators ran.
+        if !valid || *self.submitting.as_observable().borrow_observable_as() {
+            return None;
+        }
+        self.submitting.set(true);
+        // Reset even if this future is dropped before `action` completes.
+        let _reset = ResetOnDrop(&self.submitting);
+        Some(action.await)
+    }
+}
+
+/// Sets the flag back to `false` when dropped.
+struct ResetOnDrop<'a>(&'a ReactiveCell<bool>);
+impl Drop for ResetOnDrop<'_> {
+    fn drop(&mut self) {
+        self.0.set(false);
+    }
+}
@@ -9,13 +9,19 @@ use async_executor::Task;
 use pin_project_lite::pin_project;
 use scoped_async_spawn::SpawnGuard;
 
-use crate::{backend::BackendTrait, executor::spawn_local, vnode::VNode};
+use crate::{
+    backend::BackendTrait,
+    context::Contexts,
+    error_boundary::{boundary_of, poll_in_vnode, BoundaryHandle},
+    executor::{spawn_local_with_priority, Priority},
+    vnode::{node_context::ContextVNode, VNode, VNodeTrait},
+};
 
 trait ChildInnerTrait<'c, B>: 'c
 where
     B: BackendTrait,
 {
-    fn spawn(&mut self, vnode: Rc<VNode<B>>, guard: Pin<&mut SpawnGuard<'c>>);
+    fn spawn(&mut self, vnode: Rc<VNode<B>>, guard: Pin<&mut SpawnGuard<'c>>, priority: Priority);
 }
 pin_project! {
     struct ElementFuture<B, F>
@@ -25,7 +31,9 @@ pin_project! {
     {
         #[pin]
         future: F,
-        vnode: Rc<VNode<B>>
+        vnode: Rc<VNode<B>>,
+        boundary: Option<Rc<BoundaryHandle>>,
+        panicked: bool,
     }
 }
 impl<B, F> Future for ElementFuture<B, F>
@@ -36,7 +44,9 @@ where
     type Output = ();
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = self.project();
-        B::get_vnode_key().set(&this.vnode, || this.future.poll(cx))
+        poll_in_vnode(this.vnode, this.boundary.as_deref(), this.panicked, || {
+            this.future.poll(cx)
+        })
     }
 }
 impl<'c, B, F> ChildInnerTrait<'c, B> for ChildInner<F>
@@ -45,14 +55,16 @@ where
     B: BackendTrait,
     F: Future<Output = ()>,
 {
-    fn spawn(&mut self, vnode: Rc<VNode<B>>, guard: Pin<&mut SpawnGuard<'c>>) {
+    fn spawn(&mut self, vnode: Rc<VNode<B>>, guard: Pin<&mut SpawnGuard<'c>>, priority: Priority) {
         match std::mem::replace(self, Self::Null) {
             ChildInner::NotMounted { component } => {
                 let fut = guard.convert_future(ElementFuture {
                     future: component,
+                    boundary: boundary_of(&vnode),
+                    panicked: false,
                     vnode,
                 });
-                let task = spawn_local(fut);
+                let task = spawn_local_with_priority(priority, fut);
                 *self = Self::Mounted { _task: task };
             }
             _ => unreachable!(),
@@ -72,6 +84,7 @@ where
     B: BackendTrait,
 {
     inner: Box<dyn ChildInnerTrait<'c, B>>,
+    priority: Option<Priority>,
 }
 
 impl<'c, B> Child<'c, B>
@@ -86,9 +99,29 @@ where
         let component = future.into_future();
         Self {
             inner: Box::new(ChildInner::NotMounted { component }),
+            priority: None,
         }
     }
+    /// Run this child, and the children inside it, at `priority`
+    /// instead of the priority of the enclosing subtree.
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = Some(priority);
+        self
+    }
     pub(super) fn mount(&mut self, vnode: Rc<VNode<B>>, guard: Pin<&mut SpawnGuard<'c>>) {
-        self.inner.spawn(vnode, guard);
+        match self.priority {
+            Some(priority) => {
+                // Provide the priority to the subtree, so the children inside run at it too.
+                let context = vnode
+                    .get_context_map()
+                    .extend(Contexts::new().with(Rc::new(priority)));
+                let vnode = Rc::new(ContextVNode::new(vnode, context).into());
+                self.inner.spawn(vnode, guard, priority);
+            }
+            None => {
+                let priority = Priority::of_context(vnode.get_context_map());
+                self.inner.spawn(vnode, guard, priority);
+            }
+        }
     }
 }
@@ -6,7 +6,7 @@ use std::{
     task::{Context, Poll},
 };
 
-use child::Child;
+pub use child::Child;
 use pin_project_lite::pin_project;
 use scoped_async_spawn::SpawnGuard;
 
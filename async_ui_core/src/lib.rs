@@ -1,10 +1,15 @@
+pub mod animation;
 pub mod backend;
 pub use mount::mount;
 pub mod context;
+pub mod error_boundary;
 pub mod executor;
+pub mod form;
 pub mod fragment;
 pub mod list;
 pub mod mount;
 pub mod position;
+pub mod router;
+pub mod time;
 pub mod vnode;
 pub use fragment::__private_macro_only;
@@ -0,0 +1,483 @@
+use std::{
+    cell::{Cell, Ref, RefCell},
+    cmp::Ordering,
+    marker::PhantomData,
+    task::Waker,
+};
+
+use im_rc::Vector;
+use observables::{Listenable, Observable, ObservableAs, ObservableBorrow, Version};
+
+use super::{Change, Changes, ListModel, ListModelPrivateAPIs};
+
+/// Turns changes of a list into changes of a list derived from it.
+pub trait ListAdaptor<T: Clone> {
+    type Output: Clone;
+    /// Rebuild `output` from the whole upstream content.
+    fn reset(&mut self, items: &Vector<T>, output: &mut ListModel<Self::Output>);
+    fn apply(&mut self, change: &Change<T>, output: &mut ListModel<Self::Output>);
+    /// Run again for the upstream item at `index`, whose result may have changed.
+    fn refresh(&mut self, items: &Vector<T>, index: usize, output: &mut ListModel<Self::Output>) {
+        let change = Change::Splice {
+            remove_range: index..index + 1,
+            replace_with: Vector::unit(items[index].to_owned()),
+        };
+        self.apply(&change, output);
+    }
+    /// Run again for every upstream item.
+    fn refresh_all(&mut self, items: &Vector<T>, output: &mut ListModel<Self::Output>) {
+        for index in 0..items.len() {
+            self.refresh(items, index, output);
+        }
+    }
+}
+
+/// A [ListModel] observable computed from another one.
+///
+/// Upstream changes are translated when the derived list is borrowed (or checked for changes),
+/// so the `list` components can render it like any other list.
+pub struct DerivedList<W, T, A>
+where
+    W: ObservableAs<ListModel<T>>,
+    T: Clone,
+    A: ListAdaptor<T>,
+{
+    wrapped: W,
+    state: RefCell<State<T, A>>,
+    /// Bumped each time the output changes.
+    version: Cell<Version>,
+    /// Woken when the output changes. Upstream changes wake them through `wrapped` too,
+    /// but the output can also change later, in a sync that had to wait or in a refresh.
+    wakers: RefCell<Vec<Waker>>,
+    /// A refresh asked for while the output was borrowed.
+    refresh_pending: Cell<bool>,
+}
+struct State<T: Clone, A: ListAdaptor<T>> {
+    adaptor: A,
+    output: ListModel<A::Output>,
+    /// Our listener id on the upstream model, and the upstream version we have caught up to.
+    synced: Option<(usize, u64)>,
+    _phantom: PhantomData<T>,
+}
+
+impl<W, T, A> DerivedList<W, T, A>
+where
+    W: ObservableAs<ListModel<T>>,
+    T: Clone,
+    A: ListAdaptor<T>,
+{
+    pub fn new(wrapped: W, adaptor: A) -> Self {
+        Self {
+            wrapped,
+            state: RefCell::new(State {
+                adaptor,
+                output: ListModel::new(),
+                synced: None,
+                _phantom: PhantomData,
+            }),
+            version: Cell::new(Version::new()),
+            wakers: RefCell::new(Vec::new()),
+            refresh_pending: Cell::new(false),
+        }
+    }
+    /// Run the adaptor again for the upstream item at `index`.
+    ///
+    /// Call this when something the adaptor's result depends on, other than the item itself, changed.
+    pub fn refresh_item(&self, index: usize) {
+        self.refresh(Some(index))
+    }
+    /// Run the adaptor again for every upstream item. See [refresh_item][Self::refresh_item].
+    pub fn refresh_all(&self) {
+        self.refresh(None)
+    }
+    fn refresh(&self, index: Option<usize>) {
+        let Ok(mut state) = self.state.try_borrow_mut() else {
+            // Indices may be out of date by the time the output can be changed.
+            self.refresh_pending.set(true);
+            self.wake();
+            return;
+        };
+        let mut changed = self.sync_state(&mut state);
+        let State {
+            adaptor, output, ..
+        } = &mut *state;
+        let before = ListModelPrivateAPIs(output).get_version();
+        let upstream = self.wrapped.borrow_observable_as();
+        match index {
+            Some(index) => adaptor.refresh(upstream.underlying_vector(), index, output),
+            None => adaptor.refresh_all(upstream.underlying_vector(), output),
+        }
+        changed |= ListModelPrivateAPIs(output).get_version() != before;
+        drop((upstream, state));
+        if changed {
+            self.bump();
+        }
+    }
+    /// Catch up with upstream, unless the output is borrowed.
+    ///
+    /// A reader that had to see the old output while another one held it is still woken up:
+    /// the output changes in a later sync, when that one checks for changes again.
+    fn sync(&self) {
+        let Ok(mut state) = self.state.try_borrow_mut() else {
+            return;
+        };
+        let changed = self.sync_state(&mut state);
+        drop(state);
+        if changed {
+            self.bump();
+        }
+    }
+    /// Returns whether the output changed.
+    fn sync_state(&self, state: &mut State<T, A>) -> bool {
+        let State {
+            adaptor,
+            output,
+            synced,
+            ..
+        } = state;
+        let before = ListModelPrivateAPIs(output).get_version();
+        let upstream = self.wrapped.borrow_observable_as();
+        let upstream = ListModelPrivateAPIs(&*upstream);
+        let version = upstream.get_version();
+        match synced {
+            None => {
+                adaptor.reset(upstream.0.underlying_vector(), output);
+                *synced = Some((upstream.add_listener(), version));
+            }
+            Some((listener, last_version)) if *last_version != version => {
+                match upstream.changes_since_version(*last_version) {
+                    Changes::Incremental(changes) => {
+                        changes.for_each(|change| adaptor.apply(change, output))
+                    }
+                    Changes::Reset => adaptor.reset(upstream.0.underlying_vector(), output),
+                }
+                *last_version = version;
+                upstream.set_listener_version(*listener, version);
+            }
+            _ => {}
+        }
+        if self.refresh_pending.take() {
+            adaptor.refresh_all(upstream.0.underlying_vector(), output);
+        }
+        ListModelPrivateAPIs(output).get_version() != before
+    }
+    fn bump(&self) {
+        self.version.set(self.version.get().incremented());
+        self.wake();
+    }
+    fn wake(&self) {
+        for waker in self.wakers.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<W, T, A> Drop for DerivedList<W, T, A>
+where
+    W: ObservableAs<ListModel<T>>,
+    T: Clone,
+    A: ListAdaptor<T>,
+{
+    fn drop(&mut self) {
+        if let Some((listener, _)) = self.state.get_mut().synced {
+            ListModelPrivateAPIs(&*self.wrapped.borrow_observable_as()).remove_listener(listener);
+        }
+    }
+}
+
+impl<W, T, A> Listenable for DerivedList<W, T, A>
+where
+    W: ObservableAs<ListModel<T>>,
+    T: Clone,
+    A: ListAdaptor<T>,
+{
+    fn add_waker(&self, waker: Waker) {
+        let mut wakers = self.wakers.borrow_mut();
+        if !wakers.iter().any(|w| w.will_wake(&waker)) {
+            wakers.push(waker.clone());
+        }
+        self.wrapped.add_waker(waker)
+    }
+    fn get_version(&self) -> Version {
+        self.sync();
+        self.version.get()
+    }
+}
+
+impl<W, T, A> Observable for DerivedList<W, T, A>
+where
+    W: ObservableAs<ListModel<T>>,
+    T: Clone,
+    A: ListAdaptor<T>,
+{
+    type Data = ListModel<A::Output>;
+    fn borrow_observable<'b>(&'b self) -> ObservableBorrow<'b, Self::Data> {
+        self.sync();
+        ObservableBorrow::RefCell(Ref::map(self.state.borrow(), |s| &s.output))
+    }
+}
+
+pub trait ListObservableExt<T: Clone>: ObservableAs<ListModel<T>> + Sized {
+    /// The items for which `predicate` returns true.
+    ///
+    /// The predicate is run on items as they are added.
+    /// If it also depends on something else (like a filter setting, or state the item points to),
+    /// call [DerivedList::refresh_all] or [DerivedList::refresh_item] when that changes.
+    fn filtered<P: Fn(&T) -> bool>(self, predicate: P) -> DerivedList<Self, T, Filter<P>> {
+        DerivedList::new(
+            self,
+            Filter {
+                predicate,
+                included: Vec::new(),
+            },
+        )
+    }
+    fn mapped<U: Clone, F: Fn(&T) -> U>(self, mapper: F) -> DerivedList<Self, T, Map<F>> {
+        DerivedList::new(self, Map { mapper })
+    }
+    /// The items, stably sorted by `compare`.
+    fn sorted_by<C: Fn(&T, &T) -> Ordering>(self, compare: C) -> DerivedList<Self, T, SortBy<C>> {
+        DerivedList::new(
+            self,
+            SortBy {
+                compare,
+                origins: Vec::new(),
+            },
+        )
+    }
+}
+impl<T: Clone, O: ObservableAs<ListModel<T>>> ListObservableExt<T> for O {}
+
+pub struct Filter<P> {
+    predicate: P,
+    /// Whether each upstream item passed the predicate.
+    included: Vec<bool>,
+}
+impl<P> Filter<P> {
+    /// Number of included items before upstream index `end`.
+    fn count_before(&self, end: usize) -> usize {
+        self.included[..end].iter().filter(|inc| **inc).count()
+    }
+}
+impl<T: Clone, P: Fn(&T) -> bool> ListAdaptor<T> for Filter<P> {
+    type Output = T;
+    fn reset(&mut self, items: &Vector<T>, output: &mut ListModel<T>) {
+        self.included = items.iter().map(&self.predicate).collect();
+        let kept: Vec<T> = items
+            .iter()
+            .zip(self.included.iter())
+            .filter(|(_, inc)| **inc)
+            .map(|(item, _)| item.to_owned())
+            .collect();
+        output.splice(.., kept.into_iter());
+    }
+    fn apply(&mut self, change: &Change<T>, output: &mut ListModel<T>) {
+        match change {
+            Change::Splice {
+                remove_range,
+                replace_with,
+            } => {
+                let start = self.count_before(remove_range.start);
+                let removed = self.count_before(remove_range.end) - start;
+                let new_included: Vec<bool> = replace_with.iter().map(&self.predicate).collect();
+                let kept: Vec<T> = replace_with
+                    .iter()
+                    .zip(new_included.iter())
+                    .filter(|(_, inc)| **inc)
+                    .map(|(item, _)| item.to_owned())
+                    .collect();
+                self.included.splice(remove_range.clone(), new_included);
+                if removed > 0 || !kept.is_empty() {
+                    output.splice(start..start + removed, kept.into_iter());
+                }
+            }
+            Change::Remove { index } => {
+                if self.included.remove(*index) {
+                    output.remove(self.count_before(*index));
+                }
+            }
+            Change::Insert { index, value } => {
+                let inc = (self.predicate)(value);
+                self.included.insert(*index, inc);
+                if inc {
+                    output.insert(self.count_before(*index), value.to_owned());
+                }
+            }
+            Change::Move { from, to } => {
+                let down_from = self.count_before(*from);
+                let inc = self.included.remove(*from);
+                self.included.insert(*to, inc);
+                let down_to = self.count_before(*to);
+                if inc && down_from != down_to {
+                    output.move_item(down_from, down_to);
+                }
+            }
+        }
+    }
+    fn refresh(&mut self, items: &Vector<T>, index: usize, output: &mut ListModel<T>) {
+        let included = (self.predicate)(&items[index]);
+        if included != self.included[index] {
+            let position = self.count_before(index);
+            self.included[index] = included;
+            if included {
+                output.insert(position, items[index].to_owned());
+            } else {
+                output.remove(position);
+            }
+        }
+    }
+    fn refresh_all(&mut self, items: &Vector<T>, output: &mut ListModel<T>) {
+        let mut position = 0;
+        for (item, was_included) in items.iter().zip(self.included.iter_mut()) {
+            let included = (self.predicate)(item);
+            match (*was_included, included) {
+                (true, true) => position += 1,
+                (true, false) => output.remove(position),
+                (false, true) => {
+                    output.insert(position, item.to_owned());
+                    position += 1;
+                }
+                (false, false) => {}
+            }
+            *was_included = included;
+        }
+    }
+}
+
+pub struct Map<F> {
+    mapper: F,
+}
+impl<T: Clone, U: Clone, F: Fn(&T) -> U> ListAdaptor<T> for Map<F> {
+    type Output = U;
+    fn reset(&mut self, items: &Vector<T>, output: &mut ListModel<U>) {
+        output.splice(.., items.iter().map(&self.mapper));
+    }
+    fn apply(&mut self, change: &Change<T>, output: &mut ListModel<U>) {
+        match change {
+            Change::Splice {
+                remove_range,
+                replace_with,
+            } => output.splice(remove_range.clone(), replace_with.iter().map(&self.mapper)),
+            Change::Remove { index } => output.remove(*index),
+            Change::Insert { index, value } => output.insert(*index, (self.mapper)(value)),
+            Change::Move { from, to } => output.move_item(*from, *to),
+        }
+    }
+}
+
+pub struct SortBy<C> {
+    compare: C,
+    /// The upstream index of each output item.
+    origins: Vec<usize>,
+}
+impl<C> SortBy<C> {
+    /// Where an item that is at upstream index `origin` belongs in `output`,
+    /// ignoring the output item at index `skip`.
+    fn position<T: Clone>(
+        &self,
+        output: &ListModel<T>,
+        value: &T,
+        origin: usize,
+        skip: Option<usize>,
+    ) -> usize
+    where
+        C: Fn(&T, &T) -> Ordering,
+    {
+        let items = output.underlying_vector();
+        let real_index = |i: usize| match skip {
+            Some(skip) if i >= skip => i + 1,
+            _ => i,
+        };
+        let (mut low, mut high) = (0, items.len() - skip.map_or(0, |_| 1));
+        while low < high {
+            let mid = (low + high) / 2;
+            let r = real_index(mid);
+            // Ties are broken by upstream order, which makes the sort stable.
+            let ord = (self.compare)(&items[r], value).then(self.origins[r].cmp(&origin));
+            if ord == Ordering::Less {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
+    fn insert<T: Clone>(&mut self, index: usize, value: &T, output: &mut ListModel<T>)
+    where
+        C: Fn(&T, &T) -> Ordering,
+    {
+        self.origins
+            .iter_mut()
+            .filter(|o| **o >= index)
+            .for_each(|o| *o += 1);
+        let position = self.position(output, value, index, None);
+        self.origins.insert(position, index);
+        output.insert(position, value.to_owned());
+    }
+    fn remove<T: Clone>(&mut self, index: usize, output: &mut ListModel<T>) {
+        let position = self
+            .origins
+            .iter()
+            .position(|o| *o == index)
+            .expect("sorted item not found");
+        self.origins.remove(position);
+        output.remove(position);
+        self.origins
+            .iter_mut()
+            .filter(|o| **o > index)
+            .for_each(|o| *o -= 1);
+    }
+}
+impl<T: Clone, C: Fn(&T, &T) -> Ordering> ListAdaptor<T> for SortBy<C> {
+    type Output = T;
+    fn reset(&mut self, items: &Vector<T>, output: &mut ListModel<T>) {
+        self.origins = (0..items.len()).collect();
+        self.origins
+            .sort_by(|a, b| (self.compare)(&items[*a], &items[*b]));
+        output.splice(.., self.origins.iter().map(|i| items[*i].to_owned()));
+    }
+    fn apply(&mut self, change: &Change<T>, output: &mut ListModel<T>) {
+        match change {
+            Change::Splice {
+                remove_range,
+                replace_with,
+            } => {
+                for index in remove_range.clone().rev() {
+                    self.remove(index, output);
+                }
+                for (offset, value) in replace_with.iter().enumerate() {
+                    self.insert(remove_range.start + offset, value, output);
+                }
+            }
+            Change::Remove { index } => self.remove(*index, output),
+            Change::Insert { index, value } => self.insert(*index, value, output),
+            Change::Move { from, to } => {
+                let (from, to) = (*from, *to);
+                let old_position = self
+                    .origins
+                    .iter()
+                    .position(|o| *o == from)
+                    .expect("sorted item not found");
+                self.origins.iter_mut().for_each(|o| {
+                    *o = if *o == from {
+                        to
+                    } else {
+                        let o = if *o > from { *o - 1 } else { *o };
+                        if o >= to {
+                            o + 1
+                        } else {
+                            o
+                        }
+                    }
+                });
+                let value = output.underlying_vector()[old_position].to_owned();
+                let new_position = self.position(output, &value, to, Some(old_position));
+                self.origins.remove(old_position);
+                self.origins.insert(new_position, to);
+                if old_position != new_position {
+                    output.move_item(old_position, new_position);
+                }
+            }
+        }
+    }
+}
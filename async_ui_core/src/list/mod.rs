@@ -1,10 +1,15 @@
 use std::{
-    cell::Cell,
-    collections::VecDeque,
+    cell::RefCell,
+    collections::{vec_deque, HashMap, VecDeque},
+    hash::Hash,
     ops::{Bound, Range, RangeBounds},
 };
 
 use im_rc::Vector;
+use slab::Slab;
+
+mod adaptors;
+pub use adaptors::{DerivedList, Filter, ListAdaptor, ListObservableExt, Map, SortBy};
 
 #[derive(Clone)]
 pub enum Change<T> {
@@ -19,13 +24,24 @@ pub enum Change<T> {
         index: usize,
         value: T,
     },
+    /// Take the item at `from` out, then put it back so that it ends up at index `to`.
+    Move {
+        from: usize,
+        to: usize,
+    },
 }
+pub const DEFAULT_LOG_CAPACITY: usize = 128;
+
 pub struct ListModel<T: Clone> {
     head: Vector<T>,
+    /// A number for each item in `head`, kept while the item stays in the list.
+    ids: Vector<u64>,
+    next_id: u64,
     log: VecDeque<Change<T>>,
     log_start_version: u64,
-    total_listeners: Cell<usize>,
-    pending_listeners: Cell<usize>,
+    log_capacity: usize,
+    /// The version each listener has caught up to.
+    listeners: RefCell<Slab<u64>>,
 }
 
 // taken from https://users.rust-lang.org/t/whats-the-best-way-to-convert-from-rangebounds-to-range/31607
@@ -64,6 +80,10 @@ fn apply_change<T: Clone>(vector: &mut Vector<T>, change: Change<T>) {
         Change::Insert { index, value } => {
             vector.insert(index, value);
         }
+        Change::Move { from, to } => {
+            let value = vector.remove(from);
+            vector.insert(to, value);
+        }
     }
 }
 impl<T: Clone> ListModel<T> {
@@ -71,30 +91,71 @@ impl<T: Clone> ListModel<T> {
         Self::from_iter([].into_iter())
     }
     pub fn from_iter<I: Iterator<Item = T>>(iter: I) -> Self {
+        let head: Vector<T> = iter.collect();
         Self {
-            head: iter.collect(),
+            ids: (0..head.len() as u64).collect(),
+            next_id: head.len() as u64,
+            head,
             log: VecDeque::new(),
             log_start_version: 0,
-            total_listeners: Cell::new(0),
-            pending_listeners: Cell::new(0),
+            log_capacity: DEFAULT_LOG_CAPACITY,
+            listeners: RefCell::new(Slab::new()),
         }
     }
+    /// Set how many changes are kept for listeners that haven't caught up.
+    ///
+    /// Listeners that fall further behind than this get [Changes::Reset].
+    pub fn set_log_capacity(&mut self, capacity: usize) {
+        self.log_capacity = capacity;
+        self.trim_log(capacity);
+    }
+    fn trim_log(&mut self, capacity: usize) {
+        let oldest_needed = self.listeners.get_mut().iter().map(|(_, v)| *v).min();
+        let current_version = self.log_start_version + self.log.len() as u64;
+        let needed = (current_version - oldest_needed.unwrap_or(current_version)) as usize;
+        let excess = self.log.len() - needed.min(capacity).min(self.log.len());
+        self.log.drain(..excess);
+        self.log_start_version += excess as u64;
+    }
     fn change(&mut self, change: Change<T>) {
-        let total_listeners = self.total_listeners.get();
-        if total_listeners == 0 {
+        self.change_ids(&change);
+        if self.listeners.get_mut().is_empty() {
             apply_change(&mut self.head, change);
-            self.log_start_version += self.log.len() as u64;
-            self.log.clear();
+            self.trim_log(0);
+            // Nobody needs the change, but the version still counts it.
+            self.log_start_version += 1;
         } else {
             apply_change(&mut self.head, change.clone());
-            if self.pending_listeners.get() == 0 {
-                self.log_start_version += self.log.len() as u64;
-                self.log.clear();
-            }
             self.log.push_back(change);
-            self.pending_listeners.set(total_listeners);
+            self.trim_log(self.log_capacity);
         }
     }
+    fn change_ids(&mut self, change: &Change<T>) {
+        let next_id = &mut self.next_id;
+        let mut new_id = || {
+            *next_id += 1;
+            *next_id - 1
+        };
+        let change = match change {
+            Change::Splice {
+                remove_range,
+                replace_with,
+            } => Change::Splice {
+                remove_range: remove_range.clone(),
+                replace_with: replace_with.iter().map(|_| new_id()).collect(),
+            },
+            Change::Remove { index } => Change::Remove { index: *index },
+            Change::Insert { index, .. } => Change::Insert {
+                index: *index,
+                value: new_id(),
+            },
+            Change::Move { from, to } => Change::Move {
+                from: *from,
+                to: *to,
+            },
+        };
+        apply_change(&mut self.ids, change);
+    }
     pub fn insert(&mut self, index: usize, value: T) {
         self.change(Change::Insert { index, value })
     }
@@ -116,6 +177,10 @@ impl<T: Clone> ListModel<T> {
             replace_with: replace_with.collect(),
         })
     }
+    /// Move the item at `from` so that it ends up at index `to`.
+    pub fn move_item(&mut self, from: usize, to: usize) {
+        self.change(Change::Move { from, to })
+    }
     pub fn push(&mut self, value: T) {
         self.change(Change::Insert {
             index: self.head.len(),
@@ -127,6 +192,45 @@ impl<T: Clone> ListModel<T> {
             index: self.head.len() - 1,
         });
     }
+    /// Replace the content with `new_items`, matching old and new items by `key_fn`.
+    ///
+    /// Instead of one big splice, this records the removals, [Change::Move]s, and insertions needed,
+    /// moving as few items as possible.
+    /// Items with the same key are treated as the same item, so lists keep what they rendered for it.
+    /// If its value changed, it is replaced with a one-item [Change::Splice] so that lists render it again.
+    pub fn set_keyed<K: Hash + Eq, I: IntoIterator<Item = T>, F: Fn(&T) -> K>(
+        &mut self,
+        new_items: I,
+        key_fn: F,
+    ) where
+        T: PartialEq,
+    {
+        let new_items: Vec<T> = new_items.into_iter().collect();
+        let new_keys: Vec<K> = new_items.iter().map(&key_fn).collect();
+        let steps = keyed_steps(self.head.iter().map(&key_fn), &new_keys);
+        let mut kept = vec![true; new_items.len()];
+        for step in steps {
+            match step {
+                KeyedStep::Remove(index) => self.change(Change::Remove { index }),
+                KeyedStep::Move { from, to } => self.change(Change::Move { from, to }),
+                KeyedStep::Insert { index, new_index } => {
+                    kept[new_index] = false;
+                    self.change(Change::Insert {
+                        index,
+                        value: new_items[new_index].to_owned(),
+                    });
+                }
+            }
+        }
+        for (index, value) in new_items.into_iter().enumerate() {
+            if kept[index] && self.head[index] != value {
+                self.change(Change::Splice {
+                    remove_range: index..index + 1,
+                    replace_with: Vector::unit(value),
+                });
+            }
+        }
+    }
     pub fn len(&self) -> usize {
         self.head.len()
     }
@@ -135,23 +239,213 @@ impl<T: Clone> ListModel<T> {
     }
 }
 
+/// One step of turning the old items into the new ones, see [keyed_steps].
+enum KeyedStep {
+    Remove(usize),
+    Move {
+        from: usize,
+        to: usize,
+    },
+    /// Put the new item at `new_index` at `index`.
+    Insert {
+        index: usize,
+        new_index: usize,
+    },
+}
+
+/// The removals, moves, and insertions that turn items with keys `old` into items with keys `new`.
+///
+/// Old items whose key is gone (or repeated) are removed first.
+/// The longest run of remaining items already in the new order stays where it is.
+/// Then, in the new order, every other item is moved or inserted right after the item before it.
+fn keyed_steps<K: Hash + Eq>(old: impl Iterator<Item = K>, new: &[K]) -> Vec<KeyedStep> {
+    let mut new_index: HashMap<&K, usize> = HashMap::with_capacity(new.len());
+    for (i, k) in new.iter().enumerate() {
+        new_index.entry(k).or_insert(i);
+    }
+
+    // Positions (in `new`) of the old items, after removing those that are gone.
+    let mut current: Vec<usize> = Vec::new();
+    let mut taken = vec![false; new.len()];
+    let mut removed = Vec::new();
+    for (index, key) in old.enumerate() {
+        match new_index.get(&key) {
+            Some(&i) if !taken[i] => {
+                taken[i] = true;
+                current.push(i);
+            }
+            _ => removed.push(index),
+        }
+    }
+    let mut steps: Vec<KeyedStep> = removed.into_iter().rev().map(KeyedStep::Remove).collect();
+
+    let mut stable = vec![false; new.len()];
+    for i in longest_increasing_subsequence(&current) {
+        stable[i] = true;
+    }
+
+    // Lay out slots: one for where each remaining item is now, one for where each moved or
+    // inserted item goes. Slot 0 comes before everything; `next` links the slots in order.
+    const END: usize = usize::MAX;
+    let mut next: Vec<usize> = (1..=current.len()).chain([END]).collect();
+    let mut slot_of = vec![END; new.len()];
+    for (slot, &i) in current.iter().enumerate() {
+        slot_of[i] = slot + 1;
+    }
+    // (new index, slot it was in, if any, slot it goes to)
+    let mut placed = Vec::new();
+    for i in (0..new.len()).filter(|&i| !stable[i]) {
+        let after = match i {
+            0 => 0,
+            _ => slot_of[i - 1],
+        };
+        let slot = next.len();
+        next.push(next[after]);
+        next[after] = slot;
+        placed.push((i, Some(slot_of[i]).filter(|&s| s != END), slot));
+        slot_of[i] = slot;
+    }
+    let mut rank = vec![0; next.len()];
+    let (mut slot, mut r) = (0, 0);
+    while slot != END {
+        rank[slot] = r;
+        r += 1;
+        slot = next[slot];
+    }
+
+    // Count the occupied slots before an item to find its index.
+    let mut occupied = Fenwick::new(next.len());
+    for &r in &rank[1..=current.len()] {
+        occupied.add(r, true);
+    }
+    for (new_index, from_slot, to_slot) in placed {
+        match from_slot {
+            Some(from_slot) => {
+                let from = occupied.count_before(rank[from_slot]);
+                occupied.add(rank[from_slot], false);
+                let to = occupied.count_before(rank[to_slot]);
+                occupied.add(rank[to_slot], true);
+                if from != to {
+                    steps.push(KeyedStep::Move { from, to });
+                }
+            }
+            None => {
+                let index = occupied.count_before(rank[to_slot]);
+                occupied.add(rank[to_slot], true);
+                steps.push(KeyedStep::Insert { index, new_index });
+            }
+        }
+    }
+    steps
+}
+
+/// Fenwick tree counting occupied positions.
+struct Fenwick(Vec<usize>);
+impl Fenwick {
+    fn new(len: usize) -> Self {
+        Self(vec![0; len + 1])
+    }
+    fn add(&mut self, at: usize, occupied: bool) {
+        let mut i = at + 1;
+        while i < self.0.len() {
+            if occupied {
+                self.0[i] += 1;
+            } else {
+                self.0[i] -= 1;
+            }
+            i += i & i.wrapping_neg();
+        }
+    }
+    fn count_before(&self, at: usize) -> usize {
+        let (mut i, mut count) = (at, 0);
+        while i > 0 {
+            count += self.0[i];
+            i -= i & i.wrapping_neg();
+        }
+        count
+    }
+}
+
+/// Elements of `seq` forming a longest strictly increasing subsequence.
+fn longest_increasing_subsequence(seq: &[usize]) -> Vec<usize> {
+    // tails[l] is the index in `seq` of the smallest tail of any increasing run of length l + 1.
+    let mut tails: Vec<usize> = Vec::new();
+    let mut prev: Vec<Option<usize>> = Vec::with_capacity(seq.len());
+    for (i, &x) in seq.iter().enumerate() {
+        let l = tails.partition_point(|&t| seq[t] < x);
+        prev.push(l.checked_sub(1).map(|p| tails[p]));
+        if l == tails.len() {
+            tails.push(i);
+        } else {
+            tails[l] = i;
+        }
+    }
+    let mut result = Vec::with_capacity(tails.len());
+    let mut cursor = tails.last().copied();
+    while let Some(i) = cursor {
+        result.push(seq[i]);
+        cursor = prev[i];
+    }
+    result.reverse();
+    result
+}
+
 pub struct ListModelPrivateAPIs<'l, T: Clone>(pub &'l ListModel<T>);
 
 impl<'l, T: Clone> ListModelPrivateAPIs<'l, T> {
     pub fn get_version(&self) -> u64 {
         self.0.log_start_version + self.0.log.len() as u64
     }
-    pub fn changes_since_version(
-        &self,
-        version: u64,
-    ) -> std::collections::vec_deque::Iter<'_, Change<T>> {
-        let min = (version - self.0.log_start_version) as usize;
-        self.0.log.range(min..)
+    pub fn changes_since_version(&self, version: u64) -> Changes<'_, T> {
+        match version.checked_sub(self.0.log_start_version) {
+            Some(min) => Changes::Incremental(self.0.log.range(min as usize..)),
+            None => Changes::Reset,
+        }
     }
-    pub fn total_listeners(&self) -> &'_ Cell<usize> {
-        &self.0.total_listeners
+    /// Register a listener that has seen everything up to the current version.
+    /// Changes are kept in the log (up to the log capacity) until every listener has caught up.
+    pub fn add_listener(&self) -> usize {
+        self.0.listeners.borrow_mut().insert(self.get_version())
     }
-    pub fn pending_listeners(&self) -> &'_ Cell<usize> {
-        &self.0.pending_listeners
+    pub fn remove_listener(&self, listener: usize) {
+        let mut listeners = self.0.listeners.borrow_mut();
+        if listeners.contains(listener) {
+            listeners.remove(listener);
+        }
+    }
+    pub fn set_listener_version(&self, listener: usize, version: u64) {
+        if let Some(v) = self.0.listeners.borrow_mut().get_mut(listener) {
+            *v = version;
+        }
+    }
+    /// A number for each current item, kept while the item stays in the list.
+    ///
+    /// Cloning this is cheap. Keep it to catch up with [changes_from_ids][Self::changes_from_ids]
+    /// after a [Changes::Reset].
+    pub fn item_ids(&self) -> &Vector<u64> {
+        &self.0.ids
     }
+    /// The removals, [Change::Move]s, and insertions that turn the items that had `ids` into the current ones.
+    pub fn changes_from_ids(&self, ids: &Vector<u64>) -> Vec<Change<T>> {
+        let current: Vec<u64> = self.0.ids.iter().copied().collect();
+        keyed_steps(ids.iter().copied(), &current)
+            .into_iter()
+            .map(|step| match step {
+                KeyedStep::Remove(index) => Change::Remove { index },
+                KeyedStep::Move { from, to } => Change::Move { from, to },
+                KeyedStep::Insert { index, new_index } => Change::Insert {
+                    index,
+                    value: self.0.head[new_index].to_owned(),
+                },
+            })
+            .collect()
+    }
+}
+
+pub enum Changes<'l, T: Clone> {
+    /// The changes since the requested version, oldest first.
+    Incremental(vec_deque::Iter<'l, Change<T>>),
+    /// The changes since the requested version are no longer in the log.
+    /// Catch up with [ListModelPrivateAPIs::changes_from_ids], or start over from [ListModel::underlying_vector].
+    Reset,
 }
@@ -0,0 +1,287 @@
+//! Render different components depending on a location path.
+//!
+//! A [Router] is passed down through the context (with `with_context`).
+//! [route_outlet]s inside it render the first [Route] matching the current path.
+//! Outlets can be nested: a route ending in `*rest` leaves the rest of the path to outlets inside it.
+use std::{
+    cell::RefCell,
+    future::{pending, Future},
+    rc::Rc,
+};
+
+use futures_lite::FutureExt;
+use observables::{cell::ReactiveCell, Observable, ObservableAs, ObservableAsExt};
+
+use crate::{
+    backend::BackendTrait,
+    fragment::Fragment,
+    vnode::node_context::{get_context, try_get_context, WithContext},
+};
+
+/// The path params captured by the matched routes.
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct Params(Vec<(String, String)>);
+impl Params {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+enum Segment {
+    Static(String),
+    Param(String),
+    Rest(String),
+}
+
+/// A path pattern like `/users/:id/*rest`.
+///
+/// `:name` matches one segment and `*name` matches all remaining segments (possibly none).
+/// Empty segments are ignored, so trailing slashes don't matter.
+pub struct RoutePattern(Vec<Segment>);
+impl RoutePattern {
+    pub fn parse(pattern: &str) -> Self {
+        Self(
+            split_path(pattern)
+                .map(|segment| {
+                    if let Some(name) = segment.strip_prefix(':') {
+                        Segment::Param(name.to_owned())
+                    } else if let Some(name) = segment.strip_prefix('*') {
+                        Segment::Rest(name.to_owned())
+                    } else {
+                        Segment::Static(segment.to_owned())
+                    }
+                })
+                .collect(),
+        )
+    }
+    /// The params if `path` matches. `path` must not have a query or fragment.
+    pub fn matches(&self, path: &str) -> Option<Params> {
+        let segments: Vec<&str> = split_path(path).collect();
+        self.match_segments(&segments)
+            .map(|(params, _)| Params(params))
+    }
+    /// The captured params and how many segments are consumed, leaving the `*rest` to nested outlets.
+    fn match_segments(&self, segments: &[&str]) -> Option<(Vec<(String, String)>, usize)> {
+        let mut params = Vec::new();
+        let mut index = 0;
+        for pattern_segment in self.0.iter() {
+            match pattern_segment {
+                Segment::Rest(name) => {
+                    params.push((name.to_owned(), segments[index..].join("/")));
+                    return Some((params, index));
+                }
+                Segment::Param(name) => {
+                    params.push((name.to_owned(), (*segments.get(index)?).to_owned()));
+                }
+                Segment::Static(s) => {
+                    if *segments.get(index)? != s {
+                        return None;
+                    }
+                }
+            }
+            index += 1;
+        }
+        (index == segments.len()).then_some((params, index))
+    }
+}
+
+fn split_path(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|s| !s.is_empty())
+}
+
+/// A row of the route table given to [route_outlet].
+pub struct Route<'c, B: BackendTrait> {
+    pattern: RoutePattern,
+    render: Box<dyn Fn(Params) -> Fragment<'c, B> + 'c>,
+}
+impl<'c, B: BackendTrait> Route<'c, B> {
+    pub fn new<F: Fn(Params) -> Fragment<'c, B> + 'c>(pattern: &str, render: F) -> Self {
+        Self {
+            pattern: RoutePattern::parse(pattern),
+            render: Box::new(render),
+        }
+    }
+}
+
+/// Where the [Router] records navigation, like the browser's session history.
+pub trait History {
+    fn push(&self, location: &str);
+    fn replace(&self, location: &str);
+    /// Go back one entry.
+    /// Returns the new location, or `None` if the backend will call [Router::set_location] later.
+    fn back(&self) -> Option<String>;
+}
+
+/// A [History] kept in memory, for backends without a URL bar and for tests.
+pub struct MemoryHistory {
+    entries: RefCell<Vec<String>>,
+}
+impl MemoryHistory {
+    pub fn new(initial: &str) -> Self {
+        Self {
+            entries: RefCell::new(vec![initial.to_owned()]),
+        }
+    }
+}
+impl History for MemoryHistory {
+    fn push(&self, location: &str) {
+        self.entries.borrow_mut().push(location.to_owned());
+    }
+    fn replace(&self, location: &str) {
+        if let Some(last) = self.entries.borrow_mut().last_mut() {
+            *last = location.to_owned();
+        }
+    }
+    fn back(&self) -> Option<String> {
+        let mut entries = self.entries.borrow_mut();
+        if entries.len() > 1 {
+            entries.pop();
+        }
+        entries.last().cloned()
+    }
+}
+
+/// The current location, observable, and the [History] it is recorded in.
+pub struct Router {
+    location: ReactiveCell<String>,
+    history: Box<dyn History>,
+}
+impl Router {
+    pub fn new(initial: &str, history: Box<dyn History>) -> Self {
+        Self {
+            location: ReactiveCell::new(initial.to_owned()),
+            history,
+        }
+    }
+    pub fn new_in_memory(initial: &str) -> Self {
+        Self::new(initial, Box::new(MemoryHistory::new(initial)))
+    }
+    /// The current location: path, query, and fragment.
+    pub fn location(&self) -> impl ObservableAs<str> + '_ {
+        self.location.as_observable()
+    }
+    /// The path part of the current location.
+    pub fn path(&self) -> String {
+        let observable = self.location.as_observable();
+        let location = observable.borrow_observable();
+        location
+            .split(['?', '#'])
+            .next()
+            .unwrap_or_default()
+            .to_owned()
+    }
+    /// Go to `to` (an absolute path), adding an entry to the history.
+    pub fn navigate(&self, to: &str) {
+        if *self.location.as_observable().borrow_observable() == to {
+            return;
+        }
+        self.history.push(to);
+        self.location.set(to.to_owned());
+    }
+    /// Go to `to` (an absolute path), replacing the current history entry.
+    pub fn replace(&self, to: &str) {
+        self.history.replace(to);
+        self.location.set(to.to_owned());
+    }
+    pub fn back(&self) {
+        if let Some(location) = self.history.back() {
+            self.location.set(location);
+        }
+    }
+    /// Record a location change that the history made by itself, like the browser's back button.
+    pub fn set_location(&self, location: &str) {
+        self.location.set(location.to_owned());
+    }
+}
+
+/// What the enclosing outlets matched. Given to the content of each route.
+#[derive(Default)]
+struct RouteContext {
+    params: Params,
+    /// Number of path segments matched by the enclosing outlets.
+    consumed: usize,
+}
+
+/// The [Router] passed down with `with_context`.
+pub fn get_router<B: BackendTrait>() -> Rc<Router> {
+    get_context::<B, Router>()
+}
+
+/// The params matched by all the enclosing routes.
+pub fn route_params<B: BackendTrait>() -> Params {
+    try_get_context::<B, RouteContext>()
+        .map(|c| c.params.clone())
+        .unwrap_or_default()
+}
+
+/// The content of the matched route (or nothing if no route matched), ready to be mounted.
+pub type RouteContent<'c, B> = WithContext<B, Fragment<'c, B>>;
+
+/// Render the first route in `routes` that matches the current path.
+///
+/// The rendered content is dropped and replaced when the path starts matching a different route
+/// or the same route with different params.
+pub async fn route_outlet<'c, B: BackendTrait>(routes: &[Route<'c, B>]) {
+    route_outlet_with(routes, |_index, content| content).await
+}
+
+/// Like [route_outlet], but lets the backend decide where the content goes.
+///
+/// `show` is called with the index of the matched route and its content
+/// each time the match changes; the future it returns is dropped on the next change.
+pub async fn route_outlet_with<'c, B, S, F>(routes: &[Route<'c, B>], mut show: S)
+where
+    B: BackendTrait,
+    S: FnMut(Option<usize>, RouteContent<'c, B>) -> F,
+    F: Future<Output = ()>,
+{
+    let router = get_router::<B>();
+    let parent = try_get_context::<B, RouteContext>().unwrap_or_default();
+    let find = |path: &str| {
+        let segments: Vec<&str> = split_path(path).skip(parent.consumed).collect();
+        routes.iter().enumerate().find_map(|(index, route)| {
+            route
+                .pattern
+                .match_segments(&segments)
+                .map(|(params, consumed)| (index, params, consumed))
+        })
+    };
+    let mut current = find(&router.path());
+    loop {
+        let (index, content) = match current.clone() {
+            Some((index, params, consumed)) => {
+                let mut all_params = parent.params.clone();
+                all_params.0.extend(params);
+                let content = (routes[index].render)(all_params.clone());
+                let context = RouteContext {
+                    params: all_params,
+                    consumed: parent.consumed + consumed,
+                };
+                (Some(index), WithContext::new(content, Rc::new(context)))
+            }
+            None => (
+                None,
+                WithContext::new(Fragment::default(), Rc::new(RouteContext::default())),
+            ),
+        };
+        let content = async {
+            show(index, content).await;
+            pending().await
+        };
+        let next = async {
+            loop {
+                router.location().until_change().await;
+                let found = find(&router.path());
+                if found != current {
+                    break found;
+                }
+            }
+        };
+        let found = content.or(next).await;
+        current = found;
+    }
+}
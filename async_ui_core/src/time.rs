@@ -0,0 +1,154 @@
+//! Waiting for time to pass: [sleep], [interval], and observables that follow another one
+//! at a slower pace ([Debounced], [Throttled]).
+//!
+//! The timers come from the backend ([BackendTrait::sleep]): `setTimeout` on the web,
+//! the GLib main loop on GTK, and virtual time in the headless backend.
+use std::{cell::Cell, marker::PhantomData, rc::Rc, task::Waker, time::Duration};
+
+use async_executor::Task;
+use futures_lite::FutureExt;
+use observables::{
+    cell::{ReactiveCell, ReactiveCellObservable},
+    Listenable, ObservableAs, ObservableAsExt, ObservableBorrow, Version,
+};
+
+use crate::{backend::BackendTrait, executor::spawn_local};
+
+/// Complete after `duration`.
+pub async fn sleep<B: BackendTrait>(duration: Duration) {
+    B::sleep(duration).await
+}
+
+/// Call `tick` every `period`. Never completes.
+///
+/// Each period starts when the previous `tick` returns.
+pub async fn interval<B: BackendTrait, F: FnMut()>(period: Duration, mut tick: F) {
+    loop {
+        B::sleep(period).await;
+        tick();
+    }
+}
+
+/// A copy of `source` that only takes its value once `source` has stopped changing for a while.
+///
+/// Good for search-as-you-type: follow the text with a `Debounced` and search when it changes.
+/// The copy is updated by a task spawned in [new][Self::new], until the `Debounced` is dropped.
+pub struct Debounced<B: BackendTrait, T: ToOwned + ?Sized + 'static> {
+    follower: Rc<Follower<T>>,
+    _task: Task<()>,
+    _backend: PhantomData<B>,
+}
+impl<B: BackendTrait, T: ToOwned + ?Sized + 'static> Debounced<B, T> {
+    /// Update `wait` after the last change of `source`.
+    pub fn new<O: ObservableAs<T> + 'static>(source: O, wait: Duration) -> Self {
+        let follower = Rc::new(Follower::new(Box::new(source)));
+        Self {
+            _task: spawn_local(debounce::<B, T>(follower.clone(), wait)),
+            follower,
+            _backend: PhantomData,
+        }
+    }
+}
+async fn debounce<B: BackendTrait, T: ToOwned + ?Sized>(follower: Rc<Follower<T>>, wait: Duration) {
+    let source = &*follower.source;
+    loop {
+        if source.get_version() == follower.seen.get() {
+            source.until_change().await;
+        }
+        // Start the wait over each time the source changes again.
+        loop {
+            let changed = async {
+                B::sleep(wait).await;
+                false
+            }
+            .or(async {
+                source.until_change().await;
+                true
+            })
+            .await;
+            if !changed {
+                break;
+            }
+        }
+        follower.update();
+    }
+}
+
+/// A copy of `source` that takes its value at most once per period.
+///
+/// The first change is taken right away. Changes during the following period
+/// are taken together when it ends, so the last value is never lost.
+/// The copy is updated by a task spawned in [new][Self::new], until the `Throttled` is dropped.
+pub struct Throttled<B: BackendTrait, T: ToOwned + ?Sized + 'static> {
+    follower: Rc<Follower<T>>,
+    _task: Task<()>,
+    _backend: PhantomData<B>,
+}
+impl<B: BackendTrait, T: ToOwned + ?Sized + 'static> Throttled<B, T> {
+    pub fn new<O: ObservableAs<T> + 'static>(source: O, period: Duration) -> Self {
+        let follower = Rc::new(Follower::new(Box::new(source)));
+        Self {
+            _task: spawn_local(throttle::<B, T>(follower.clone(), period)),
+            follower,
+            _backend: PhantomData,
+        }
+    }
+}
+async fn throttle<B: BackendTrait, T: ToOwned + ?Sized>(
+    follower: Rc<Follower<T>>,
+    period: Duration,
+) {
+    let source = &*follower.source;
+    loop {
+        if source.get_version() == follower.seen.get() {
+            source.until_change().await;
+        }
+        follower.update();
+        B::sleep(period).await;
+    }
+}
+
+/// The part shared by [Debounced] and [Throttled]: a source and a copy of it.
+struct Follower<T: ToOwned + ?Sized + 'static> {
+    source: Box<dyn ObservableAs<T>>,
+    /// The version of the source when the copy was made.
+    seen: Cell<Version>,
+    copy: ReactiveCellObservable<T::Owned, Rc<ReactiveCell<T::Owned>>>,
+    cell: Rc<ReactiveCell<T::Owned>>,
+}
+impl<T: ToOwned + ?Sized> Follower<T> {
+    fn new(source: Box<dyn ObservableAs<T>>) -> Self {
+        let cell = Rc::new(ReactiveCell::new(source.borrow_observable_as().to_owned()));
+        Self {
+            seen: Cell::new(source.get_version()),
+            source,
+            copy: cell.as_observable_rc(),
+            cell,
+        }
+    }
+    fn update(&self) {
+        self.seen.set(self.source.get_version());
+        let value = self.source.borrow_observable_as().to_owned();
+        self.cell.set(value);
+    }
+}
+
+macro_rules! impl_observable {
+    ($name:ident) => {
+        impl<B: BackendTrait, T: ToOwned + ?Sized> Listenable for $name<B, T> {
+            fn add_waker(&self, waker: Waker) {
+                self.follower.copy.add_waker(waker)
+            }
+            fn get_version(&self) -> Version {
+                self.follower.copy.get_version()
+            }
+        }
+        impl<B: BackendTrait, T: ToOwned + ?Sized> ObservableAs<T> for $name<B, T> {
+            fn borrow_observable_as<'b>(&'b self) -> ObservableBorrow<'b, T> {
+                ObservableAs::<T>::borrow_observable_as(&self.follower.copy)
+            }
+        }
+    };
+}
+impl_observable!(Debounced);
+impl_observable!(Throttled);
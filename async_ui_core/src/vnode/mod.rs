@@ -7,13 +7,18 @@ pub mod node_concrete;
 pub mod node_context;
 pub mod node_pass;
 pub mod node_portal;
-use crate::context::ContextMap;
+pub mod node_suspense;
+pub mod node_transition;
+use crate::{
+    context::ContextMap,
+    error_boundary::{boundary_of, poll_in_vnode, BoundaryHandle},
+};
 
 use crate::{backend::BackendTrait, position::PositionIndex};
 
 use self::{
     node_concrete::ConcreteNodeVNode, node_context::ContextVNode, node_pass::PassVNode,
-    node_portal::PortalVNode,
+    node_portal::PortalVNode, node_suspense::SuspenseVNode, node_transition::TransitionVNode,
 };
 
 #[enum_dispatch]
@@ -21,7 +26,15 @@ pub trait VNodeTrait<B: BackendTrait> {
     fn add_child_node(&self, node: B::Node, position: PositionIndex);
     fn del_child_node(&self, position: PositionIndex) -> B::Node;
     fn get_context_map<'s>(&'s self) -> &'s ContextMap;
+    /// Call `f` with the parent node and the children just before and after where a node at `position` would go.
+    /// Used to find nodes that are already in place (when hydrating server-rendered content).
+    fn with_child_slot(&self, position: PositionIndex, f: ChildSlotFn<'_, B>);
 }
+pub type ChildSlotFn<'f, B> = &'f mut dyn FnMut(
+    &<B as BackendTrait>::Node,
+    Option<&<B as BackendTrait>::Node>,
+    Option<&<B as BackendTrait>::Node>,
+);
 
 #[enum_dispatch(VNodeTrait<B>)]
 pub enum VNode<B: BackendTrait> {
@@ -29,19 +42,29 @@ pub enum VNode<B: BackendTrait> {
     Context(ContextVNode<B>),
     Pass(PassVNode<B>),
     Portal(PortalVNode<B>),
+    Suspense(SuspenseVNode<B>),
+    Transition(TransitionVNode<B>),
 }
 
 pin_project! {
     pub struct WithVNode<B: BackendTrait, F: Future> {
         #[pin]
         future: F,
-        vnode: Rc<VNode<B>>
+        vnode: Rc<VNode<B>>,
+        boundary: Option<Rc<BoundaryHandle>>,
+        panicked: bool,
     }
 }
 
 impl<B: BackendTrait, F: Future> WithVNode<B, F> {
     pub fn new(future: F, vnode: Rc<VNode<B>>) -> Self {
-        Self { future, vnode }
+        let boundary = boundary_of(&vnode);
+        Self {
+            future,
+            vnode,
+            boundary,
+            panicked: false,
+        }
     }
 }
 impl<B: BackendTrait, F: Future> Future for WithVNode<B, F> {
@@ -52,6 +75,8 @@ impl<B: BackendTrait, F: Future> Future for WithVNode<B, F> {
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Self::Output> {
         let this = self.project();
-        B::get_vnode_key().set(this.vnode, || this.future.poll(cx))
+        poll_in_vnode(this.vnode, this.boundary.as_deref(), this.panicked, || {
+            this.future.poll(cx)
+        })
     }
 }
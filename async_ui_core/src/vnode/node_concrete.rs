@@ -11,7 +11,7 @@ use pin_project_lite::pin_project;
 
 use crate::{backend::BackendTrait, context::ContextMap, position::PositionIndex};
 
-use super::{VNode, VNodeTrait};
+use super::{ChildSlotFn, VNode, VNodeTrait};
 
 pub struct ConcreteNodeVNode<B: BackendTrait> {
     inside: RefCell<Inside<B>>,
@@ -20,6 +20,7 @@ pub struct ConcreteNodeVNode<B: BackendTrait> {
 struct Inside<B: BackendTrait> {
     node: RefNode<B>,
     children: BTreeMap<PositionIndex, B::Node>,
+    detached: bool,
 }
 
 impl<B: BackendTrait> ConcreteNodeVNode<B> {
@@ -28,10 +29,17 @@ impl<B: BackendTrait> ConcreteNodeVNode<B> {
             inside: RefCell::new(Inside {
                 node,
                 children: BTreeMap::new(),
+                detached: false,
             }),
             context,
         }
     }
+    /// Mark that the node is being removed along with everything in it.
+    /// Children removed after this are left in the node,
+    /// so that it stays whole if it is kept around to animate it out.
+    pub fn detach(&self) {
+        self.inside.borrow_mut().detached = true;
+    }
 }
 pub enum RefNode<B: BackendTrait> {
     Parent { parent: B::Node },
@@ -44,6 +52,7 @@ impl<B: BackendTrait> VNodeTrait<B> for ConcreteNodeVNode<B> {
         let Inside {
             node: this_node,
             children: children_map,
+            ..
         } = &mut *inside;
         let next_node = children_map
             .range(position.clone()..)
@@ -63,6 +72,9 @@ impl<B: BackendTrait> VNodeTrait<B> for ConcreteNodeVNode<B> {
     fn del_child_node(&self, position: PositionIndex) -> B::Node {
         let mut inside = self.inside.borrow_mut();
         let mut removed = inside.children.remove(&position).unwrap();
+        if inside.detached {
+            return removed;
+        }
         match &mut inside.node {
             RefNode::Parent { parent } => B::del_child_node(parent, &mut removed),
             RefNode::Sibling { parent, .. } => B::del_child_node(parent, &mut removed),
@@ -73,6 +85,22 @@ impl<B: BackendTrait> VNodeTrait<B> for ConcreteNodeVNode<B> {
     fn get_context_map<'s>(&'s self) -> &'s ContextMap {
         &self.context
     }
+
+    fn with_child_slot(&self, position: PositionIndex, f: ChildSlotFn<'_, B>) {
+        let inside = self.inside.borrow();
+        let prev_node = inside
+            .children
+            .range(..position.clone())
+            .next_back()
+            .map(|(_k, v)| v);
+        let next_node = inside.children.range(position..).next().map(|(_k, v)| v);
+        match &inside.node {
+            RefNode::Parent { parent } => f(parent, prev_node, next_node),
+            RefNode::Sibling { parent, sibling } => {
+                f(parent, prev_node, Some(next_node.unwrap_or(sibling)))
+            }
+        }
+    }
 }
 
 enum WithConcreteNodeState<B: BackendTrait> {
@@ -1,16 +1,22 @@
 use std::{
-    any::{Any, TypeId},
     future::{Future, IntoFuture},
     pin::Pin,
     rc::Rc,
     task::{Context, Poll},
 };
 
+use futures_lite::FutureExt;
+use observables::{cell::ReactiveCell, ObservableAs, ObservableAsExt};
 use pin_project_lite::pin_project;
 
-use crate::{backend::BackendTrait, context::ContextMap, position::PositionIndex, vnode::VNode};
+use crate::{
+    backend::BackendTrait,
+    context::{ContextMap, Contexts, ObservableContext},
+    position::PositionIndex,
+    vnode::VNode,
+};
 
-use super::VNodeTrait;
+use super::{ChildSlotFn, VNodeTrait};
 
 pub struct ContextVNode<B: BackendTrait> {
     parent: Rc<VNode<B>>,
@@ -35,26 +41,27 @@ impl<B: BackendTrait> VNodeTrait<B> for ContextVNode<B> {
     fn get_context_map<'s>(&'s self) -> &'s ContextMap {
         &self.context
     }
+
+    fn with_child_slot(&self, position: PositionIndex, f: ChildSlotFn<'_, B>) {
+        self.parent.with_child_slot(position, f)
+    }
 }
 
 pub fn get_context<B: BackendTrait, T: 'static>() -> Rc<T> {
-    B::get_vnode_key().with(|vn| {
-        let cmap = vn.get_context_map();
-        let type_id = TypeId::of::<T>();
-        let entry = cmap
-            .inner
-            .get(&type_id)
-            .expect("Context not set.")
-            .to_owned();
-        let val = entry.downcast::<T>().unwrap();
-        val
-    })
+    try_get_context::<B, T>().expect("Context not set.")
+}
+pub fn try_get_context<B: BackendTrait, T: 'static>() -> Option<Rc<T>> {
+    B::get_vnode_key().with(|vn| vn.get_context_map().get::<T>())
+}
+/// The context value of type `T`, or `T::default()` if none was provided.
+pub fn get_context_or_default<B: BackendTrait, T: Default + 'static>() -> Rc<T> {
+    try_get_context::<B, T>().unwrap_or_default()
 }
 enum WithContextState<B>
 where
     B: BackendTrait,
 {
-    NotStarted { value: Rc<dyn Any> },
+    NotStarted { values: Contexts },
     Started { vnode: Rc<VNode<B>> },
     Null,
 }
@@ -81,14 +88,9 @@ where
         let this = self.project();
         let vk = B::get_vnode_key();
         let vnode = match std::mem::replace(this.state, WithContextState::Null) {
-            WithContextState::NotStarted { value } => {
+            WithContextState::NotStarted { values } => {
                 let parent = vk.with(Clone::clone);
-                let context = ContextMap {
-                    inner: parent
-                        .get_context_map()
-                        .inner
-                        .update(value.as_ref().type_id(), value),
-                };
+                let context = parent.get_context_map().extend(values);
                 let vnode = Rc::new(ContextVNode::new(parent, context).into());
                 vnode
             }
@@ -106,9 +108,70 @@ where
     F: Future,
 {
     pub fn new<T: 'static, I: IntoFuture<IntoFuture = F>>(into_future: I, value: Rc<T>) -> Self {
+        Self::new_many(into_future, Contexts::new().with(value))
+    }
+    /// Provide all of `values` in one context node.
+    pub fn new_many<I: IntoFuture<IntoFuture = F>>(into_future: I, values: Contexts) -> Self {
         Self {
             future: into_future.into_future(),
-            state: WithContextState::NotStarted { value },
+            state: WithContextState::NotStarted { values },
         }
     }
 }
+
+/// Provide `value` to `children`, following its changes.
+///
+/// Consumers get it with [get_observable_context].
+/// The value is copied (with [ToOwned]) each time it changes, since the context outlives the borrow.
+pub async fn provide_observable_context<B, I, T>(
+    children: I,
+    value: &dyn ObservableAs<T>,
+) -> I::Output
+where
+    B: BackendTrait,
+    I: IntoFuture,
+    T: ToOwned + ?Sized + 'static,
+{
+    provide_keyed_observable_context::<B, (), I, T>(children, value).await
+}
+/// Like [provide_observable_context], but only for consumers that look it up with the key type `K`
+/// (see [get_keyed_observable_context]), so it doesn't shadow or get shadowed by other contexts of type `T`.
+pub async fn provide_keyed_observable_context<B, K, I, T>(
+    children: I,
+    value: &dyn ObservableAs<T>,
+) -> I::Output
+where
+    B: BackendTrait,
+    K: 'static,
+    I: IntoFuture,
+    T: ToOwned + ?Sized + 'static,
+{
+    let cell = Rc::new(ReactiveCell::new(value.borrow_observable_as().to_owned()));
+    WithContext::<B, _>::new(children, Rc::new(ObservableContext::<T, K>::new(&cell)))
+        .or(async {
+            loop {
+                value.until_change().await;
+                cell.set(value.borrow_observable_as().to_owned());
+            }
+        })
+        .await
+}
+pub fn get_observable_context<B: BackendTrait, T: ToOwned + ?Sized + 'static>(
+) -> Rc<ObservableContext<T>> {
+    try_get_observable_context::<B, T>().expect("Observable context not set.")
+}
+pub fn try_get_observable_context<B: BackendTrait, T: ToOwned + ?Sized + 'static>(
+) -> Option<Rc<ObservableContext<T>>> {
+    try_get_keyed_observable_context::<B, (), T>()
+}
+pub fn get_keyed_observable_context<B: BackendTrait, K: 'static, T: ToOwned + ?Sized + 'static>(
+) -> Rc<ObservableContext<T, K>> {
+    try_get_keyed_observable_context::<B, K, T>().expect("Observable context not set.")
+}
+pub fn try_get_keyed_observable_context<
+    B: BackendTrait,
+    K: 'static,
+    T: ToOwned + ?Sized + 'static,
+>() -> Option<Rc<ObservableContext<T, K>>> {
+    try_get_context::<B, ObservableContext<T, K>>()
+}
@@ -2,7 +2,7 @@ use std::rc::Rc;
 
 use crate::{backend::BackendTrait, context::ContextMap, position::PositionIndex, vnode::VNode};
 
-use super::VNodeTrait;
+use super::{ChildSlotFn, VNodeTrait};
 
 pub struct PassVNode<B: BackendTrait> {
     parent: Rc<VNode<B>>,
@@ -35,4 +35,9 @@ impl<B: BackendTrait> VNodeTrait<B> for PassVNode<B> {
     fn get_context_map<'s>(&'s self) -> &'s ContextMap {
         &self.context
     }
+
+    fn with_child_slot(&self, mut position: PositionIndex, f: ChildSlotFn<'_, B>) {
+        position.wrap(self.index);
+        self.parent.with_child_slot(position, f)
+    }
 }
@@ -12,7 +12,7 @@ use pin_project_lite::pin_project;
 
 use crate::{backend::BackendTrait, context::ContextMap, position::PositionIndex};
 
-use super::{VNode, VNodeTrait};
+use super::{ChildSlotFn, VNode, VNodeTrait};
 
 struct Shared<B: BackendTrait> {
     target: Option<Rc<VNode<B>>>,
@@ -38,13 +38,26 @@ impl<B: BackendTrait> VNodeTrait<B> for PortalVNode<B> {
 
     fn del_child_node(&self, position: PositionIndex) -> B::Node {
         let mut bm = self.shared.borrow_mut();
-        let _removed = bm.nodes.remove(&position);
-        bm.target.as_ref().unwrap().del_child_node(position)
+        let removed = bm.nodes.remove(&position);
+        match bm.target.as_ref() {
+            Some(target) => target.del_child_node(position),
+            // The exit is not mounted (or was already dropped), so we are holding the node.
+            None => removed
+                .flatten()
+                .expect("node not found in inactive portal"),
+        }
     }
 
     fn get_context_map<'s>(&'s self) -> &'s ContextMap {
         &self.context
     }
+
+    fn with_child_slot(&self, position: PositionIndex, f: ChildSlotFn<'_, B>) {
+        let target = self.shared.borrow().target.clone();
+        if let Some(target) = target {
+            target.with_child_slot(position, f);
+        }
+    }
 }
 
 pub struct PortalEntry<B: BackendTrait> {
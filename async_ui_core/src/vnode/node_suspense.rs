@@ -0,0 +1,204 @@
+use std::{
+    any::{Any, TypeId},
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+    future::{Future, IntoFuture},
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+use pin_project_lite::pin_project;
+
+use crate::{backend::BackendTrait, context::ContextMap, position::PositionIndex};
+
+use super::{node_context::try_get_context, node_pass::PassVNode, ChildSlotFn, VNode, VNodeTrait};
+
+/// Put in the context of the children, so they can say they are ready without adding a node.
+struct ReadySignal {
+    ready: Cell<bool>,
+    waker: RefCell<Option<Waker>>,
+}
+impl ReadySignal {
+    fn set_ready(&self) {
+        if !self.ready.replace(true) {
+            if let Some(waker) = self.waker.borrow().as_ref() {
+                waker.wake_by_ref();
+            }
+        }
+    }
+}
+
+struct Shared<B: BackendTrait> {
+    revealed: bool,
+    /// Nodes the children added before they were revealed.
+    held: BTreeMap<PositionIndex, B::Node>,
+}
+
+/// Holds back the nodes of the children until they are revealed.
+pub struct SuspenseVNode<B: BackendTrait> {
+    parent: Rc<VNode<B>>,
+    shared: Rc<RefCell<Shared<B>>>,
+    signal: Rc<ReadySignal>,
+    context: ContextMap,
+}
+
+impl<B: BackendTrait> VNodeTrait<B> for SuspenseVNode<B> {
+    fn add_child_node(&self, node: B::Node, position: PositionIndex) {
+        let mut shared = self.shared.borrow_mut();
+        if shared.revealed {
+            self.parent.add_child_node(node, position);
+        } else {
+            shared.held.insert(position, node);
+            self.signal.set_ready();
+        }
+    }
+
+    fn del_child_node(&self, position: PositionIndex) -> B::Node {
+        let mut shared = self.shared.borrow_mut();
+        if shared.revealed {
+            self.parent.del_child_node(position)
+        } else {
+            shared
+                .held
+                .remove(&position)
+                .expect("node not found in suspense")
+        }
+    }
+
+    fn get_context_map(&self) -> &ContextMap {
+        &self.context
+    }
+
+    fn with_child_slot(&self, position: PositionIndex, f: ChildSlotFn<'_, B>) {
+        if self.shared.borrow().revealed {
+            self.parent.with_child_slot(position, f);
+        }
+    }
+}
+
+impl<B: BackendTrait> SuspenseVNode<B> {
+    /// Take down the fallback first, then put in the held nodes, in the same poll.
+    fn reveal(&self) {
+        let held = {
+            let mut shared = self.shared.borrow_mut();
+            if shared.revealed {
+                return;
+            }
+            shared.revealed = true;
+            std::mem::take(&mut shared.held)
+        };
+        for (position, node) in held {
+            self.parent.add_child_node(node, position);
+        }
+    }
+}
+
+/// Tell the closest enclosing [Suspense] that its children are ready to be shown,
+/// even if they have not added any node.
+pub fn mark_ready<B: BackendTrait>() {
+    if let Some(signal) = try_get_context::<B, ReadySignal>() {
+        signal.set_ready();
+    }
+}
+
+struct SuspenseVNodes<B: BackendTrait> {
+    children: Rc<VNode<B>>,
+    fallback: Rc<VNode<B>>,
+    signal: Rc<ReadySignal>,
+}
+
+pin_project! {
+    /// Shows `fallback` until `children` add their first node (or call [mark_ready]),
+    /// then swaps it for the children.
+    pub struct Suspense<B, F, G>
+    where
+        B: BackendTrait,
+        F: Future,
+        G: Future<Output = ()>,
+    {
+        #[pin]
+        children: F,
+        #[pin]
+        fallback: Option<G>,
+        vnodes: Option<SuspenseVNodes<B>>,
+    }
+}
+
+impl<B, F, G> Suspense<B, F, G>
+where
+    B: BackendTrait,
+    F: Future,
+    G: Future<Output = ()>,
+{
+    pub fn new<I: IntoFuture<IntoFuture = F>, J: IntoFuture<IntoFuture = G>>(
+        children: I,
+        fallback: J,
+    ) -> Self {
+        Self {
+            children: children.into_future(),
+            fallback: Some(fallback.into_future()),
+            vnodes: None,
+        }
+    }
+}
+
+impl<B, F, G> Future for Suspense<B, F, G>
+where
+    B: BackendTrait,
+    F: Future,
+    G: Future<Output = ()>,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        let vk = B::get_vnode_key();
+        let vnodes = this.vnodes.get_or_insert_with(|| {
+            let parent = vk.with(Clone::clone);
+            let signal = Rc::new(ReadySignal {
+                ready: Cell::new(false),
+                waker: RefCell::new(None),
+            });
+            let value: Rc<dyn Any> = signal.clone();
+            let context = ContextMap {
+                inner: parent
+                    .get_context_map()
+                    .inner
+                    .update(TypeId::of::<ReadySignal>(), value),
+            };
+            let children = Rc::new(
+                SuspenseVNode {
+                    parent: Rc::new(PassVNode::new(parent.clone(), 0).into()),
+                    shared: Rc::new(RefCell::new(Shared {
+                        revealed: false,
+                        held: BTreeMap::new(),
+                    })),
+                    signal: signal.clone(),
+                    context,
+                }
+                .into(),
+            );
+            let fallback = Rc::new(PassVNode::new(parent, 1).into());
+            SuspenseVNodes {
+                children,
+                fallback,
+                signal,
+            }
+        });
+        *vnodes.signal.waker.borrow_mut() = Some(cx.waker().to_owned());
+        let res = vk.set(&vnodes.children, || this.children.poll(cx));
+        if vnodes.signal.ready.get() || res.is_ready() {
+            // Dropping the fallback removes its nodes.
+            this.fallback.set(None);
+            if let VNode::Suspense(suspense) = &*vnodes.children {
+                suspense.reveal();
+            }
+        } else if let Some(fallback) = this.fallback.as_mut().as_pin_mut() {
+            if vk.set(&vnodes.fallback, || fallback.poll(cx)).is_ready() {
+                this.fallback.set(None);
+            }
+        }
+        res
+    }
+}
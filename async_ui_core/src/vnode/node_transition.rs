@@ -0,0 +1,108 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+use pin_project_lite::pin_project;
+
+use crate::{backend::BackendTrait, context::ContextMap, position::PositionIndex, vnode::VNode};
+
+use super::{ChildSlotFn, VNodeTrait};
+
+/// What a backend does as nodes come and go inside a [WithTransition], to animate them.
+pub trait TransitionHooks<B: BackendTrait> {
+    /// Called just before `node` is added.
+    fn enter(&self, node: &B::Node);
+    /// Called just after `node` is removed from `parent`, where it was right before `next`.
+    ///
+    /// To animate the removal, put `node` back at the same place, then remove it once the animation ends.
+    /// Nodes put back like this are not tracked by the vnode tree, so later siblings are placed as if they were gone.
+    fn exit(&self, node: &B::Node, parent: &B::Node, next: Option<&B::Node>);
+}
+
+pub struct TransitionVNode<B: BackendTrait> {
+    parent: Rc<VNode<B>>,
+    hooks: Rc<dyn TransitionHooks<B>>,
+    context: ContextMap,
+}
+
+impl<B: BackendTrait> TransitionVNode<B> {
+    pub fn new(parent: Rc<VNode<B>>, hooks: Rc<dyn TransitionHooks<B>>) -> Self {
+        let context = parent.get_context_map().to_owned();
+        Self {
+            parent,
+            hooks,
+            context,
+        }
+    }
+}
+
+impl<B: BackendTrait> VNodeTrait<B> for TransitionVNode<B> {
+    fn add_child_node(&self, node: B::Node, position: PositionIndex) {
+        self.hooks.enter(&node);
+        self.parent.add_child_node(node, position)
+    }
+
+    fn del_child_node(&self, position: PositionIndex) -> B::Node {
+        let node = self.parent.del_child_node(position.clone());
+        self.parent
+            .with_child_slot(position, &mut |parent, _prev, next| {
+                self.hooks.exit(&node, parent, next)
+            });
+        node
+    }
+
+    fn get_context_map(&self) -> &ContextMap {
+        &self.context
+    }
+
+    fn with_child_slot(&self, position: PositionIndex, f: ChildSlotFn<'_, B>) {
+        self.parent.with_child_slot(position, f)
+    }
+}
+
+enum WithTransitionState<B: BackendTrait> {
+    NotStarted { hooks: Rc<dyn TransitionHooks<B>> },
+    Started { vnode: Rc<VNode<B>> },
+    Null,
+}
+pin_project! {
+    /// Runs `future` with the nodes it adds and removes going through the given [TransitionHooks].
+    pub struct WithTransition<B, F>
+    where
+        B: BackendTrait,
+        F: Future
+    {
+        #[pin]
+        future: F,
+        state: WithTransitionState<B>,
+    }
+}
+impl<B: BackendTrait, F: Future> WithTransition<B, F> {
+    pub fn new(future: F, hooks: Rc<dyn TransitionHooks<B>>) -> Self {
+        Self {
+            future,
+            state: WithTransitionState::NotStarted { hooks },
+        }
+    }
+}
+impl<B: BackendTrait, F: Future> Future for WithTransition<B, F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let vk = B::get_vnode_key();
+        let vnode = match std::mem::replace(this.state, WithTransitionState::Null) {
+            WithTransitionState::NotStarted { hooks } => {
+                Rc::new(TransitionVNode::new(vk.with(Clone::clone), hooks).into())
+            }
+            WithTransitionState::Started { vnode } => vnode,
+            WithTransitionState::Null => unreachable!(),
+        };
+        let res = vk.set(&vnode, || this.future.poll(cx));
+        *this.state = WithTransitionState::Started { vnode };
+        res
+    }
+}
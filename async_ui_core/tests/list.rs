@@ -0,0 +1,305 @@
+use std::cell::Cell;
+
+use async_ui_core::list::{Change, Changes, ListModel, ListModelPrivateAPIs, ListObservableExt};
+use im_rc::Vector;
+use observables::{cell::ReactiveCell, Listenable, ObservableAs};
+
+/// Run `set_keyed` and return the recorded changes along with the list they rebuild.
+fn keyed_changes(
+    old: &[&'static str],
+    new: &[&'static str],
+) -> (Vec<Change<&'static str>>, Vec<&'static str>) {
+    let mut model = ListModel::from_iter(old.iter().copied());
+    let version = {
+        let model = ListModelPrivateAPIs(&model);
+        model.add_listener();
+        model.get_version()
+    };
+    model.set_keyed(new.iter().copied(), |s| *s);
+    let model = ListModelPrivateAPIs(&model);
+    let changes: Vec<_> = match model.changes_since_version(version) {
+        Changes::Incremental(changes) => changes.cloned().collect(),
+        Changes::Reset => panic!("log overflowed"),
+    };
+    let mut replayed: Vector<&'static str> = old.iter().copied().collect();
+    for change in changes.iter() {
+        match change.clone() {
+            Change::Splice {
+                remove_range,
+                replace_with,
+            } => {
+                let mut right = replayed.split_off(remove_range.start);
+                let right = right.split_off(remove_range.len());
+                replayed.append(replace_with);
+                replayed.append(right);
+            }
+            Change::Remove { index } => {
+                replayed.remove(index);
+            }
+            Change::Insert { index, value } => replayed.insert(index, value),
+            Change::Move { from, to } => {
+                let value = replayed.remove(from);
+                replayed.insert(to, value);
+            }
+        }
+    }
+    assert_eq!(
+        model
+            .0
+            .underlying_vector()
+            .iter()
+            .copied()
+            .collect::<Vec<_>>(),
+        new
+    );
+    (changes, replayed.into_iter().collect())
+}
+
+#[test]
+fn keyed_rotation_moves_one_item() {
+    let (changes, replayed) = keyed_changes(&["a", "b", "c", "d"], &["b", "c", "d", "a"]);
+    assert_eq!(replayed, ["b", "c", "d", "a"]);
+    assert!(matches!(changes[..], [Change::Move { from: 0, to: 3 }]));
+}
+
+#[test]
+fn keyed_mixed() {
+    let cases: &[(&[&str], &[&str])] = &[
+        (&[], &["a", "b"]),
+        (&["a", "b"], &[]),
+        (&["a", "b", "c"], &["a", "b", "c"]),
+        (&["a", "b", "c", "d", "e"], &["e", "d", "c", "b", "a"]),
+        (&["a", "b", "c", "d"], &["x", "d", "b", "y", "a"]),
+        (&["a", "a", "b"], &["b", "a", "b"]),
+    ];
+    for (old, new) in cases {
+        let (changes, replayed) = keyed_changes(old, new);
+        assert_eq!(&replayed, new);
+        assert!(changes.iter().all(|c| !matches!(c, Change::Splice { .. })));
+    }
+    let (changes, _) = keyed_changes(&["a", "b", "c"], &["a", "b", "c"]);
+    assert!(changes.is_empty());
+}
+
+#[test]
+fn keyed_value_change_replaces_item() {
+    let mut model = ListModel::from_iter([(1, "a"), (2, "b"), (3, "c")].into_iter());
+    let version = {
+        let model = ListModelPrivateAPIs(&model);
+        model.add_listener();
+        model.get_version()
+    };
+    model.set_keyed([(3, "c"), (1, "a"), (2, "B")], |(k, _)| *k);
+    let model = ListModelPrivateAPIs(&model);
+    match model.changes_since_version(version) {
+        Changes::Incremental(changes) => {
+            let changes: Vec<_> = changes.collect();
+            assert!(matches!(changes[0], Change::Move { from: 2, to: 0 }));
+            assert!(matches!(
+                changes[1],
+                Change::Splice { remove_range, replace_with }
+                    if *remove_range == (2..3) && replace_with[0] == (2, "B")
+            ));
+            assert_eq!(changes.len(), 2);
+        }
+        Changes::Reset => panic!("log overflowed"),
+    }
+}
+
+#[test]
+fn keyed_large_reorder() {
+    let mut model = ListModel::from_iter(0..5_000u32);
+    let mut new: Vec<u32> = (0..5_000).rev().step_by(2).collect();
+    new.extend(5_000..5_100);
+    model.set_keyed(new.iter().copied(), |i| *i);
+    assert!(model.underlying_vector().iter().eq(new.iter()));
+}
+
+#[test]
+fn log_is_bounded() {
+    let mut model = ListModel::from_iter(0..3);
+    model.set_log_capacity(4);
+    let (fast, slow, version) = {
+        let model = ListModelPrivateAPIs(&model);
+        (
+            model.add_listener(),
+            model.add_listener(),
+            model.get_version(),
+        )
+    };
+    for i in 0..3 {
+        model.push(i);
+    }
+    {
+        let model = ListModelPrivateAPIs(&model);
+        match model.changes_since_version(version) {
+            Changes::Incremental(changes) => assert_eq!(changes.len(), 3),
+            Changes::Reset => panic!("log overflowed early"),
+        }
+        model.set_listener_version(fast, model.get_version());
+    }
+    for i in 0..10 {
+        model.push(i);
+    }
+    let model = ListModelPrivateAPIs(&model);
+    assert!(matches!(
+        model.changes_since_version(version),
+        Changes::Reset
+    ));
+    match model.changes_since_version(model.get_version() - 4) {
+        Changes::Incremental(changes) => assert_eq!(changes.len(), 4),
+        Changes::Reset => panic!("recent changes dropped"),
+    }
+    model.remove_listener(slow);
+    model.remove_listener(fast);
+    assert_eq!(model.0.len(), 16);
+}
+
+/// Apply random edits upstream and check the derived list against one computed from scratch,
+/// also replaying the derived list's own changes (which must never be a reset).
+fn check_adaptor<U: Clone + PartialEq + std::fmt::Debug, D: ObservableAs<ListModel<U>>>(
+    upstream: &ReactiveCell<ListModel<u32>>,
+    derived: &D,
+    expected: impl Fn(&[u32]) -> Vec<U>,
+) {
+    let (listener, mut version, mut replayed) = {
+        let model = derived.borrow_observable_as();
+        let model = ListModelPrivateAPIs(&*model);
+        let items: Vec<U> = model.0.underlying_vector().iter().cloned().collect();
+        (model.add_listener(), model.get_version(), items)
+    };
+    let mut seed = 7u32;
+    let mut random = |below: usize| {
+        seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+        (seed >> 16) as usize % below.max(1)
+    };
+    for step in 0..300 {
+        {
+            let mut model = upstream.borrow_mut();
+            let len = model.len();
+            let value = random(20) as u32;
+            match random(6) {
+                0 | 1 => model.insert(random(len + 1), value),
+                2 if len > 0 => model.remove(random(len)),
+                3 if len > 0 => model.move_item(random(len), random(len)),
+                4 => {
+                    let start = random(len + 1);
+                    let end = start + random(len - start + 1);
+                    model.splice(start..end, (0..random(3)).map(|i| value + i as u32));
+                }
+                _ => {
+                    let mut items: Vec<u32> = model.underlying_vector().iter().copied().collect();
+                    items.reverse();
+                    model.set_keyed(items, |i| *i);
+                }
+            }
+        }
+        let upstream_items: Vec<u32> = upstream
+            .borrow_mut()
+            .underlying_vector()
+            .iter()
+            .copied()
+            .collect();
+        let model = derived.borrow_observable_as();
+        let model = ListModelPrivateAPIs(&*model);
+        match model.changes_since_version(version) {
+            Changes::Incremental(changes) => {
+                for change in changes {
+                    match change.clone() {
+                        Change::Splice {
+                            remove_range,
+                            replace_with,
+                        } => {
+                            replayed.splice(remove_range, replace_with);
+                        }
+                        Change::Remove { index } => {
+                            replayed.remove(index);
+                        }
+                        Change::Insert { index, value } => replayed.insert(index, value),
+                        Change::Move { from, to } => {
+                            let value = replayed.remove(from);
+                            replayed.insert(to, value);
+                        }
+                    }
+                }
+            }
+            Changes::Reset => panic!("derived list reset"),
+        }
+        version = model.get_version();
+        model.set_listener_version(listener, version);
+        let current: Vec<U> = model.0.underlying_vector().iter().cloned().collect();
+        assert_eq!(current, expected(&upstream_items), "step {step}");
+        assert_eq!(replayed, current, "step {step}");
+    }
+}
+
+#[test]
+fn adaptors() {
+    let upstream = ReactiveCell::new(ListModel::from_iter([3, 1, 4, 1, 5].into_iter()));
+    check_adaptor(
+        &upstream,
+        &upstream.as_observable().filtered(|i| i % 2 == 0),
+        |items| items.iter().copied().filter(|i| i % 2 == 0).collect(),
+    );
+    check_adaptor(
+        &upstream,
+        &upstream.as_observable().mapped(|i| i.to_string()),
+        |items| items.iter().map(|i| i.to_string()).collect(),
+    );
+    check_adaptor(
+        &upstream,
+        &upstream
+            .as_observable()
+            .sorted_by(|a, b| (a / 3).cmp(&(b / 3))),
+        |items| {
+            let mut items = items.to_vec();
+            items.sort_by_key(|i| i / 3);
+            items
+        },
+    );
+    check_adaptor(
+        &upstream,
+        &upstream
+            .as_observable()
+            .filtered(|i| *i > 5)
+            .sorted_by(|a, b| b.cmp(a)),
+        |items| {
+            let mut items: Vec<u32> = items.iter().copied().filter(|i| *i > 5).collect();
+            items.sort_by(|a, b| b.cmp(a));
+            items
+        },
+    );
+}
+
+#[test]
+fn filter_refresh() {
+    let threshold = Cell::new(2);
+    let upstream = ReactiveCell::new(ListModel::from_iter(0..5u32));
+    let derived = upstream.as_observable().filtered(|i| *i >= threshold.get());
+    let items = || -> Vec<u32> {
+        let model = derived.borrow_observable_as();
+        model.underlying_vector().iter().copied().collect()
+    };
+    assert_eq!(items(), [2, 3, 4]);
+    threshold.set(4);
+    derived.refresh_all();
+    assert_eq!(items(), [4]);
+    threshold.set(0);
+    derived.refresh_item(1);
+    assert_eq!(items(), [1, 4]);
+}
+
+#[test]
+fn derived_list_catches_up_after_overlapping_borrows() {
+    let upstream = ReactiveCell::new(ListModel::from_iter(0..2u32));
+    let derived = upstream.as_observable().mapped(|i| i * 10);
+    let outer = derived.borrow_observable_as();
+    let version = derived.get_version();
+    upstream.borrow_mut().push(2);
+    // The output can't change while `outer` is held.
+    assert_eq!(derived.borrow_observable_as().len(), 2);
+    assert!(derived.get_version() == version);
+    drop(outer);
+    assert!(derived.get_version() > version);
+    assert_eq!(derived.borrow_observable_as().len(), 3);
+}
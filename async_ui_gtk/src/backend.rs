@@ -1,4 +1,4 @@
-use std::{future::Future, rc::Rc};
+use std::{future::Future, pin::Pin, rc::Rc, time::Duration};
 
 use async_ui_core::{backend::BackendTrait, vnode::VNode};
 use scoped_tls::scoped_thread_local;
@@ -27,6 +27,10 @@ impl BackendTrait for Backend {
 
     fn initialize() {}
 
+    fn sleep(duration: Duration) -> Pin<Box<dyn Future<Output = ()>>> {
+        glib::timeout_future(duration)
+    }
+
     fn get_vnode_key(
     ) -> &'static scoped_tls::ScopedKey<std::rc::Rc<async_ui_core::vnode::VNode<Self>>> {
         &VNODE_TLS
@@ -1,3 +1,5 @@
+use std::rc::Rc;
+
 use futures_lite::FutureExt;
 use glib::Cast;
 use gtk::traits::ButtonExt;
@@ -8,7 +10,7 @@ use crate::{
 };
 
 use super::{
-    events::{EventsManager, QueuedEvent},
+    events::{Events, EventsManager, QueuedEvent},
     ElementFuture,
 };
 
@@ -19,15 +21,59 @@ pub struct ButtonProps<'c> {
     pub on_press: Option<&'c mut (dyn FnMut(PressEvent) + 'c)>,
 }
 
-pub async fn button<'c>(
+/// A button whose presses can be awaited, instead of handled in `on_press`.
+///
+/// ```ignore
+/// let ok = Button::new();
+/// fragment((
+///     ok.render(ButtonProps {
+///         children: fragment((text(&"OK"),)),
+///         ..Default::default()
+///     }),
+///     async {
+///         ok.clicks().next().await;
+///         // The user pressed OK.
+///     },
+/// ))
+/// ```
+pub struct Button {
+    presses: Rc<EventsManager<PressEvent>>,
+}
+impl Default for Button {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Button {
+    pub fn new() -> Self {
+        Self {
+            presses: EventsManager::new(),
+        }
+    }
+    /// Render the button. `on_press`, if given, is still called too.
+    pub async fn render<'c>(&self, props: ButtonProps<'c>) {
+        render_button(props, Some(&self.presses)).await
+    }
+    /// The presses of the rendered button.
+    pub fn clicks(&self) -> Events<'_, PressEvent> {
+        Events::new(&self.presses)
+    }
+}
+
+pub async fn button<'c>(props: ButtonProps<'c>) {
+    render_button(props, None).await
+}
+
+async fn render_button<'c>(
     ButtonProps {
         children,
         mut on_press,
     }: ButtonProps<'c>,
+    handle: Option<&EventsManager<PressEvent>>,
 ) {
     let button = gtk::Button::new();
     let manager = EventsManager::new();
-    if on_press.is_some() {
+    if on_press.is_some() || handle.is_some() {
         let mgr = manager.clone();
         button.connect_clicked(move |_b| mgr.add_event(QueuedEvent::Click));
     }
@@ -39,6 +85,9 @@ pub async fn button<'c>(
                 for event in events.drain(..) {
                     match event {
                         QueuedEvent::Click => {
+                            if let Some(handle) = handle {
+                                handle.forward(PressEvent {});
+                            }
                             on_press.as_mut().map(|f| f(PressEvent {}));
                         }
                         _ => {}
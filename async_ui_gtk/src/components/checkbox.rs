@@ -0,0 +1,81 @@
+use futures_lite::FutureExt;
+use glib::Cast;
+use gtk::traits::CheckButtonExt;
+use observables::{ObservableAs, ObservableAsExt};
+
+use crate::widget::{WidgetOp, WrappedWidget};
+
+use super::{
+    events::{EventsManager, QueuedEvent},
+    ElementFuture,
+};
+
+pub struct CheckboxChangeEvent {
+    button: gtk::CheckButton,
+}
+impl CheckboxChangeEvent {
+    pub fn get_value(&self) -> bool {
+        self.button.is_active()
+    }
+}
+
+#[derive(Default)]
+pub struct CheckboxProps<'c> {
+    pub value: Option<&'c (dyn ObservableAs<bool> + 'c)>,
+    pub on_change: Option<&'c mut (dyn FnMut(CheckboxChangeEvent) + 'c)>,
+    pub label: Option<&'c (dyn ObservableAs<str> + 'c)>,
+}
+
+pub async fn checkbox<'c>(
+    CheckboxProps {
+        value,
+        mut on_change,
+        label,
+    }: CheckboxProps<'c>,
+) {
+    let value = value.unwrap_or(&false);
+    let label = label.unwrap_or(&"");
+    let check_button = gtk::CheckButton::new();
+    let manager = EventsManager::new();
+    if on_change.is_some() {
+        let mgr = manager.clone();
+        check_button.connect_toggled(move |_b| mgr.add_event(QueuedEvent::Toggle));
+    }
+    ElementFuture::new(
+        (async {
+            manager.grab_waker().await;
+            loop {
+                let mut events = manager.get_queue().await;
+                for event in events.drain(..) {
+                    // Setting the value from `value` toggles too. Only report what the user did.
+                    let changed = check_button.is_active() != *value.borrow_observable_as();
+                    if let (QueuedEvent::Toggle, true, Some(f)) =
+                        (event, changed, on_change.as_mut())
+                    {
+                        f(CheckboxChangeEvent {
+                            button: check_button.clone(),
+                        });
+                    }
+                }
+            }
+        })
+        .or(async {
+            loop {
+                check_button.set_active(*value.borrow_observable_as());
+                value.until_change().await;
+            }
+        })
+        .or(async {
+            loop {
+                check_button.set_label(Some(&*label.borrow_observable_as()));
+                label.until_change().await;
+            }
+        }),
+        WrappedWidget {
+            widget: check_button.clone().upcast(),
+            inner_widget: check_button.clone().upcast(),
+            op: WidgetOp::NoChild,
+        },
+    )
+    .await;
+}
@@ -3,10 +3,13 @@ use std::{
     collections::VecDeque,
     future::poll_fn,
     ops::AddAssign,
+    pin::Pin,
     rc::Rc,
-    task::{Poll, Waker},
+    task::{Context, Poll, Waker},
 };
 
+use futures_lite::Stream;
+
 pub(super) enum QueuedEvent {
     Click,
     MouseDown,
@@ -18,19 +21,28 @@ pub(super) enum QueuedEvent {
     KeyDown,
     Focus,
     Blur,
+    Bind(gtk::ListItem),
+    Unbind(gtk::ListItem),
+    SelectionChange,
+    Toggle,
 }
 
-pub(super) struct EventsManager {
-    inner: RefCell<EventHandlerInner>,
+/// Queues events from handlers until the component's task handles them.
+///
+/// Component handles (like [Button][super::Button]) use one holding their own event type.
+pub(super) struct EventsManager<T = QueuedEvent> {
+    inner: RefCell<EventHandlerInner<T>>,
 }
-struct EventHandlerInner {
-    events: VecDeque<QueuedEvent>,
+struct EventHandlerInner<T> {
+    events: VecDeque<T>,
     waker: Option<Waker>,
     version: u64,
     last_version: u64,
+    /// How many [Events] streams read from this.
+    streams: usize,
 }
 
-impl EventsManager {
+impl<T> EventsManager<T> {
     pub fn new() -> Rc<Self> {
         Rc::new(Self {
             inner: RefCell::new(EventHandlerInner {
@@ -38,10 +50,11 @@ impl EventsManager {
                 waker: None,
                 version: 1,
                 last_version: 1,
+                streams: 0,
             }),
         })
     }
-    pub fn add_event(&self, event: QueuedEvent) {
+    pub fn add_event(&self, event: T) {
         let bm = &mut *self.inner.borrow_mut();
         bm.events.push_back(event);
         bm.version.add_assign(1);
@@ -49,7 +62,15 @@ impl EventsManager {
             waker.wake_by_ref();
         }
     }
-    fn borrow_queue_mut<'b>(&'b self) -> Option<RefMut<'b, VecDeque<QueuedEvent>>> {
+    /// Queue `event` for the [Events] streams, or drop it if there are none.
+    ///
+    /// Components use this for their handles, so events nobody is waiting for don't pile up.
+    pub fn forward(&self, event: T) {
+        if self.inner.borrow().streams > 0 {
+            self.add_event(event);
+        }
+    }
+    fn borrow_queue_mut<'b>(&'b self) -> Option<RefMut<'b, VecDeque<T>>> {
         let mut bm = self.inner.borrow_mut();
         if bm.last_version < bm.version {
             bm.last_version = bm.version;
@@ -68,7 +89,18 @@ impl EventsManager {
         let waker = poll_fn(|cx| Poll::Ready(cx.waker().clone())).await;
         self.set_waker(waker);
     }
-    pub async fn get_queue<'b>(&'b self) -> RefMut<'b, VecDeque<QueuedEvent>> {
+    /// Take the next event, or wake the current task when one comes.
+    fn poll_event(&self, cx: &mut Context<'_>) -> Poll<T> {
+        let mut bm = self.inner.borrow_mut();
+        match bm.events.pop_front() {
+            Some(event) => Poll::Ready(event),
+            None => {
+                bm.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+    pub async fn get_queue<'b>(&'b self) -> RefMut<'b, VecDeque<T>> {
         poll_fn(|_cx| match self.borrow_queue_mut() {
             Some(q) => Poll::Ready(q),
             None => Poll::Pending,
@@ -76,3 +108,32 @@ impl EventsManager {
         .await
     }
 }
+
+/// The events a component forwards to its handle, like [Button::clicks][super::Button::clicks].
+///
+/// Only events that happen while a stream exists are delivered; the ones before are dropped.
+/// Each event goes to only one stream, so keep one stream per kind of event at a time.
+pub struct Events<'h, T> {
+    manager: &'h EventsManager<T>,
+}
+impl<'h, T> Events<'h, T> {
+    pub(super) fn new(manager: &'h EventsManager<T>) -> Self {
+        manager.inner.borrow_mut().streams += 1;
+        Self { manager }
+    }
+}
+impl<'h, T> Drop for Events<'h, T> {
+    fn drop(&mut self) {
+        let mut inner = self.manager.inner.borrow_mut();
+        inner.streams -= 1;
+        if inner.streams == 0 {
+            inner.events.clear();
+        }
+    }
+}
+impl<'h, T> Stream for Events<'h, T> {
+    type Item = T;
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.manager.poll_event(cx).map(Some)
+    }
+}
@@ -1,26 +1,27 @@
-use std::{
-    cell::RefCell,
-    collections::{HashMap, VecDeque},
-    future::IntoFuture,
-    rc::Rc,
-};
+use std::{collections::HashMap, future::IntoFuture, rc::Rc};
 
 use async_task::Task;
-pub use async_ui_core::list::ListModel;
+pub use async_ui_core::list::{ListModel, ListObservableExt};
 use async_ui_core::{
     backend::BackendTrait,
-    executor::spawn_local,
-    list::{Change, ListModelPrivateAPIs},
+    executor::spawn_local_in,
+    list::{Change, Changes, ListModelPrivateAPIs},
     vnode::{
         node_concrete::{ConcreteNodeVNode, RefNode},
         VNodeTrait, WithVNode,
     },
 };
-use futures_lite::pin;
+use futures_lite::{future::pending, pin, FutureExt};
 use glib::{Cast, Object, ObjectExt, StaticType};
-use gtk::{gio::ListStore, NoSelection, Widget};
+use gtk::{
+    gio::ListStore,
+    prelude::{ListModelExt, SelectionModelExt},
+    MultiSelection, NoSelection, SelectionModel, SingleSelection, Widget,
+};
 use im_rc::Vector;
-use observables::{ObservableAs, ObservableAsExt};
+use observables::{
+    cell::ReactiveCell, Listenable, Observable, ObservableAs, ObservableAsExt, Version,
+};
 use scoped_async_spawn::SpawnGuard;
 
 use crate::{
@@ -28,7 +29,10 @@ use crate::{
     widget::{SingleChildWidgetOp, WidgetOp, WrappedWidget},
 };
 
-use super::ElementFuture;
+use super::{
+    events::{EventsManager, QueuedEvent},
+    ElementFuture,
+};
 
 glib::wrapper! {
     pub struct KeyObject(ObjectSubclass<imp::KeyObject>);
@@ -95,15 +99,31 @@ impl KeyObject {
     }
 }
 
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum SelectionMode {
+    #[default]
+    None,
+    Single,
+    Multiple,
+}
+
 pub struct ListProps<'c, T: Clone, F: IntoFuture<Output = ()>> {
     pub data: Option<&'c dyn ObservableAs<ListModel<T>>>,
     pub render: Option<&'c dyn Fn(T) -> F>,
+    pub selection_mode: SelectionMode,
+    /// Indices of the selected items, in increasing order.
+    ///
+    /// The list keeps this up to date as the user selects items or the data changes,
+    /// and selects whatever you put in it.
+    pub selection: Option<&'c ReactiveCell<Vec<usize>>>,
 }
 impl<'c, T: Clone, F: IntoFuture<Output = ()>> Default for ListProps<'c, T, F> {
     fn default() -> Self {
         Self {
             data: None,
             render: None,
+            selection_mode: SelectionMode::None,
+            selection: None,
         }
     }
 }
@@ -127,11 +147,34 @@ impl SingleChildWidgetOp for ListItemWidgetOp {
 
 struct ItemAndTask<T> {
     item: T,
-    task: Option<Task<()>>,
+    /// The row the item is rendered in, while it is bound to one.
+    task: Option<(gtk::ListItem, Task<()>)>,
 }
 
+fn get_selection(model: &SelectionModel) -> Vec<usize> {
+    let bitset = model.selection();
+    (0..bitset.size())
+        .map(|n| bitset.nth(n as u32) as usize)
+        .collect()
+}
+fn set_selection(model: &SelectionModel, selected: &[usize]) {
+    model.unselect_all();
+    for index in selected.iter() {
+        model.select_item(*index as u32, false);
+    }
+}
+
+/// A list of items, rendered lazily by a `gtk::ListView`.
+///
+/// Only the rows GTK needs to show are rendered,
+/// and their futures are dropped when GTK recycles the row.
 pub async fn list<'c, T: Clone, F: IntoFuture<Output = ()>>(
-    ListProps { data, render }: ListProps<'c, T, F>,
+    ListProps {
+        data,
+        render,
+        selection_mode,
+        selection,
+    }: ListProps<'c, T, F>,
 ) {
     let (data, render) = match (data, render) {
         (Some(d), Some(r)) => (d, r),
@@ -140,7 +183,16 @@ pub async fn list<'c, T: Clone, F: IntoFuture<Output = ()>>(
         }
     };
     let store = ListStore::new(KeyObject::static_type());
-    let selection_model = NoSelection::new(Some(&store));
+    let selection_model: SelectionModel = match selection_mode {
+        SelectionMode::None => NoSelection::new(Some(&store)).upcast(),
+        SelectionMode::Single => {
+            let model = SingleSelection::new(Some(&store));
+            model.set_autoselect(false);
+            model.set_can_unselect(true);
+            model.upcast()
+        }
+        SelectionMode::Multiple => MultiSelection::new(Some(&store)).upcast(),
+    };
     let factory = gtk::SignalListItemFactory::new();
     let list_view = gtk::ListView::new(Some(&selection_model), Some(&factory));
     let scrolled_window = gtk::ScrolledWindow::builder()
@@ -151,6 +203,20 @@ pub async fn list<'c, T: Clone, F: IntoFuture<Output = ()>>(
         .build();
     let dummy_widget = gtk::Label::new(None);
 
+    let manager = EventsManager::new();
+    {
+        let mgr = manager.clone();
+        factory.connect_bind(move |_fac, li| mgr.add_event(QueuedEvent::Bind(li.to_owned())));
+        let mgr = manager.clone();
+        factory.connect_unbind(move |_fac, li| mgr.add_event(QueuedEvent::Unbind(li.to_owned())));
+    }
+    if selection.is_some() {
+        let mgr = manager.clone();
+        selection_model.connect_selection_changed(move |_model, _position, _n_items| {
+            mgr.add_event(QueuedEvent::SelectionChange)
+        });
+    }
+
     let dummy_widget_copy = dummy_widget.clone();
     let inside = async move {
         let mut current_id = 0;
@@ -164,21 +230,9 @@ pub async fn list<'c, T: Clone, F: IntoFuture<Output = ()>>(
 
         let guard = SpawnGuard::new();
         pin!(guard);
-        let bind_channel = Rc::new(RefCell::new(VecDeque::new()));
-
-        let bind_channel_bind = bind_channel.clone();
-        factory.connect_bind(move |_fac, li| {
-            bind_channel_bind
-                .borrow_mut()
-                .push_back((li.to_owned(), true));
-        });
-        let bind_channel_unbind = bind_channel.clone();
-        factory.connect_unbind(move |_fac, li| {
-            bind_channel_unbind
-                .borrow_mut()
-                .push_back((li.to_owned(), false));
-        });
 
+        let listener;
+        let mut seen_ids;
         let mut last_version = {
             {
                 for item in start.iter() {
@@ -195,111 +249,158 @@ pub async fn list<'c, T: Clone, F: IntoFuture<Output = ()>>(
                 }
             }
             let model = ListModelPrivateAPIs(&start_bm);
-            model
-                .total_listeners()
-                .set(model.total_listeners().get() + 1);
+            listener = model.add_listener();
+            seen_ids = model.item_ids().clone();
             model.get_version()
         };
         drop(start_bm);
         let _guard = scopeguard::guard((), |_| {
             let b = data.borrow_observable_as();
-            let model = ListModelPrivateAPIs(&*b);
-            model
-                .total_listeners()
-                .set(model.total_listeners().get() - 1);
+            ListModelPrivateAPIs(&*b).remove_listener(listener);
         });
+        let mut selection_version = Version::new_null();
+        let mut events = Vec::new();
+        manager.grab_waker().await;
         loop {
             {
-                for (list_item, is_bind) in bind_channel.borrow_mut().drain(..) {
-                    let key = list_item.item().unwrap().downcast::<KeyObject>().unwrap();
-                    if is_bind {
-                        match keys_map.get_mut(&key.get_key_id()) {
-                            Some(ItemAndTask { item, task }) if task.is_none() => {
-                                let fut = render(item.to_owned()).into_future();
-                                let vnode = ConcreteNodeVNode::<Backend>::new(
-                                    RefNode::Parent {
-                                        parent: WrappedWidget {
-                                            widget: dummy_widget_copy.clone().upcast(),
-                                            inner_widget: list_item.to_owned().upcast(),
-                                            op: WidgetOp::SingleChild(&ListItemWidgetOp),
-                                        },
-                                    },
-                                    parent_context.clone(),
-                                );
-                                let fut = WithVNode::<Backend, _>::new(fut, Rc::new(vnode.into()));
-                                let fut = guard.as_mut().convert_future(fut);
-                                *task = Some(spawn_local(fut));
+                let model = &*data.borrow_observable_as();
+                let model_priv = ListModelPrivateAPIs(model);
+                if model_priv.get_version() != last_version {
+                    let catch_up;
+                    let changes: Box<dyn Iterator<Item = &Change<T>>> =
+                        match model_priv.changes_since_version(last_version) {
+                            Changes::Incremental(changes) => Box::new(changes),
+                            // Items still in the list keep their rows.
+                            Changes::Reset => {
+                                catch_up = model_priv.changes_from_ids(&seen_ids);
+                                Box::new(catch_up.iter())
                             }
-                            _ => panic!("invalid bind"),
                         };
-                    } else {
-                        match keys_map.get_mut(&key.get_key_id()) {
-                            Some(ItemAndTask { task, .. }) if task.is_some() => {
-                                *task = None;
+                    for change in changes {
+                        match change {
+                            Change::Splice {
+                                remove_range,
+                                replace_with,
+                            } => {
+                                let n_items = ExactSizeIterator::len(remove_range);
+                                let mut right = keys_list.split_off(remove_range.start);
+                                let mut new_right = right.split_off(n_items);
+                                for key in right.into_iter() {
+                                    store.remove(remove_range.start as u32);
+                                    keys_map.remove(&key);
+                                }
+                                for item in replace_with.iter().rev() {
+                                    current_id += 1;
+                                    keys_map.insert(
+                                        current_id,
+                                        ItemAndTask {
+                                            item: item.to_owned(),
+                                            task: None,
+                                        },
+                                    );
+                                    new_right.push_front(current_id);
+                                    store.insert(
+                                        remove_range.start as u32,
+                                        &KeyObject::new(current_id),
+                                    );
+                                }
+                                keys_list.append(new_right);
                             }
-                            Some(ItemAndTask { .. }) => panic!("invalid unbind"),
-                            _ => {}
-                        }
-                    }
-                }
-            }
-            data.until_change().await;
-            {
-                let model = &*data.borrow_observable_as();
-                let model_priv = ListModelPrivateAPIs(model);
-                let changes = model_priv.changes_since_version(last_version);
-                for change in changes {
-                    match change {
-                        Change::Splice {
-                            remove_range,
-                            replace_with,
-                        } => {
-                            let n_items = ExactSizeIterator::len(remove_range);
-                            let mut right = keys_list.split_off(remove_range.start);
-                            let mut new_right = right.split_off(n_items);
-                            for key in right.into_iter() {
-                                store.remove(remove_range.start as u32);
-                                keys_map.remove(&key);
+                            Change::Remove { index } => {
+                                let key = keys_list.remove(*index);
+                                store.remove(*index as u32);
+                                keys_map.remove(&key).unwrap();
                             }
-                            for item in replace_with.iter().rev() {
+                            Change::Insert { index, value } => {
                                 current_id += 1;
                                 keys_map.insert(
                                     current_id,
                                     ItemAndTask {
-                                        item: item.to_owned(),
+                                        item: value.to_owned(),
                                         task: None,
                                     },
                                 );
-                                new_right.push_front(current_id);
-                                store
-                                    .insert(remove_range.start as u32, &KeyObject::new(current_id));
+                                keys_list.insert(*index, current_id);
+                                store.insert(*index as u32, &KeyObject::new(current_id));
+                            }
+                            Change::Move { from, to } => {
+                                let key = keys_list.remove(*from);
+                                keys_list.insert(*to, key);
+                                // Rotate the same objects in one splice. GTK then keeps the
+                                // moved row bound, so the item's task keeps running.
+                                let start = (*from).min(*to);
+                                let mut objects: Vec<Object> = (start..=(*from).max(*to))
+                                    .map(|index| store.item(index as u32).unwrap())
+                                    .collect();
+                                if from < to {
+                                    objects.rotate_left(1);
+                                } else {
+                                    objects.rotate_right(1);
+                                }
+                                store.splice(start as u32, objects.len() as u32, &objects);
                             }
-                            keys_list.append(new_right);
-                        }
-                        Change::Remove { index } => {
-                            let key = keys_list.remove(*index);
-                            store.remove(*index as u32);
-                            keys_map.remove(&key).unwrap();
-                        }
-                        Change::Insert { index, value } => {
-                            current_id += 1;
-                            keys_map.insert(
-                                current_id,
-                                ItemAndTask {
-                                    item: value.to_owned(),
-                                    task: None,
-                                },
-                            );
-                            keys_list.insert(*index, current_id);
-                            store.insert(*index as u32, &KeyObject::new(current_id));
                         }
                     }
+                    last_version = model_priv.get_version();
+                    seen_ids = model_priv.item_ids().clone();
+                    model_priv.set_listener_version(listener, last_version);
+                }
+            }
+            for event in events.drain(..) {
+                let (list_item, is_bind) = match event {
+                    QueuedEvent::Bind(list_item) => (list_item, true),
+                    QueuedEvent::Unbind(list_item) => (list_item, false),
+                    _ => continue,
+                };
+                let key = list_item.item().unwrap().downcast::<KeyObject>().unwrap();
+                // Items that were removed from the store have no entry anymore.
+                let Some(ItemAndTask { item, task }) = keys_map.get_mut(&key.get_key_id()) else {
+                    continue;
+                };
+                let bound_here = matches!(task, Some((bound, _)) if *bound == list_item);
+                if is_bind && !bound_here {
+                    let fut = render(item.to_owned()).into_future();
+                    let vnode = ConcreteNodeVNode::<Backend>::new(
+                        RefNode::Parent {
+                            parent: WrappedWidget {
+                                widget: dummy_widget_copy.clone().upcast(),
+                                inner_widget: list_item.to_owned().upcast(),
+                                op: WidgetOp::SingleChild(&ListItemWidgetOp),
+                            },
+                        },
+                        parent_context.clone(),
+                    );
+                    let fut = WithVNode::<Backend, _>::new(fut, Rc::new(vnode.into()));
+                    let fut = guard.as_mut().convert_future(fut);
+                    // GTK may bind the item to its new row before unbinding the old one.
+                    *task = Some((list_item, spawn_local_in(parent_context, fut)));
+                } else if !is_bind && bound_here {
+                    *task = None;
                 }
-                last_version = model_priv.get_version();
-                model_priv
-                    .pending_listeners()
-                    .set(model_priv.pending_listeners().get() - 1);
             }
+            if let Some(cell) = selection {
+                let observable = cell.as_observable();
+                if observable.get_version() != selection_version {
+                    set_selection(&selection_model, &observable.borrow_observable());
+                }
+                let current = get_selection(&selection_model);
+                if *observable.borrow_observable() != current {
+                    cell.set(current);
+                }
+                selection_version = observable.get_version();
+            }
+            let selection_changed = async {
+                match selection {
+                    Some(cell) => {
+                        ObservableAsExt::<Vec<usize>>::until_change(&cell.as_observable()).await
+                    }
+                    None => pending().await,
+                }
+            };
+            data.until_change()
+                .or(selection_changed)
+                .or(async { events.extend(manager.get_queue().await.drain(..)) })
+                .await;
         }
     };
 
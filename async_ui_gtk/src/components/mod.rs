@@ -1,14 +1,19 @@
 mod events;
 
 mod button;
+mod checkbox;
 mod list;
+mod radio;
 mod text;
 mod text_input;
 mod view;
-pub use button::{button, ButtonProps};
-pub use list::{list, ListModel, ListProps};
+pub use button::{button, Button, ButtonProps, PressEvent};
+pub use checkbox::{checkbox, CheckboxChangeEvent, CheckboxProps};
+pub use events::Events;
+pub use list::{list, ListModel, ListObservableExt, ListProps, SelectionMode};
+pub use radio::{radio_button, radio_group, RadioGroupProps, RadioProps};
 pub use text::text;
-pub use text_input::{text_input, TextInputProps};
+pub use text_input::{text_input, TextInput, TextInputEvent, TextInputProps};
 pub use view::{view, ViewProps};
 
 use async_ui_core::{
@@ -30,9 +35,10 @@ use crate::{backend::Backend, widget::WrappedWidget};
 
 pin_project! {
     pub struct ElementFuture<F: Future> {
+        // Dropped first, so the node is removed whole before its children drop.
+        inner: ElementFutureInner,
         #[pin]
         future: F,
-        inner: ElementFutureInner
     }
 }
 struct ElementFutureInner {
@@ -46,13 +52,16 @@ struct MyAndParentVNodes {
 
 impl Drop for ElementFutureInner {
     fn drop(&mut self) {
-        if let Some(MyAndParentVNodes { parent, .. }) = &self.vnodes {
+        if let Some(MyAndParentVNodes { my, parent }) = &self.vnodes {
+            if let VNode::ConcreteNode(my) = &**my {
+                my.detach();
+            }
             parent.del_child_node(Default::default());
         }
     }
 }
 impl<F: Future> ElementFuture<F> {
-    fn new(future: F, node: WrappedWidget) -> Self {
+    pub(crate) fn new(future: F, node: WrappedWidget) -> Self {
         Self {
             future,
             inner: ElementFutureInner { node, vnodes: None },
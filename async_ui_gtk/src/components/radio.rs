@@ -0,0 +1,103 @@
+use std::{cell::RefCell, rc::Rc};
+
+use futures_lite::FutureExt;
+use glib::Cast;
+use gtk::traits::CheckButtonExt;
+use observables::{cell::ReactiveCell, ObservableAs, ObservableAsExt};
+
+use crate::{
+    get_context, get_keyed_observable_context, provide_keyed_observable_context,
+    widget::{WidgetOp, WrappedWidget},
+    with_context, Fragment,
+};
+
+use super::ElementFuture;
+
+#[derive(Default)]
+pub struct RadioProps<'c, E: Clone + PartialEq + 'static> {
+    pub value: E,
+    pub label: Option<&'c str>,
+}
+
+#[derive(Default)]
+pub struct RadioGroupProps<'c, E: Clone + PartialEq + 'static> {
+    pub children: Fragment<'c>,
+    pub value: Option<&'c (dyn ObservableAs<E> + 'c)>,
+    pub on_change: Option<&'c mut (dyn FnMut(E) + 'c)>,
+}
+
+/// Context the group provides to its buttons.
+///
+/// The group's `value` is provided as an observable context keyed by this private type,
+/// so it can't clash with other contexts of type `E`.
+struct RadioGroup<E: 'static> {
+    /// The first button of the group. The others join its group so that only one is active.
+    leader: RefCell<Option<gtk::CheckButton>>,
+    /// The value of the button the user just activated, for the group to report.
+    picked: ReactiveCell<Option<E>>,
+}
+
+pub async fn radio_group<'c, E: Clone + PartialEq + 'static>(
+    RadioGroupProps {
+        children,
+        value,
+        mut on_change,
+    }: RadioGroupProps<'c, E>,
+) {
+    let value = if let Some(v) = value { v } else { return };
+    let group = Rc::new(RadioGroup {
+        leader: RefCell::new(None),
+        picked: ReactiveCell::new(None),
+    });
+    let group_1 = group.clone();
+    provide_keyed_observable_context::<RadioGroup<E>, _, E>(with_context(children, group), value)
+        .or(async {
+            loop {
+                group_1.picked.as_observable().until_change().await;
+                let picked = group_1.picked.borrow_mut().take();
+                if let (Some(picked), Some(on_change)) = (picked, on_change.as_mut()) {
+                    on_change(picked);
+                }
+            }
+        })
+        .await;
+}
+
+pub async fn radio_button<'c, E: Clone + PartialEq + 'static>(
+    RadioProps { value, label }: RadioProps<'c, E>,
+) {
+    let ctx = get_context::<RadioGroup<E>>();
+    let selected = get_keyed_observable_context::<RadioGroup<E>, E>();
+    let check_button = gtk::CheckButton::new();
+    if let Some(label) = label {
+        check_button.set_label(Some(label));
+    }
+    {
+        let mut leader = ctx.leader.borrow_mut();
+        match &*leader {
+            Some(leader) => check_button.set_group(Some(leader)),
+            None => *leader = Some(check_button.clone()),
+        }
+    }
+    let selected_1 = selected.clone();
+    let value_1 = value.clone();
+    check_button.connect_toggled(move |b| {
+        if b.is_active() && *selected_1.borrow_observable_as() != value_1 {
+            ctx.picked.set(Some(value_1.clone()));
+        }
+    });
+    ElementFuture::new(
+        async {
+            loop {
+                check_button.set_active(*selected.borrow_observable_as() == value);
+                selected.until_change().await;
+            }
+        },
+        WrappedWidget {
+            widget: check_button.clone().upcast(),
+            inner_widget: check_button.clone().upcast(),
+            op: WidgetOp::NoChild,
+        },
+    )
+    .await;
+}
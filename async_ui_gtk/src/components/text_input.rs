@@ -1,3 +1,5 @@
+use std::rc::Rc;
+
 use futures_lite::FutureExt;
 use glib::Cast;
 use gtk::{
@@ -9,7 +11,7 @@ use observables::{ObservableAs, ObservableAsExt};
 use crate::widget::{WidgetOp, WrappedWidget};
 
 use super::{
-    events::{EventsManager, QueuedEvent},
+    events::{Events, EventsManager, QueuedEvent},
     ElementFuture,
 };
 
@@ -69,7 +71,52 @@ pub struct TextInputProps<'c> {
     pub placeholder: Option<&'c (dyn ObservableAs<str> + 'c)>,
 }
 
-pub async fn text_input<'c>(
+/// A text input whose events can be awaited, instead of handled in the `on_*` props.
+pub struct TextInput {
+    changes: Rc<EventsManager<TextInputEvent>>,
+    submits: Rc<EventsManager<TextInputEvent>>,
+    blurs: Rc<EventsManager<TextInputEvent>>,
+    focuses: Rc<EventsManager<TextInputEvent>>,
+}
+impl Default for TextInput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl TextInput {
+    pub fn new() -> Self {
+        Self {
+            changes: EventsManager::new(),
+            submits: EventsManager::new(),
+            blurs: EventsManager::new(),
+            focuses: EventsManager::new(),
+        }
+    }
+    /// Render the input. The `on_*` props, if given, are still called too.
+    pub async fn render<'c>(&self, props: TextInputProps<'c>) {
+        render_text_input(props, Some(self)).await
+    }
+    /// Each time the text changes.
+    pub fn changes(&self) -> Events<'_, TextInputEvent> {
+        Events::new(&self.changes)
+    }
+    /// Each time the user activates a one-line input, usually by pressing Enter.
+    pub fn submits(&self) -> Events<'_, TextInputEvent> {
+        Events::new(&self.submits)
+    }
+    pub fn blurs(&self) -> Events<'_, TextInputEvent> {
+        Events::new(&self.blurs)
+    }
+    pub fn focuses(&self) -> Events<'_, TextInputEvent> {
+        Events::new(&self.focuses)
+    }
+}
+
+pub async fn text_input<'c>(props: TextInputProps<'c>) {
+    render_text_input(props, None).await
+}
+
+async fn render_text_input<'c>(
     TextInputProps {
         text,
         mut on_change_text,
@@ -79,6 +126,7 @@ pub async fn text_input<'c>(
         multiline,
         placeholder,
     }: TextInputProps<'c>,
+    handle: Option<&TextInput>,
 ) {
     let text = text.unwrap_or(&"");
     let placeholder = placeholder.unwrap_or(&"");
@@ -98,7 +146,7 @@ pub async fn text_input<'c>(
         false => {
             let entry_buffer = gtk::EntryBuffer::new(None);
             let entry = gtk::Entry::new();
-            if on_submit.is_some() {
+            if on_submit.is_some() || handle.is_some() {
                 let mgr = manager.clone();
                 entry.connect_activate(move |_e| {
                     mgr.add_event(QueuedEvent::Submit);
@@ -110,13 +158,13 @@ pub async fn text_input<'c>(
             entry_node = Some(entry);
         }
     };
-    if on_change_text.is_some() {
+    if on_change_text.is_some() || handle.is_some() {
         let mgr = manager.clone();
         buffer.connect_changed(move || {
             mgr.add_event(QueuedEvent::Input);
         });
     }
-    if on_blur.is_some() || on_focus.is_some() {
+    if on_blur.is_some() || on_focus.is_some() || handle.is_some() {
         let mgr = manager.clone();
         let focus_controller = gtk::EventControllerFocus::new();
         input.add_controller(&focus_controller);
@@ -134,16 +182,23 @@ pub async fn text_input<'c>(
             loop {
                 let mut events = manager.get_queue().await;
                 for event in events.drain(..) {
-                    let text_input_event = TextInputEvent {
-                        buffer: buffer.clone(),
-                    };
-                    match event {
-                        QueuedEvent::Input => on_change_text.as_mut().map(|f| f(text_input_event)),
-                        QueuedEvent::Blur => on_blur.as_mut().map(|f| f(text_input_event)),
-                        QueuedEvent::Focus => on_focus.as_mut().map(|f| f(text_input_event)),
-                        QueuedEvent::Submit => on_submit.as_mut().map(|f| f(text_input_event)),
-                        _ => None,
+                    let (callback, queue) = match event {
+                        QueuedEvent::Input => (&mut on_change_text, handle.map(|h| &h.changes)),
+                        QueuedEvent::Blur => (&mut on_blur, handle.map(|h| &h.blurs)),
+                        QueuedEvent::Focus => (&mut on_focus, handle.map(|h| &h.focuses)),
+                        QueuedEvent::Submit => (&mut on_submit, handle.map(|h| &h.submits)),
+                        _ => continue,
                     };
+                    if let Some(queue) = queue {
+                        queue.forward(TextInputEvent {
+                            buffer: buffer.clone(),
+                        });
+                    }
+                    if let Some(f) = callback {
+                        f(TextInputEvent {
+                            buffer: buffer.clone(),
+                        });
+                    }
                 }
             }
         })
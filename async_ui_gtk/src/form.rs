@@ -0,0 +1,95 @@
+//! Bind form [Field]s to inputs, and make the inputs insensitive while the [Form] submits.
+//!
+//! See [async_ui_core::form] for validation and state.
+pub use async_ui_core::form::{Field, Form, FormField};
+use futures_lite::{FutureExt, StreamExt};
+use glib::Cast;
+use gtk::traits::WidgetExt;
+use observables::{ObservableAs, ObservableAsExt};
+
+use crate::{
+    components::{
+        checkbox, radio_group, CheckboxChangeEvent, CheckboxProps, ElementFuture, RadioGroupProps,
+        TextInput, TextInputProps,
+    },
+    fragment,
+    widget::{gtk_box::GtkBoxOp, WidgetOp, WrappedWidget},
+    Fragment,
+};
+
+/// Render `children` in a vertical [gtk::Box], which is insensitive (with every input inside) while `form` submits.
+///
+/// This also keeps the errors of the form's fields up to date as their values change.
+pub async fn form_view<'c>(form: &'c Form<'_>, children: Fragment<'c>) {
+    let b = gtk::Box::new(gtk::Orientation::Vertical, 0);
+    let b_1 = b.clone();
+    let submitting = form.submitting();
+    ElementFuture::new(
+        children.or(form.run()).or(async {
+            loop {
+                b_1.set_sensitive(!*submitting.borrow_observable_as());
+                submitting.until_change().await;
+            }
+        }),
+        WrappedWidget {
+            widget: b.clone().upcast(),
+            inner_widget: b.upcast(),
+            op: WidgetOp::MultiChild(&GtkBoxOp),
+        },
+    )
+    .await;
+}
+
+/// A [text_input][crate::components::text_input] for `field`. The `text` prop is replaced by the field's value.
+pub async fn text_field<'c>(field: &'c Field<'_, str>, props: TextInputProps<'c>) {
+    let input = TextInput::new();
+    input
+        .render(TextInputProps {
+            text: Some(field.value()),
+            ..props
+        })
+        .or(async {
+            let mut changes = input.changes();
+            while let Some(ev) = changes.next().await {
+                field.input(ev.get_text());
+            }
+        })
+        .or(async {
+            let mut blurs = input.blurs();
+            while blurs.next().await.is_some() {
+                field.touch();
+            }
+        })
+        .await
+}
+
+/// A [checkbox] for `field`, labelled `label`.
+pub async fn checkbox_field(field: &Field<'_, bool>, label: &str) {
+    let mut on_change = |ev: CheckboxChangeEvent| {
+        field.input(ev.get_value());
+        field.touch();
+    };
+    checkbox(CheckboxProps {
+        value: Some(field.value()),
+        on_change: Some(&mut on_change),
+        label: Some(&label),
+    })
+    .await
+}
+
+/// A [radio_group] for `field`, with the [radio_button][crate::components::radio_button]s in `children`.
+pub async fn radio_field<'c, E: Clone + PartialEq + 'static>(
+    field: &'c Field<'_, E>,
+    children: Fragment<'c>,
+) {
+    let mut on_change = |value: E| {
+        field.input(value);
+        field.touch();
+    };
+    radio_group(RadioGroupProps {
+        children: fragment((children,)),
+        value: Some(field.value()),
+        on_change: Some(&mut on_change),
+    })
+    .await
+}
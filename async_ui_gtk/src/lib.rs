@@ -1,18 +1,30 @@
 use std::{future::IntoFuture, rc::Rc};
 
-use async_ui_core::fragment::Fragment as FragmentBase;
+use async_ui_core::{
+    error_boundary::ErrorBoundary, fragment::Fragment as FragmentBase,
+    vnode::node_suspense::Suspense,
+};
 use backend::Backend;
+use observables::ObservableAs;
 mod backend;
 pub mod components;
 mod executor;
+pub mod form;
 mod mount;
+pub mod router;
+pub mod time;
 mod widget;
 pub use gtk;
 pub use mount::{mount, mount_at};
 
+pub use async_ui_core::context::{ContextCell, Contexts, ObservableContext};
+pub use async_ui_core::error_boundary::{ComponentError, ComponentResult};
+pub use async_ui_core::executor::Priority;
 pub use futures_lite;
 
 pub type Fragment<'c> = FragmentBase<'c, Backend>;
+/// One child of a [Fragment], for giving it a [Priority] with [with_priority][async_ui_core::fragment::Child::with_priority].
+pub type Child<'c> = async_ui_core::fragment::Child<'c, Backend>;
 
 pub mod __private_macro_only {
     pub use super::Fragment;
@@ -38,3 +50,74 @@ pub async fn with_context<I: IntoFuture, T: 'static>(future: I, value: Rc<T>) ->
 pub fn get_context<T: 'static>() -> Rc<T> {
     async_ui_core::vnode::node_context::get_context::<Backend, T>()
 }
+pub fn try_get_context<T: 'static>() -> Option<Rc<T>> {
+    async_ui_core::vnode::node_context::try_get_context::<Backend, T>()
+}
+pub fn get_context_or_default<T: Default + 'static>() -> Rc<T> {
+    async_ui_core::vnode::node_context::get_context_or_default::<Backend, T>()
+}
+/// Like [with_context], but provides several values at once.
+pub async fn with_contexts<I: IntoFuture>(future: I, values: Contexts) -> I::Output {
+    use async_ui_core::vnode::node_context::WithContext;
+    WithContext::<Backend, _>::new_many(future.into_future(), values).await
+}
+/// Provide `value` to `children`, waking consumers when it changes.
+pub async fn provide_observable_context<I: IntoFuture, T: ToOwned + ?Sized + 'static>(
+    children: I,
+    value: &dyn ObservableAs<T>,
+) -> I::Output {
+    async_ui_core::vnode::node_context::provide_observable_context::<Backend, _, _>(children, value)
+        .await
+}
+pub fn get_observable_context<T: ToOwned + ?Sized + 'static>() -> Rc<ObservableContext<T>> {
+    async_ui_core::vnode::node_context::get_observable_context::<Backend, T>()
+}
+pub fn try_get_observable_context<T: ToOwned + ?Sized + 'static>(
+) -> Option<Rc<ObservableContext<T>>> {
+    async_ui_core::vnode::node_context::try_get_observable_context::<Backend, T>()
+}
+/// Like [provide_observable_context], but keyed by the type `K` so it doesn't clash with other contexts of type `T`.
+pub async fn provide_keyed_observable_context<
+    K: 'static,
+    I: IntoFuture,
+    T: ToOwned + ?Sized + 'static,
+>(
+    children: I,
+    value: &dyn ObservableAs<T>,
+) -> I::Output {
+    async_ui_core::vnode::node_context::provide_keyed_observable_context::<Backend, K, _, _>(
+        children, value,
+    )
+    .await
+}
+pub fn get_keyed_observable_context<K: 'static, T: ToOwned + ?Sized + 'static>(
+) -> Rc<ObservableContext<T, K>> {
+    async_ui_core::vnode::node_context::get_keyed_observable_context::<Backend, K, T>()
+}
+pub fn try_get_keyed_observable_context<K: 'static, T: ToOwned + ?Sized + 'static>(
+) -> Option<Rc<ObservableContext<T, K>>> {
+    async_ui_core::vnode::node_context::try_get_keyed_observable_context::<Backend, K, T>()
+}
+
+/// Render `children`, replacing them with `fallback` if they panic or return an error.
+pub async fn error_boundary<I, G, FB>(children: I, fallback: FB)
+where
+    I: IntoFuture,
+    I::Output: ComponentResult,
+    G: IntoFuture<Output = ()>,
+    FB: FnOnce(ComponentError) -> G,
+{
+    ErrorBoundary::<Backend, _, _, _>::new(children, fallback).await
+}
+
+/// Render `fallback` until `children` put up their first node, then show `children` instead.
+pub async fn suspense<I: IntoFuture, J: IntoFuture<Output = ()>>(
+    children: I,
+    fallback: J,
+) -> I::Output {
+    Suspense::<Backend, _, _>::new(children, fallback).await
+}
+/// Make the enclosing [suspense] show its children now, even if they haven't rendered anything.
+pub fn suspense_ready() {
+    async_ui_core::vnode::node_suspense::mark_ready::<Backend>()
+}
@@ -0,0 +1,89 @@
+//! Render different components depending on a location path.
+//!
+//! GTK has no URL bar. Create a [Router] (usually [Router::new_in_memory]),
+//! give it to the app with [with_context][crate::with_context], and call [navigate] from inside.
+//! Use [route_stack] to show each route as a page of a [gtk::Stack],
+//! or [route_outlet] to put the matched content in place.
+use std::rc::Rc;
+
+pub use async_ui_core::router::{History, MemoryHistory, Params, RoutePattern, Router};
+use async_ui_core::{
+    router as base,
+    vnode::node_concrete::{RefNode, WithConcreteNode},
+};
+use glib::Cast;
+
+use crate::{
+    backend::Backend,
+    components::ElementFuture,
+    widget::{gtk_box::GtkBoxOp, WidgetOp, WrappedWidget},
+};
+
+pub type Route<'c> = base::Route<'c, Backend>;
+
+/// The [Router] given to the enclosing components.
+pub fn get_router() -> Rc<Router> {
+    base::get_router::<Backend>()
+}
+
+/// Go to `to` using the enclosing [Router].
+pub fn navigate(to: &str) {
+    get_router().navigate(to)
+}
+
+/// The params matched by the enclosing routes, including those of outer outlets.
+pub fn route_params() -> Params {
+    base::route_params::<Backend>()
+}
+
+/// Render the first route in `routes` that matches the current path.
+///
+/// A route ending in `*rest` leaves the rest of the path to the outlets inside it.
+pub async fn route_outlet<'c>(routes: &[Route<'c>]) {
+    base::route_outlet(routes).await
+}
+
+/// Like [route_outlet], but in a [gtk::Stack] with one page per route.
+///
+/// The matched route is rendered in its page, and the stack switches to that page,
+/// so the stack's transitions play when the route changes.
+/// The page named `"none"` is shown when no route matches.
+pub async fn route_stack<'c>(routes: &[Route<'c>]) {
+    let stack = gtk::Stack::new();
+    let add_page = |name: &str| {
+        let page = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        stack.add_named(&page, Some(name));
+        page
+    };
+    let pages: Vec<gtk::Box> = (0..routes.len())
+        .map(|index| add_page(&index.to_string()))
+        .collect();
+    let empty_page = add_page("none");
+    let stack_copy = stack.clone();
+    let outlet = base::route_outlet_with(routes, |index, content| {
+        let (name, page) = match index {
+            Some(index) => (index.to_string(), pages[index].clone()),
+            None => ("none".to_owned(), empty_page.clone()),
+        };
+        stack_copy.set_visible_child_name(&name);
+        WithConcreteNode::<Backend, _>::new(
+            content,
+            RefNode::Parent {
+                parent: WrappedWidget {
+                    widget: page.clone().upcast(),
+                    inner_widget: page.upcast(),
+                    op: WidgetOp::MultiChild(&GtkBoxOp),
+                },
+            },
+        )
+    });
+    ElementFuture::new(
+        outlet,
+        WrappedWidget {
+            widget: stack.clone().upcast(),
+            inner_widget: stack.upcast(),
+            op: WidgetOp::NoChild,
+        },
+    )
+    .await;
+}
@@ -0,0 +1,19 @@
+//! Timers on the GLib main loop: [sleep], [interval], and [Debounced] and [Throttled] observables.
+use std::time::Duration;
+
+use async_ui_core::time as base;
+
+use crate::backend::Backend;
+
+pub type Debounced<T> = base::Debounced<Backend, T>;
+pub type Throttled<T> = base::Throttled<Backend, T>;
+
+/// Complete after `duration`.
+pub async fn sleep(duration: Duration) {
+    base::sleep::<Backend>(duration).await
+}
+
+/// Call `tick` every `period`. Never completes.
+pub async fn interval<F: FnMut()>(period: Duration, tick: F) {
+    base::interval::<Backend, F>(period, tick).await
+}
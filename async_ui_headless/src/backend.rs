@@ -0,0 +1,43 @@
+use std::{future::Future, pin::Pin, rc::Rc, time::Duration};
+
+use async_ui_core::{backend::BackendTrait, vnode::VNode};
+use scoped_tls::{scoped_thread_local, ScopedKey};
+
+use crate::{
+    executor::{schedule, set_executor_future},
+    node::Node,
+    time::Sleep,
+};
+
+pub struct Backend;
+impl BackendTrait for Backend {
+    type Node = Node;
+
+    fn add_child_node(
+        parent: &mut Self::Node,
+        child: &mut Self::Node,
+        insert_before_sibling: Option<&Self::Node>,
+    ) {
+        parent.insert_before(child, insert_before_sibling);
+    }
+
+    fn del_child_node(parent: &mut Self::Node, child: &mut Self::Node) {
+        parent.remove_child(child);
+    }
+    fn drive_executor<F: Future<Output = ()> + 'static>(fut: F) {
+        set_executor_future(Box::new(fut) as _);
+        schedule();
+    }
+    fn initialize() {}
+    fn sleep(duration: Duration) -> Pin<Box<dyn Future<Output = ()>>> {
+        Box::pin(Sleep::new(duration))
+    }
+
+    fn get_vnode_key() -> &'static ScopedKey<Rc<VNode<Self>>> {
+        &VNODE
+    }
+}
+
+scoped_thread_local!(
+    static VNODE: Rc<VNode<Backend>>
+);
@@ -0,0 +1,20 @@
+use observables::{ObservableAs, ObservableAsExt};
+
+use crate::node::Node;
+
+use super::ElementFuture;
+
+pub async fn text(text: &dyn ObservableAs<str>) {
+    let node = Node::new_text("");
+    let node_copy = node.clone();
+    ElementFuture::new(
+        async {
+            loop {
+                node_copy.set_data(&text.borrow_observable_as());
+                text.until_change().await;
+            }
+        },
+        node,
+    )
+    .await;
+}
@@ -0,0 +1,25 @@
+use crate::{node::Node, Fragment};
+
+use super::ElementFuture;
+
+pub struct ViewProps<'c> {
+    pub children: Fragment<'c>,
+    pub element_tag: &'c str,
+}
+impl<'c> Default for ViewProps<'c> {
+    fn default() -> Self {
+        Self {
+            children: Default::default(),
+            element_tag: "div",
+        }
+    }
+}
+pub async fn view<'c>(
+    ViewProps {
+        children,
+        element_tag,
+    }: ViewProps<'c>,
+) {
+    let elem = Node::new_element(element_tag);
+    ElementFuture::new(children, elem).await;
+}
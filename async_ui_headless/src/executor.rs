@@ -0,0 +1,53 @@
+use std::{
+    cell::{Cell, RefCell},
+    future::Future,
+    pin::Pin,
+    task::{Context, Waker},
+};
+
+thread_local! {
+    static EXECUTOR: ExecutorSingleton = ExecutorSingleton::new()
+}
+struct ExecutorSingleton {
+    waker: Waker,
+    future: RefCell<Option<Pin<Box<dyn Future<Output = ()>>>>>,
+    scheduled: Cell<bool>,
+}
+
+impl ExecutorSingleton {
+    fn new() -> Self {
+        let waker = waker_fn::waker_fn(schedule);
+        Self {
+            waker,
+            future: RefCell::new(None),
+            scheduled: Cell::new(false),
+        }
+    }
+}
+pub(crate) fn set_executor_future(future: Box<dyn Future<Output = ()>>) {
+    EXECUTOR.with(|exe| *exe.future.borrow_mut() = Some(future.into()))
+}
+
+/// Poll the mounted UI until no task is awake anymore.
+///
+/// Nothing runs in the background: wakes only mark the executor as scheduled,
+/// and the work happens the next time this function is called.
+pub fn run_until_idle() {
+    EXECUTOR.with(|exe| {
+        while exe.scheduled.replace(false) {
+            let mut cx = Context::from_waker(&exe.waker);
+            if let Some(fu) = exe.future.borrow_mut().as_mut() {
+                let _ = fu.as_mut().poll(&mut cx);
+            }
+        }
+    })
+}
+
+/// Whether some task has been woken since the last [run_until_idle].
+pub fn is_idle() -> bool {
+    EXECUTOR.with(|exe| !exe.scheduled.get())
+}
+
+pub(crate) fn schedule() {
+    EXECUTOR.with(|exe| exe.scheduled.set(true))
+}
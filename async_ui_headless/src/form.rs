@@ -0,0 +1,11 @@
+//! Form fields with validation, and their submission. See [async_ui_core::form].
+use futures_lite::FutureExt;
+
+pub use async_ui_core::form::{Field, Form, FormField};
+
+use crate::Fragment;
+
+/// Render `children`, keeping the errors of the form's fields up to date as their values change.
+pub async fn form_view<'c>(form: &'c Form<'_>, children: Fragment<'c>) {
+    children.or(form.run()).await
+}
@@ -0,0 +1,39 @@
+use std::{future::IntoFuture, rc::Rc};
+
+use async_ui_core::{
+    mount as core_mount,
+    vnode::{
+        node_concrete::{ConcreteNodeVNode, RefNode},
+        WithVNode,
+    },
+};
+
+use crate::{backend::Backend, executor::run_until_idle, node::Node};
+
+pub fn mount_at<F: IntoFuture<Output = ()> + 'static>(root: F, node: Node) {
+    let fut = WithVNode::new(
+        root.into_future(),
+        Rc::new(
+            ConcreteNodeVNode::new(
+                RefNode::<Backend>::Parent { parent: node },
+                Default::default(),
+            )
+            .into(),
+        ),
+    );
+    core_mount::<Backend, _>(fut)
+}
+
+/// Mount into a fresh `<body>` node and return that node.
+pub fn mount<F: IntoFuture<Output = ()> + 'static>(root: F) -> Node {
+    let node = Node::new_element("body");
+    mount_at(root, node.clone());
+    node
+}
+
+/// Mount, then run until idle and return the HTML inside the root node.
+pub fn render_to_string<F: IntoFuture<Output = ()> + 'static>(root: F) -> String {
+    let node = mount(root);
+    run_until_idle();
+    node.inner_html()
+}
@@ -0,0 +1,250 @@
+use std::{
+    cell::{Ref, RefCell},
+    collections::BTreeMap,
+    fmt,
+    hash::{Hash, Hasher},
+    rc::{Rc, Weak},
+};
+
+/// A node of the in-memory tree.
+///
+/// This is a cheap handle (like `web_sys::Node`); clones refer to the same node.
+#[derive(Clone)]
+pub struct Node(Rc<RefCell<NodeInner>>);
+
+struct NodeInner {
+    kind: NodeKind,
+    children: Vec<Node>,
+    parent: Weak<RefCell<NodeInner>>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum NodeKind {
+    Element {
+        tag: String,
+        attributes: BTreeMap<String, String>,
+    },
+    Text(String),
+    Comment(String),
+}
+
+impl Node {
+    fn from_kind(kind: NodeKind) -> Self {
+        Self(Rc::new(RefCell::new(NodeInner {
+            kind,
+            children: Vec::new(),
+            parent: Weak::new(),
+        })))
+    }
+    pub fn new_element(tag: &str) -> Self {
+        Self::from_kind(NodeKind::Element {
+            tag: tag.to_owned(),
+            attributes: BTreeMap::new(),
+        })
+    }
+    pub fn new_text(data: &str) -> Self {
+        Self::from_kind(NodeKind::Text(data.to_owned()))
+    }
+    pub fn new_comment(data: &str) -> Self {
+        Self::from_kind(NodeKind::Comment(data.to_owned()))
+    }
+    pub fn kind(&self) -> Ref<'_, NodeKind> {
+        Ref::map(self.0.borrow(), |inner| &inner.kind)
+    }
+    pub fn tag(&self) -> Option<String> {
+        match &self.0.borrow().kind {
+            NodeKind::Element { tag, .. } => Some(tag.to_owned()),
+            _ => None,
+        }
+    }
+    pub fn children(&self) -> Vec<Node> {
+        self.0.borrow().children.clone()
+    }
+    pub fn parent(&self) -> Option<Node> {
+        self.0.borrow().parent.upgrade().map(Node)
+    }
+    pub fn first_child(&self) -> Option<Node> {
+        self.0.borrow().children.first().cloned()
+    }
+    pub fn previous_sibling(&self) -> Option<Node> {
+        let parent = self.parent()?;
+        let index = parent.position_of(self)?;
+        let sibling = parent
+            .0
+            .borrow()
+            .children
+            .get(index.checked_sub(1)?)
+            .cloned();
+        sibling
+    }
+    pub fn next_sibling(&self) -> Option<Node> {
+        let parent = self.parent()?;
+        let index = parent.position_of(self)?;
+        let sibling = parent.0.borrow().children.get(index + 1).cloned();
+        sibling
+    }
+    pub fn attribute(&self, name: &str) -> Option<String> {
+        match &self.0.borrow().kind {
+            NodeKind::Element { attributes, .. } => attributes.get(name).cloned(),
+            _ => None,
+        }
+    }
+    pub fn set_attribute(&self, name: &str, value: &str) {
+        match &mut self.0.borrow_mut().kind {
+            NodeKind::Element { attributes, .. } => {
+                attributes.insert(name.to_owned(), value.to_owned());
+            }
+            _ => panic!("set attribute failed: not an element"),
+        }
+    }
+    pub fn remove_attribute(&self, name: &str) {
+        match &mut self.0.borrow_mut().kind {
+            NodeKind::Element { attributes, .. } => {
+                attributes.remove(name);
+            }
+            _ => panic!("remove attribute failed: not an element"),
+        }
+    }
+    /// Set the data of a text or comment node.
+    pub fn set_data(&self, data: &str) {
+        match &mut self.0.borrow_mut().kind {
+            NodeKind::Text(d) | NodeKind::Comment(d) => {
+                d.clear();
+                d.push_str(data);
+            }
+            _ => panic!("set data failed: not a text or comment node"),
+        }
+    }
+    /// Concatenation of all text nodes in this subtree.
+    pub fn text_content(&self) -> String {
+        let mut out = String::new();
+        self.write_text_content(&mut out);
+        out
+    }
+    fn write_text_content(&self, out: &mut String) {
+        let inner = self.0.borrow();
+        match &inner.kind {
+            NodeKind::Text(data) => out.push_str(data),
+            NodeKind::Comment(_) => {}
+            NodeKind::Element { .. } => inner
+                .children
+                .iter()
+                .for_each(|ch| ch.write_text_content(out)),
+        }
+    }
+    /// The children of this node serialized as HTML.
+    pub fn inner_html(&self) -> String {
+        self.0
+            .borrow()
+            .children
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+    fn position_of(&self, child: &Node) -> Option<usize> {
+        self.0.borrow().children.iter().position(|ch| ch == child)
+    }
+    /// Insert `child` before `before`, or at the end if `before` is `None`.
+    ///
+    /// Like the DOM, this moves `child` out of its current parent first.
+    pub fn insert_before(&self, child: &Node, before: Option<&Node>) {
+        if let Some(old_parent) = child.parent() {
+            old_parent.remove_child(child);
+        }
+        let index = match before {
+            Some(before) => self
+                .position_of(before)
+                .expect("insert failed: reference node is not a child"),
+            None => self.0.borrow().children.len(),
+        };
+        self.0.borrow_mut().children.insert(index, child.clone());
+        child.0.borrow_mut().parent = Rc::downgrade(&self.0);
+    }
+    pub fn append_child(&self, child: &Node) {
+        self.insert_before(child, None)
+    }
+    pub fn remove_child(&self, child: &Node) {
+        let index = self
+            .position_of(child)
+            .expect("remove failed: node is not a child");
+        self.0.borrow_mut().children.remove(index);
+        child.0.borrow_mut().parent = Weak::new();
+    }
+}
+
+impl PartialEq for Node {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+impl Eq for Node {}
+impl Hash for Node {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Rc::as_ptr(&self.0).hash(state)
+    }
+}
+
+fn write_escaped(f: &mut fmt::Formatter<'_>, s: &str, attribute: bool) -> fmt::Result {
+    for c in s.chars() {
+        match c {
+            '&' => f.write_str("&amp;")?,
+            '<' => f.write_str("&lt;")?,
+            '>' => f.write_str("&gt;")?,
+            '"' if attribute => f.write_str("&quot;")?,
+            c => fmt::Write::write_char(f, c)?,
+        }
+    }
+    Ok(())
+}
+
+/// Elements that have no end tag (and no children) in HTML.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Write comment data so that it can't end the comment early.
+fn write_comment(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_str("<!--")?;
+    for c in s.chars() {
+        match c {
+            '<' => f.write_str("&lt;")?,
+            '>' => f.write_str("&gt;")?,
+            c => fmt::Write::write_char(f, c)?,
+        }
+    }
+    f.write_str("-->")
+}
+
+/// Serializes the subtree as HTML.
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = self.0.borrow();
+        match &inner.kind {
+            NodeKind::Element { tag, attributes } => {
+                write!(f, "<{tag}")?;
+                for (name, value) in attributes.iter() {
+                    write!(f, " {name}=\"")?;
+                    write_escaped(f, value, true)?;
+                    f.write_str("\"")?;
+                }
+                f.write_str(">")?;
+                if VOID_ELEMENTS.contains(&tag.as_str()) {
+                    return Ok(());
+                }
+                for child in inner.children.iter() {
+                    fmt::Display::fmt(child, f)?;
+                }
+                write!(f, "</{tag}>")
+            }
+            NodeKind::Text(data) => write_escaped(f, data, false),
+            NodeKind::Comment(data) => write_comment(f, data),
+        }
+    }
+}
+
+impl fmt::Debug for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
@@ -0,0 +1,34 @@
+//! Render different components depending on a location path.
+//!
+//! There is no URL bar here. Create a [Router] (usually [Router::new_in_memory]),
+//! give it to the app with [with_context][crate::with_context], and call [Router::navigate] on it.
+use std::rc::Rc;
+
+use async_ui_core::router as base;
+pub use async_ui_core::router::{History, MemoryHistory, Params, RoutePattern, Router};
+
+use crate::backend::Backend;
+
+pub type Route<'c> = base::Route<'c, Backend>;
+
+/// The [Router] given to the enclosing components.
+pub fn get_router() -> Rc<Router> {
+    base::get_router::<Backend>()
+}
+
+/// Go to `to` using the enclosing [Router].
+pub fn navigate(to: &str) {
+    get_router().navigate(to)
+}
+
+/// The params matched by the enclosing routes, including those of outer outlets.
+pub fn route_params() -> Params {
+    base::route_params::<Backend>()
+}
+
+/// Render the first route in `routes` that matches the current path.
+///
+/// A route ending in `*rest` leaves the rest of the path to the outlets inside it.
+pub async fn route_outlet<'c>(routes: &[Route<'c>]) {
+    base::route_outlet(routes).await
+}
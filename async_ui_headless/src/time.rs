@@ -0,0 +1,121 @@
+//! Timers on virtual time.
+//!
+//! Time only passes when the test calls [advance_time], so tests with timers are fast and repeatable.
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use async_ui_core::time as base;
+
+use crate::{backend::Backend, executor::run_until_idle};
+
+pub type Debounced<T> = base::Debounced<Backend, T>;
+pub type Throttled<T> = base::Throttled<Backend, T>;
+
+thread_local! {
+    static TIMERS: RefCell<Timers> = RefCell::new(Timers::default());
+}
+#[derive(Default)]
+struct Timers {
+    now: Duration,
+    next_id: u64,
+    /// Id, deadline, and waker of each pending [Sleep].
+    pending: Vec<(u64, Duration, Waker)>,
+}
+
+pub(crate) struct Sleep {
+    id: u64,
+    deadline: Duration,
+}
+impl Sleep {
+    pub(crate) fn new(duration: Duration) -> Self {
+        TIMERS.with(|timers| {
+            let mut timers = timers.borrow_mut();
+            timers.next_id += 1;
+            Self {
+                id: timers.next_id,
+                deadline: timers.now + duration,
+            }
+        })
+    }
+}
+impl Future for Sleep {
+    type Output = ();
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        TIMERS.with(|timers| {
+            let mut timers = timers.borrow_mut();
+            if timers.now >= self.deadline {
+                return Poll::Ready(());
+            }
+            match timers.pending.iter_mut().find(|(id, ..)| *id == self.id) {
+                Some((_, _, waker)) => waker.clone_from(cx.waker()),
+                None => timers
+                    .pending
+                    .push((self.id, self.deadline, cx.waker().to_owned())),
+            }
+            Poll::Pending
+        })
+    }
+}
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        let _ = TIMERS.try_with(|timers| {
+            timers
+                .borrow_mut()
+                .pending
+                .retain(|(id, ..)| *id != self.id)
+        });
+    }
+}
+
+/// How much virtual time has passed.
+pub fn now() -> Duration {
+    TIMERS.with(|timers| timers.borrow().now)
+}
+
+/// Move virtual time forward by `duration`, firing the timers that come due on the way.
+///
+/// The UI is run until idle after each timer, so timers started by other timers fire too
+/// if they come due before the end.
+pub fn advance_time(duration: Duration) {
+    let end = now() + duration;
+    loop {
+        run_until_idle();
+        let due = TIMERS.with(|timers| {
+            let mut timers = timers.borrow_mut();
+            let next = timers
+                .pending
+                .iter()
+                .map(|(_, deadline, _)| *deadline)
+                .filter(|deadline| *deadline <= end)
+                .min()?;
+            timers.now = next;
+            let (due, pending) = std::mem::take(&mut timers.pending)
+                .into_iter()
+                .partition(|(_, deadline, _)| *deadline <= next);
+            timers.pending = pending;
+            Some(due)
+        });
+        match due {
+            Some(due) => due
+                .into_iter()
+                .for_each(|(_, _, waker): (u64, Duration, Waker)| waker.wake()),
+            None => break,
+        }
+    }
+    TIMERS.with(|timers| timers.borrow_mut().now = end);
+}
+
+/// Complete after `duration` of virtual time.
+pub async fn sleep(duration: Duration) {
+    base::sleep::<Backend>(duration).await
+}
+
+/// Call `tick` every `period` of virtual time. Never completes.
+pub async fn interval<F: FnMut()>(period: Duration, tick: F) {
+    base::interval::<Backend, F>(period, tick).await
+}
@@ -0,0 +1,70 @@
+use std::rc::Rc;
+
+use async_ui_headless::{
+    animation::{easing, ManualClock, Spring, SpringConfig, Tween},
+    mount, run_until_idle,
+};
+use observables::ObservableAs;
+
+fn value(observable: &dyn ObservableAs<f64>) -> f64 {
+    *observable.borrow_observable_as()
+}
+
+#[test]
+fn tween_follows_clock_and_retargets() {
+    let clock = Rc::new(ManualClock::new(0.0));
+    let tween = Rc::new(Tween::new(0.0, 100.0, easing::linear));
+    let (clock_1, tween_1) = (clock.clone(), tween.clone());
+    let _root = mount(async move { tween_1.run(&*clock_1).await });
+    run_until_idle();
+
+    tween.set_target(100.0);
+    run_until_idle();
+    // The first frame marks the start.
+    clock.advance(16.0);
+    run_until_idle();
+    assert_eq!(value(&*tween), 0.0);
+    clock.advance(50.0);
+    run_until_idle();
+    assert_eq!(value(&*tween), 50.0);
+
+    // Interrupted halfway: the new animation starts from 50.
+    tween.set_target(0.0);
+    run_until_idle();
+    clock.advance(16.0);
+    run_until_idle();
+    clock.advance(25.0);
+    run_until_idle();
+    assert_eq!(value(&*tween), 37.5);
+
+    clock.advance(1000.0);
+    run_until_idle();
+    assert_eq!(value(&*tween), 0.0);
+    assert!(!*tween.is_animating().borrow_observable_as());
+}
+
+#[test]
+fn spring_keeps_velocity_and_settles() {
+    let spring = Spring::new(0.0, SpringConfig::default());
+    spring.set_target(100.0);
+    let mut now = 0.0;
+    for _ in 0..10 {
+        spring.step(now);
+        now += 16.0;
+    }
+    let moving = spring.velocity();
+    assert!(value(&spring) > 0.0 && moving > 0.0);
+
+    // Retargeting behind it doesn't stop it dead.
+    spring.set_target(0.0);
+    spring.step(now);
+    assert!(spring.velocity() > 0.0);
+
+    for _ in 0..500 {
+        now += 16.0;
+        spring.step(now);
+    }
+    assert_eq!(value(&spring), 0.0);
+    assert_eq!(spring.velocity(), 0.0);
+    assert!(!*spring.is_animating().borrow_observable_as());
+}
@@ -0,0 +1,107 @@
+use std::{cell::Cell, future::poll_fn, rc::Rc, task::Poll};
+
+use async_ui_headless::{
+    components::{text, view, ViewProps},
+    error_boundary, fragment,
+    futures_lite::FutureExt,
+    mount, run_until_idle, Child, Fragment, Priority,
+};
+use observables::{cell::ReactiveCell, Listenable, ObservableAs, ObservableAsExt};
+
+#[test]
+fn returned_error_shows_fallback() {
+    let fail = Rc::new(ReactiveCell::new(false));
+    let fail_1 = fail.clone();
+    let root = mount(async move {
+        fragment((
+            text(&"before "),
+            error_boundary(
+                async {
+                    let content = view(ViewProps {
+                        children: fragment((text(&"content"),)),
+                        element_tag: "p",
+                    });
+                    let fail = async {
+                        fail_1.as_observable().until_change().await;
+                        Err::<(), _>("broken")
+                    };
+                    async {
+                        content.await;
+                        Ok(())
+                    }
+                    .or(fail)
+                    .await
+                },
+                |e| async move { text(&format!("failed: {e}")).await },
+            ),
+            text(&" after"),
+        ))
+        .await;
+    });
+    run_until_idle();
+    assert_eq!(root.inner_html(), "before <p>content</p> after");
+    fail.set(true);
+    run_until_idle();
+    assert_eq!(root.inner_html(), "before failed: broken after");
+}
+
+#[test]
+fn panic_in_nested_child_shows_fallback() {
+    let explode = Rc::new(ReactiveCell::new(false));
+    let explode_1 = explode.clone();
+    let root = mount(async move {
+        fragment((
+            text(&"a"),
+            error_boundary(
+                fragment((
+                    text(&"b"),
+                    view(ViewProps {
+                        children: fragment((async {
+                            explode_1.as_observable().until_change().await;
+                            panic!("boom");
+                        },)),
+                        element_tag: "p",
+                    }),
+                )),
+                |e| async move { text(&e.to_string()).await },
+            ),
+        ))
+        .await;
+    });
+    run_until_idle();
+    assert_eq!(root.inner_html(), "ab<p></p>");
+    explode.set(true);
+    run_until_idle();
+    assert_eq!(root.inner_html(), "acomponent panicked: boom");
+}
+
+#[test]
+fn panicked_component_is_not_polled_again() {
+    let explode = Rc::new(ReactiveCell::new(false));
+    let polls = Rc::new(Cell::new(0));
+    let (explode_1, polls_1) = (explode.clone(), polls.clone());
+    let root = mount(async move {
+        let component = poll_fn(|cx| {
+            polls_1.set(polls_1.get() + 1);
+            let explode = explode_1.as_observable();
+            if *explode.borrow_observable_as() {
+                // Wake the component before the boundary gets to drop it.
+                cx.waker().wake_by_ref();
+                panic!("boom");
+            }
+            explode.add_waker(cx.waker().clone());
+            Poll::<()>::Pending
+        });
+        // In a more urgent lane than the boundary, so its wake is handled first.
+        let children = Fragment::new_from_vec_child(vec![
+            Child::new(component).with_priority(Priority::UserBlocking)
+        ]);
+        error_boundary(children, |e| async move { text(&e.to_string()).await }).await;
+    });
+    run_until_idle();
+    assert_eq!(polls.get(), 1);
+    explode.set(true);
+    run_until_idle();
+    assert_eq!(polls.get(), 2);
+    assert_eq!(root.inner_html(), "component panicked: boom");
+}
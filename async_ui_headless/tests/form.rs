@@ -0,0 +1,145 @@
+use std::{cell::Cell, rc::Rc};
+
+use async_ui_headless::{
+    components::text,
+    form::{form_view, Field, Form},
+    fragment,
+    futures_lite::{future::zip, FutureExt},
+    mount, run_until_idle,
+};
+use observables::{cell::ReactiveCell, Observable, ObservableAs, ObservableAsExt};
+
+async fn status(field: &Field<'_, str>, form: &Form<'_>) {
+    let flag = |on: bool, c: char| if on { c } else { '-' };
+    let shown = ReactiveCell::new(String::new());
+    let update = async {
+        loop {
+            shown.set(format!(
+                "{}|{}{}{}{}",
+                field.errors().borrow_observable_as().join(","),
+                flag(*field.dirty().borrow_observable_as(), 'd'),
+                flag(*field.touched().borrow_observable_as(), 't'),
+                flag(*field.is_valid().borrow_observable_as(), 'v'),
+                flag(*form.submitting().borrow_observable_as(), 's'),
+            ));
+            field
+                .errors()
+                .until_change()
+                .or(field.dirty().until_change())
+                .or(field.touched().until_change())
+                .or(field.is_valid().until_change())
+                .or(form.submitting().until_change())
+                .await;
+        }
+    };
+    text(&shown.as_observable()).or(update).await
+}
+
+#[test]
+fn validation_and_submit() {
+    let typed = Rc::new(ReactiveCell::new(String::new()));
+    let submit = Rc::new(ReactiveCell::new(()));
+    let reply = Rc::new(ReactiveCell::new(()));
+    let saved = Rc::new(Cell::new(0));
+    let (typed_1, submit_1, reply_1, saved_1) =
+        (typed.clone(), submit.clone(), reply.clone(), saved.clone());
+    let root = mount(async move {
+        let name = ReactiveCell::new(String::new());
+        let reply = &reply_1;
+        let field = Field::from_cell(&name)
+            .validate(|v: &str| match v.is_empty() {
+                true => Err("required".into()),
+                false => Ok(()),
+            })
+            .validate_async(move |v: String| async move {
+                reply.as_observable().until_change().await;
+                match v == "taken" {
+                    true => Err("taken".into()),
+                    false => Ok(()),
+                }
+            });
+        let form = Form::new(&[&field]);
+        let typing = async {
+            loop {
+                let typed = typed_1.as_observable();
+                ObservableAsExt::<str>::until_change(&typed).await;
+                field.input(typed.borrow_observable().clone());
+            }
+        };
+        let submitting = async {
+            loop {
+                submit_1.as_observable().until_change().await;
+                let save = async { reply.as_observable().until_change().await };
+                if form.submit(save).await.is_some() {
+                    saved_1.set(saved_1.get() + 1);
+                }
+            }
+        };
+        form_view(&form, fragment((status(&field, &form), typing, submitting))).await
+    });
+    run_until_idle();
+    assert_eq!(root.inner_html(), "required|----");
+
+    typed.set("taken".into());
+    run_until_idle();
+    assert_eq!(root.inner_html(), "|d---");
+    reply.set(());
+    run_until_idle();
+    assert_eq!(root.inner_html(), "taken|d---");
+
+    typed.set("free".into());
+    run_until_idle();
+    reply.set(());
+    run_until_idle();
+    assert_eq!(root.inner_html(), "|d-v-");
+
+    submit.set(());
+    run_until_idle();
+    assert_eq!(root.inner_html(), "|dt--");
+    reply.set(());
+    run_until_idle();
+    assert_eq!(root.inner_html(), "|dtvs");
+    assert_eq!(saved.get(), 0);
+    reply.set(());
+    run_until_idle();
+    assert_eq!(root.inner_html(), "|dtv-");
+    assert_eq!(saved.get(), 1);
+}
+
+#[test]
+fn submit_cancelled_or_started_twice() {
+    let reply = Rc::new(ReactiveCell::new(()));
+    let saved = Rc::new(Cell::new(0));
+    let submitting = Rc::new(Cell::new(true));
+    let (reply_1, saved_1, submitting_1) = (reply.clone(), saved.clone(), submitting.clone());
+    let _root = mount(async move {
+        let name = ReactiveCell::new(String::from("name"));
+        let reply = &reply_1;
+        let field = Field::<str>::from_cell(&name).validate_async(move |_: String| async move {
+            reply.as_observable().until_change().await;
+            Ok(())
+        });
+        let form = Form::new(&[&field]);
+        let save = || async {
+            saved_1.set(saved_1.get() + 1);
+            reply.as_observable().until_change().await;
+        };
+        // Both start while the other is being validated, but only one saves.
+        let (first, second) = zip(form.submit(save()), form.submit(save())).await;
+        assert!(first.is_some() != second.is_some());
+        // Dropped while saving.
+        let cancel = async {
+            reply.as_observable().until_change().await;
+            None
+        };
+        assert!(form.submit(save()).or(cancel).await.is_none());
+        submitting_1.set(*form.submitting().borrow_observable_as());
+    });
+    run_until_idle();
+    for _ in 0..3 {
+        reply.set(());
+        run_until_idle();
+    }
+    assert_eq!(saved.get(), 2);
+    assert!(!submitting.get());
+}
@@ -0,0 +1,39 @@
+use std::{cell::RefCell, rc::Rc};
+
+use async_ui_headless::{fragment, mount, run_until_idle, with_context, Child, Fragment, Priority};
+use observables::{cell::ReactiveCell, ObservableAsExt};
+
+#[test]
+fn urgent_lanes_run_first() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let wake = Rc::new(ReactiveCell::new(()));
+    let (log_1, wake_1) = (log.clone(), wake.clone());
+    let _root = mount(async move {
+        let watch = |name: &'static str| {
+            let (log, wake) = (log_1.clone(), wake_1.clone());
+            async move {
+                loop {
+                    log.borrow_mut().push(name);
+                    wake.as_observable().until_change().await;
+                }
+            }
+        };
+        Fragment::new_from_vec_child(vec![
+            // The priority in context applies to the children inside.
+            Child::new(with_context(
+                fragment((watch("idle"),)),
+                Rc::new(Priority::Idle),
+            )),
+            Child::new(watch("normal")),
+            Child::new(watch("urgent")).with_priority(Priority::UserBlocking),
+        ])
+        .await
+    });
+    run_until_idle();
+    assert_eq!(*log.borrow(), ["urgent", "normal", "idle"]);
+
+    log.borrow_mut().clear();
+    wake.set(());
+    run_until_idle();
+    assert_eq!(*log.borrow(), ["urgent", "normal", "idle"]);
+}
@@ -0,0 +1,220 @@
+use std::rc::Rc;
+
+use async_ui_headless::{
+    components::{text, view, ViewProps},
+    create_portal, fragment,
+    futures_lite::FutureExt,
+    get_context, get_context_or_default, get_keyed_observable_context, get_observable_context,
+    mount, provide_keyed_observable_context, provide_observable_context, render_to_string,
+    run_until_idle, try_get_context, try_get_keyed_observable_context, with_context, with_contexts,
+    ContextCell, Contexts, Node,
+};
+use observables::{cell::ReactiveCell, ObservableAs, ObservableAsExt};
+
+#[test]
+fn nested_fragments() {
+    let html = render_to_string(async {
+        fragment((
+            text(&"a"),
+            fragment((text(&"b"), fragment((text(&"c"), text(&"d"))))),
+            view(ViewProps {
+                children: fragment((text(&"e"),)),
+                element_tag: "p",
+            }),
+        ))
+        .await;
+    });
+    assert_eq!(html, "abcd<p>e</p>");
+}
+
+#[test]
+fn late_children_keep_position() {
+    let show = Rc::new(ReactiveCell::new(false));
+    let show_1 = show.clone();
+    let root = mount(async move {
+        let late = async {
+            show_1.as_observable().until_change().await;
+            text(&"first").await;
+        };
+        fragment((late, text(&"second"), fragment((async {}, text(&"third"))))).await;
+    });
+    run_until_idle();
+    assert_eq!(root.inner_html(), "secondthird");
+    show.set(true);
+    run_until_idle();
+    assert_eq!(root.inner_html(), "firstsecondthird");
+}
+
+#[test]
+fn text_updates_and_unmount() {
+    let value = Rc::new(ReactiveCell::new(String::from("hello")));
+    let removed = Rc::new(ReactiveCell::new(false));
+    let (value_1, removed_1) = (value.clone(), removed.clone());
+    let root = mount(async move {
+        fragment((
+            view(ViewProps {
+                children: fragment((text(&value_1.as_observable()),)),
+                ..Default::default()
+            }),
+            text(&"temporary").or(removed_1.as_observable().until_change()),
+        ))
+        .await;
+    });
+    run_until_idle();
+    assert_eq!(root.inner_html(), "<div>hello</div>temporary");
+    value.set("bye".into());
+    run_until_idle();
+    assert_eq!(root.inner_html(), "<div>bye</div>temporary");
+    removed.set(true);
+    run_until_idle();
+    assert_eq!(root.inner_html(), "<div>bye</div>");
+}
+
+#[test]
+fn portal() {
+    let root = mount(async {
+        let (mut entry, exit) = create_portal();
+        fragment((
+            view(ViewProps {
+                children: fragment((text(&"before"), exit, text(&"after"))),
+                element_tag: "header",
+            }),
+            entry.mount(fragment((text(&"x"), text(&"y")))),
+            text(&"main"),
+        ))
+        .await;
+    });
+    run_until_idle();
+    assert_eq!(root.inner_html(), "<header>beforexyafter</header>main");
+}
+
+#[test]
+fn context() {
+    struct Greeting(&'static str);
+    let html = render_to_string(async {
+        with_context(
+            fragment((async {
+                let greeting = get_context::<Greeting>();
+                text(&greeting.0).await;
+            },)),
+            Rc::new(Greeting("hi")),
+        )
+        .await;
+    });
+    assert_eq!(html, "hi");
+}
+
+#[test]
+fn optional_and_multiple_contexts() {
+    struct Greeting(&'static str);
+    struct Name(&'static str);
+    impl Default for Name {
+        fn default() -> Self {
+            Self("nobody")
+        }
+    }
+    struct Absent;
+    let html = render_to_string(async {
+        fragment((
+            async {
+                assert!(try_get_context::<Greeting>().is_none());
+                text(&get_context_or_default::<Name>().0).await;
+            },
+            with_contexts(
+                fragment((async {
+                    assert!(try_get_context::<Absent>().is_none());
+                    let greeting = get_context::<Greeting>();
+                    let name = get_context_or_default::<Name>();
+                    fragment((text(&greeting.0), text(&name.0))).await;
+                },)),
+                Contexts::new()
+                    .with(Rc::new(Greeting("hi")))
+                    .with(Rc::new(Name("bob"))),
+            ),
+        ))
+        .await;
+    });
+    assert_eq!(html, "nobodyhibob");
+}
+
+#[test]
+fn swapped_context() {
+    let theme = Rc::new(ContextCell::new(Rc::new("light")));
+    let root = mount(with_context(
+        fragment((async {
+            let theme = get_context::<ContextCell<&'static str>>();
+            loop {
+                text(&*theme.get()).or(theme.until_change()).await;
+            }
+        },)),
+        theme.clone(),
+    ));
+    run_until_idle();
+    assert_eq!(root.inner_html(), "light");
+    theme.swap(Rc::new("dark"));
+    run_until_idle();
+    assert_eq!(root.inner_html(), "dark");
+}
+
+#[test]
+fn observable_context() {
+    let locale = Rc::new(ReactiveCell::new(String::from("en")));
+    let locale_1 = locale.clone();
+    let root = mount(async move {
+        provide_observable_context::<_, str>(
+            fragment((async {
+                let locale = get_observable_context::<str>();
+                text(&*locale).await;
+            },)),
+            &locale_1.as_observable(),
+        )
+        .await;
+    });
+    run_until_idle();
+    assert_eq!(root.inner_html(), "en");
+    locale.set(String::from("fr"));
+    run_until_idle();
+    assert_eq!(root.inner_html(), "fr");
+}
+
+#[test]
+fn keyed_observable_context() {
+    struct Title;
+    let title = Rc::new(ReactiveCell::new(String::from("Home")));
+    let title_1 = title.clone();
+    let root = mount(async move {
+        provide_keyed_observable_context::<Title, _, str>(
+            provide_observable_context::<_, str>(
+                fragment((async {
+                    let title = get_keyed_observable_context::<Title, str>();
+                    // Same `T` as the title, but not shadowed by it.
+                    let locale = get_observable_context::<str>();
+                    assert_eq!(&*locale.borrow_observable_as(), "en");
+                    assert!(try_get_keyed_observable_context::<(u8,), str>().is_none());
+                    text(&*title).await;
+                },)),
+                &"en",
+            ),
+            &title_1.as_observable(),
+        )
+        .await;
+    });
+    run_until_idle();
+    assert_eq!(root.inner_html(), "Home");
+    title.set(String::from("About"));
+    run_until_idle();
+    assert_eq!(root.inner_html(), "About");
+}
+
+#[test]
+fn void_elements_and_comments() {
+    let root = Node::new_element("div");
+    let input = Node::new_element("input");
+    input.set_attribute("value", "a");
+    root.append_child(&input);
+    root.append_child(&Node::new_comment("x --><b>"));
+    assert_eq!(
+        root.to_string(),
+        "<div><input value=\"a\"><!--x --&gt;&lt;b&gt;--></div>"
+    );
+}
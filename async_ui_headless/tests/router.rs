@@ -0,0 +1,57 @@
+use std::rc::Rc;
+
+use async_ui_headless::{
+    components::text,
+    fragment, mount,
+    router::{route_outlet, route_params, Route, Router},
+    run_until_idle, with_context,
+};
+
+async fn user_page() {
+    let id = route_params().get("id").unwrap_or_default().to_owned();
+    text(&id).await
+}
+
+async fn user_section() {
+    fragment((
+        text(&"user:"),
+        route_outlet(&[
+            Route::new("/", |_| fragment((user_page(),))),
+            Route::new("/posts", |_| fragment((text(&"posts"),))),
+        ]),
+    ))
+    .await
+}
+
+#[test]
+fn nested_outlets() {
+    let router = Rc::new(Router::new_in_memory("/"));
+    let root = mount(with_context(
+        async {
+            route_outlet(&[
+                Route::new("/", |_| fragment((text(&"home"),))),
+                Route::new("/users/:id/*rest", |_| fragment((user_section(),))),
+            ])
+            .await
+        },
+        router.clone(),
+    ));
+    run_until_idle();
+    assert_eq!(root.inner_html(), "home");
+
+    router.navigate("/users/42");
+    run_until_idle();
+    assert_eq!(root.inner_html(), "user:42");
+
+    router.navigate("/users/42/posts?page=2");
+    run_until_idle();
+    assert_eq!(root.inner_html(), "user:posts");
+
+    router.navigate("/nowhere");
+    run_until_idle();
+    assert_eq!(root.inner_html(), "");
+
+    router.back();
+    run_until_idle();
+    assert_eq!(root.inner_html(), "user:posts");
+}
@@ -0,0 +1,61 @@
+use std::rc::Rc;
+
+use async_ui_headless::{
+    components::{text, view, ViewProps},
+    fragment, mount, run_until_idle, suspense, suspense_ready,
+};
+use observables::{cell::ReactiveCell, ObservableAsExt};
+
+#[test]
+fn fallback_until_first_node() {
+    let loaded = Rc::new(ReactiveCell::new(false));
+    let loaded_1 = loaded.clone();
+    let root = mount(async move {
+        fragment((
+            text(&"["),
+            suspense(
+                async {
+                    loaded_1.as_observable().until_change().await;
+                    fragment((
+                        text(&"a"),
+                        view(ViewProps {
+                            children: fragment((text(&"b"),)),
+                            element_tag: "p",
+                        }),
+                    ))
+                    .await;
+                },
+                text(&"loading"),
+            ),
+            text(&"]"),
+        ))
+        .await;
+    });
+    run_until_idle();
+    assert_eq!(root.inner_html(), "[loading]");
+    loaded.set(true);
+    run_until_idle();
+    assert_eq!(root.inner_html(), "[a<p>b</p>]");
+}
+
+#[test]
+fn explicit_ready() {
+    let loaded = Rc::new(ReactiveCell::new(false));
+    let loaded_1 = loaded.clone();
+    let root = mount(async move {
+        suspense(
+            async {
+                loaded_1.as_observable().until_change().await;
+                suspense_ready();
+                std::future::pending::<()>().await;
+            },
+            text(&"loading"),
+        )
+        .await;
+    });
+    run_until_idle();
+    assert_eq!(root.inner_html(), "loading");
+    loaded.set(true);
+    run_until_idle();
+    assert_eq!(root.inner_html(), "");
+}
@@ -0,0 +1,79 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use async_ui_headless::{
+    futures_lite::FutureExt,
+    mount, run_until_idle,
+    time::{advance_time, interval, sleep, Debounced, Throttled},
+};
+use observables::{cell::ReactiveCell, ObservableAs, ObservableAsExt};
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn sleep_and_interval() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let log_1 = log.clone();
+    let _root = mount(async move {
+        let mut ticks = 0;
+        interval(ms(30), || ticks += 1)
+            .or(async {
+                sleep(ms(100)).await;
+                log_1.borrow_mut().push("slept");
+            })
+            .await;
+        log_1.borrow_mut().push("done");
+    });
+    run_until_idle();
+    advance_time(ms(99));
+    assert!(log.borrow().is_empty());
+    advance_time(ms(1));
+    assert_eq!(*log.borrow(), ["slept", "done"]);
+}
+
+#[test]
+fn debounce_waits_for_quiet() {
+    let source = Rc::new(ReactiveCell::new(String::new()));
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let (source_1, seen_1) = (source.clone(), seen.clone());
+    let _root = mount(async move {
+        let debounced = Debounced::<str>::new(source_1.as_observable_rc(), ms(100));
+        loop {
+            ObservableAsExt::<str>::until_change(&debounced).await;
+            let value = debounced.borrow_observable_as().to_owned();
+            seen_1.borrow_mut().push(value);
+        }
+    });
+    run_until_idle();
+    for text in ["a", "ab", "abc"] {
+        source.set(text.to_owned());
+        advance_time(ms(60));
+    }
+    assert!(seen.borrow().is_empty());
+    advance_time(ms(40));
+    assert_eq!(*seen.borrow(), ["abc"]);
+}
+
+#[test]
+fn throttle_takes_at_most_one_per_period() {
+    let source = Rc::new(ReactiveCell::new(0));
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let (source_1, seen_1) = (source.clone(), seen.clone());
+    let _root = mount(async move {
+        let throttled = Throttled::<i32>::new(source_1.as_observable_rc(), ms(100));
+        loop {
+            throttled.until_change().await;
+            seen_1.borrow_mut().push(*throttled.borrow_observable_as());
+        }
+    });
+    run_until_idle();
+    for value in 1..=5 {
+        source.set(value);
+        advance_time(ms(30));
+    }
+    // 1 right away, 4 at the end of the first period, and 5 at the end of the second.
+    assert_eq!(*seen.borrow(), [1, 4]);
+    advance_time(ms(200));
+    assert_eq!(*seen.borrow(), [1, 4, 5]);
+}
@@ -0,0 +1,60 @@
+use std::{cell::RefCell, rc::Rc};
+
+use async_ui_headless::{
+    backend::Backend,
+    components::{text, view, ViewProps},
+    fragment,
+    futures_lite::FutureExt,
+    mount, run_until_idle, transition, Node, TransitionHooks,
+};
+use observables::{cell::ReactiveCell, ObservableAsExt};
+
+/// Keeps removed nodes in place until the test lets them go.
+#[derive(Default)]
+struct Recorder {
+    entered: RefCell<Vec<String>>,
+    exiting: RefCell<Vec<(Node, Node)>>,
+}
+impl TransitionHooks<Backend> for Recorder {
+    fn enter(&self, node: &Node) {
+        let name = node.tag().unwrap_or_else(|| "#text".into());
+        self.entered.borrow_mut().push(name);
+    }
+    fn exit(&self, node: &Node, parent: &Node, next: Option<&Node>) {
+        parent.insert_before(node, next);
+        self.exiting
+            .borrow_mut()
+            .push((parent.clone(), node.clone()));
+    }
+}
+
+#[test]
+fn exit_keeps_node_in_place() {
+    let recorder = Rc::new(Recorder::default());
+    let hide = Rc::new(ReactiveCell::new(()));
+    let (recorder_1, hide_1) = (recorder.clone(), hide.clone());
+    let root = mount(async move {
+        let content = async {
+            view(ViewProps {
+                children: fragment((text(&"b"),)),
+                ..Default::default()
+            })
+            .or(hide_1.as_observable().until_change())
+            .await;
+            text(&"d").await;
+        };
+        fragment((text(&"a"), transition(content, recorder_1), text(&"c"))).await
+    });
+    run_until_idle();
+    assert_eq!(root.inner_html(), "a<div>b</div>c");
+
+    hide.set(());
+    run_until_idle();
+    assert_eq!(root.inner_html(), "a<div>b</div>dc");
+    assert_eq!(*recorder.entered.borrow(), ["div", "#text"]);
+
+    for (parent, node) in recorder.exiting.borrow_mut().drain(..) {
+        parent.remove_child(&node);
+    }
+    assert_eq!(root.inner_html(), "adc");
+}
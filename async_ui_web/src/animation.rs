@@ -1,5 +1,20 @@
-use std::rc::Rc;
+//! Animation driven by the browser's animation frames.
+//!
+//! [Tween]s and [Spring]s are observable, so they can be bound to styles directly:
+//! ```ignore
+//! let animator = Animator::new();
+//! let left = Spring::new(0.0, SpringConfig::WOBBLY);
+//! let attributes = [Binding::StyleNumber("left", &left, "px")];
+//! left.set_target(200.0);
+//! view(ViewProps { attributes: &attributes, ..Default::default() })
+//!     .or(left.run(&animator))
+//!     .await;
+//! ```
+use std::{future::Future, pin::Pin, rc::Rc};
 
+pub use async_ui_core::animation::{
+    easing, Animatable, Clock, ManualClock, Spring, SpringConfig, Tween,
+};
 use observables::{cell::ReactiveCell, Observable, ObservableAsExt};
 use wasm_bindgen::{prelude::Closure, JsCast};
 
@@ -28,3 +43,9 @@ impl Animator {
         return *self.cell.as_observable().borrow_observable();
     }
 }
+
+impl Clock for Animator {
+    fn next_frame(&self) -> Pin<Box<dyn Future<Output = f64> + '_>> {
+        Box::pin(Animator::next_frame(self))
+    }
+}
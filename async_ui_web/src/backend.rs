@@ -1,10 +1,13 @@
 use async_ui_core::{backend::BackendTrait, vnode::VNode};
 use scoped_tls::{scoped_thread_local, ScopedKey};
-use std::{future::Future, rc::Rc};
-use wasm_bindgen::UnwrapThrowExt;
-use web_sys::Node;
+use std::{future::Future, pin::Pin, rc::Rc, time::Duration};
 
-use crate::executor::{schedule, set_executor_future};
+use crate::{
+    dom::Node,
+    executor::{schedule, set_executor_future},
+    hydrate::take_claimed,
+    time::sleep_future,
+};
 
 pub struct Backend;
 impl BackendTrait for Backend {
@@ -15,19 +18,22 @@ impl BackendTrait for Backend {
         child: &mut Self::Node,
         insert_before_sibling: Option<&Self::Node>,
     ) {
-        parent
-            .insert_before(child, insert_before_sibling)
-            .expect_throw("insert failed");
+        if !take_claimed(child) {
+            parent.insert_before(child, insert_before_sibling);
+        }
     }
 
     fn del_child_node(parent: &mut Self::Node, child: &mut Self::Node) {
-        parent.remove_child(child).expect_throw("remove failed");
+        parent.remove_child(child);
     }
     fn drive_executor<F: Future<Output = ()> + 'static>(fut: F) {
         set_executor_future(Box::new(fut) as _);
         schedule();
     }
     fn initialize() {}
+    fn sleep(duration: Duration) -> Pin<Box<dyn Future<Output = ()>>> {
+        sleep_future(duration)
+    }
 
     fn get_vnode_key() -> &'static ScopedKey<Rc<VNode<Self>>> {
         &VNODE
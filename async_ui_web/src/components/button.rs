@@ -1,12 +1,20 @@
+use std::rc::Rc;
+
 use futures_lite::FutureExt;
 use smallvec::SmallVec;
-use wasm_bindgen::JsCast;
 use web_sys::{HtmlButtonElement, MouseEvent};
 
-use crate::{utils::class_list::ClassList, window::DOCUMENT, Fragment};
+use crate::{
+    dom::create_element,
+    utils::{
+        bindings::{sync_bindings, Binding},
+        class_list::ClassList,
+    },
+    Fragment,
+};
 
 use super::{
-    events::{create_handler, EventsManager, QueuedEvent},
+    events::{create_handler, Events, EventsManager, QueuedEvent},
     ElementFuture,
 };
 
@@ -15,44 +23,95 @@ pub struct ButtonProps<'c> {
     pub children: Fragment<'c>,
     pub on_press: Option<&'c mut dyn FnMut(PressEvent)>,
     pub class: Option<&'c ClassList<'c>>,
+    /// Attributes and styles to keep in sync, for what has no prop of its own.
+    pub attributes: &'c [Binding<'c>],
 }
 
 pub struct PressEvent {
     pub native_event: MouseEvent,
 }
 
-pub async fn button<'c>(
+/// A button whose presses can be awaited, instead of handled in `on_press`.
+///
+/// ```ignore
+/// let ok = Button::new();
+/// fragment((
+///     ok.render(ButtonProps {
+///         children: fragment((text(&"OK"),)),
+///         ..Default::default()
+///     }),
+///     async {
+///         ok.clicks().next().await;
+///         // The user pressed OK.
+///     },
+/// ))
+/// ```
+pub struct Button {
+    presses: Rc<EventsManager<PressEvent>>,
+}
+impl Default for Button {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Button {
+    pub fn new() -> Self {
+        Self {
+            presses: EventsManager::new(),
+        }
+    }
+    /// Render the button. `on_press`, if given, is still called too.
+    pub async fn render<'c>(&self, props: ButtonProps<'c>) {
+        render_button(props, Some(&self.presses)).await
+    }
+    /// The presses of the rendered button.
+    pub fn clicks(&self) -> Events<'_, PressEvent> {
+        Events::new(&self.presses)
+    }
+}
+
+pub async fn button<'c>(props: ButtonProps<'c>) {
+    render_button(props, None).await
+}
+
+async fn render_button<'c>(
     ButtonProps {
         children,
         mut on_press,
         class,
+        attributes,
     }: ButtonProps<'c>,
+    handle: Option<&EventsManager<PressEvent>>,
 ) {
-    let button = DOCUMENT.with(|doc| {
-        let elem = doc.create_element("button").expect("create element failed");
-        let elem: HtmlButtonElement = elem.unchecked_into();
-        elem
-    });
+    let button = create_element("button");
 
     let mut handlers = SmallVec::<[_; 1]>::new();
     let manager = EventsManager::new();
 
-    if on_press.is_some() {
-        let h = create_handler(&manager, |e| QueuedEvent::Click(e));
-        button.set_onclick(Some(h.get_function()));
+    if let (true, Some(elem)) = (
+        on_press.is_some() || handle.is_some(),
+        button.web_as::<HtmlButtonElement>(),
+    ) {
+        let h = create_handler(&manager, QueuedEvent::Click);
+        elem.set_onclick(Some(h.get_function()));
         handlers.push(h);
     }
     if let Some(class) = class {
-        class.set_dom(button.class_list());
+        class.set_dom(&button);
     }
 
-    let future = children.or(async {
+    let future = children.or(sync_bindings(&button, attributes)).or(async {
         manager.grab_waker().await;
         loop {
             let mut events = manager.get_queue().await;
             for event in events.drain(..) {
                 match event {
                     QueuedEvent::Click(native_event) => {
+                        if let Some(handle) = handle {
+                            handle.forward(PressEvent {
+                                native_event: native_event.clone(),
+                            });
+                        }
                         on_press.as_mut().map(|f| f(PressEvent { native_event }));
                     }
                     _ => {}
@@ -60,5 +119,5 @@ pub async fn button<'c>(
             }
         }
     });
-    ElementFuture::new(future, button.into()).await
+    ElementFuture::new(future, button.clone()).await
 }
@@ -1,10 +1,15 @@
 use futures_lite::FutureExt;
 use observables::{ObservableAs, ObservableAsExt};
 use smallvec::SmallVec;
-use wasm_bindgen::JsCast;
 use web_sys::{Event, HtmlInputElement};
 
-use crate::{utils::class_list::ClassList, window::DOCUMENT};
+use crate::{
+    dom::{create_element, Node},
+    utils::{
+        bindings::{sync_bindings, Binding},
+        class_list::ClassList,
+    },
+};
 
 use super::{
     events::{create_handler, EventsManager, QueuedEvent},
@@ -25,6 +30,8 @@ pub struct CheckboxProps<'c> {
     pub value: Option<&'c dyn ObservableAs<bool>>,
     pub on_change: Option<&'c mut dyn FnMut(CheckboxChangeEvent)>,
     pub class: Option<&'c ClassList<'c>>,
+    /// Attributes and styles to keep in sync, for what has no prop of its own.
+    pub attributes: &'c [Binding<'c>],
 }
 
 pub async fn checkbox<'c>(
@@ -32,23 +39,21 @@ pub async fn checkbox<'c>(
         value,
         mut on_change,
         class,
+        attributes,
     }: CheckboxProps<'c>,
 ) {
-    let elem: HtmlInputElement = DOCUMENT.with(|doc| {
-        let elem = doc.create_element("input").expect("create element failed");
-        elem.unchecked_into()
-    });
-    elem.set_type("checkbox");
+    let elem = create_element("input");
+    elem.set_attribute("type", "checkbox");
     let value = value.unwrap_or(&false);
     let mut handlers = SmallVec::<[_; 1]>::new();
     let manager = EventsManager::new();
-    if on_change.is_some() {
+    if let (Some(_), Some(input)) = (&on_change, elem.web_as::<HtmlInputElement>()) {
         let h = create_handler(&manager, |_ev: Event| QueuedEvent::Check());
-        elem.set_onchange(Some(h.get_function()));
+        input.set_onchange(Some(h.get_function()));
         handlers.push(h);
     }
     if let Some(cl) = class {
-        cl.set_dom(elem.class_list());
+        cl.set_dom(&elem);
     }
     let elem_1 = elem.clone();
     let elem_2 = elem.clone();
@@ -56,8 +61,9 @@ pub async fn checkbox<'c>(
         loop {
             let mut events = manager.get_queue().await;
             for event in events.drain(..) {
+                // Events only come from handlers, which only exist on browser nodes.
                 let checkbox_change_event = CheckboxChangeEvent {
-                    node: elem_1.clone(),
+                    node: elem_1.web_as::<HtmlInputElement>().unwrap().clone(),
                 };
                 match event {
                     QueuedEvent::Check() => {
@@ -70,9 +76,18 @@ pub async fn checkbox<'c>(
     })
     .or(async {
         loop {
-            elem_2.set_checked(*value.borrow_observable_as());
+            set_checked(&elem_2, *value.borrow_observable_as());
             value.until_change().await;
         }
-    });
-    ElementFuture::new(future, elem.into()).await;
+    })
+    .or(sync_bindings(&elem_2, attributes));
+    ElementFuture::new(future, elem).await;
+}
+
+pub(super) fn set_checked(elem: &Node, checked: bool) {
+    match elem.web_as::<HtmlInputElement>() {
+        Some(input) => input.set_checked(checked),
+        None if checked => elem.set_attribute("checked", ""),
+        None => elem.remove_attribute("checked"),
+    }
 }
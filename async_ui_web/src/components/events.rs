@@ -3,12 +3,14 @@ use std::{
     collections::VecDeque,
     future::poll_fn,
     ops::AddAssign,
+    pin::Pin,
     rc::Rc,
-    task::{Poll, Waker},
+    task::{Context, Poll, Waker},
 };
 
+use futures_lite::Stream;
 use js_sys::Function;
-use web_sys::{Event, FocusEvent, InputEvent, KeyboardEvent, MouseEvent};
+use web_sys::{Event, EventTarget, FocusEvent, InputEvent, KeyboardEvent, MouseEvent};
 
 pub(super) enum QueuedEvent {
     Click(MouseEvent),
@@ -21,19 +23,30 @@ pub(super) enum QueuedEvent {
     Focus(FocusEvent),
     Blur(FocusEvent),
     Check(),
+    Change(),
+    CompositionStart(),
+    CompositionEnd(),
+    Scroll(),
+    /// The `ResizeObserverEntry`s of the elements that resized.
+    Resize(js_sys::Array),
 }
 
-pub(super) struct EventsManager {
-    inner: RefCell<EventHandlerInner>,
+/// Queues events from handlers until the component's task handles them.
+///
+/// Component handles (like [Button][super::Button]) use one holding their own event type.
+pub(super) struct EventsManager<T = QueuedEvent> {
+    inner: RefCell<EventHandlerInner<T>>,
 }
-struct EventHandlerInner {
-    events: VecDeque<QueuedEvent>,
+struct EventHandlerInner<T> {
+    events: VecDeque<T>,
     waker: Option<Waker>,
     version: u64,
     last_version: u64,
+    /// How many [Events] streams read from this.
+    streams: usize,
 }
 
-impl EventsManager {
+impl<T> EventsManager<T> {
     pub fn new() -> Rc<Self> {
         Rc::new(Self {
             inner: RefCell::new(EventHandlerInner {
@@ -41,10 +54,11 @@ impl EventsManager {
                 waker: None,
                 version: 1,
                 last_version: 1,
+                streams: 0,
             }),
         })
     }
-    pub fn add_event(&self, event: QueuedEvent) {
+    pub fn add_event(&self, event: T) {
         let bm = &mut *self.inner.borrow_mut();
         bm.events.push_back(event);
         bm.version.add_assign(1);
@@ -52,7 +66,15 @@ impl EventsManager {
             waker.wake_by_ref();
         }
     }
-    fn borrow_queue_mut<'b>(&'b self) -> Option<RefMut<'b, VecDeque<QueuedEvent>>> {
+    /// Queue `event` for the [Events] streams, or drop it if there are none.
+    ///
+    /// Components use this for their handles, so events nobody is waiting for don't pile up.
+    pub fn forward(&self, event: T) {
+        if self.inner.borrow().streams > 0 {
+            self.add_event(event);
+        }
+    }
+    fn borrow_queue_mut<'b>(&'b self) -> Option<RefMut<'b, VecDeque<T>>> {
         let mut bm = self.inner.borrow_mut();
         if bm.last_version < bm.version {
             bm.last_version = bm.version;
@@ -71,7 +93,18 @@ impl EventsManager {
         let waker = poll_fn(|cx| Poll::Ready(cx.waker().clone())).await;
         self.set_waker(waker);
     }
-    pub async fn get_queue<'b>(&'b self) -> RefMut<'b, VecDeque<QueuedEvent>> {
+    /// Take the next event, or wake the current task when one comes.
+    fn poll_event(&self, cx: &mut Context<'_>) -> Poll<T> {
+        let mut bm = self.inner.borrow_mut();
+        match bm.events.pop_front() {
+            Some(event) => Poll::Ready(event),
+            None => {
+                bm.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+    pub async fn get_queue<'b>(&'b self) -> RefMut<'b, VecDeque<T>> {
         poll_fn(|_cx| match self.borrow_queue_mut() {
             Some(q) => Poll::Ready(q),
             None => Poll::Pending,
@@ -83,7 +116,7 @@ impl EventsManager {
 use wasm_bindgen::prelude::Closure;
 use wasm_bindgen::JsCast;
 
-use crate::executor::schedule;
+use crate::executor::schedule_input;
 
 pub(super) struct EventHandler<'h> {
     closure: Closure<dyn Fn(Event) + 'h>,
@@ -96,7 +129,7 @@ impl<'h> EventHandler<'h> {
         let closure = Closure::new(move |event: Event| {
             let event: E = event.unchecked_into();
             execute(event);
-            schedule();
+            schedule_input();
         });
         Self { closure }
     }
@@ -119,3 +152,81 @@ pub(super) fn create_handler<
         manager.add_event(q);
     })
 }
+
+/// The events named `name` fired at `target`, as a [Stream].
+///
+/// The events are queued by the handler and handled in the task polling the stream,
+/// through the same queue as the streams of component handles, like [Button::clicks][super::Button::clicks].
+/// Since handling happens later, calling `prevent_default` on them has no effect.
+/// Events that aren't an `E` are skipped.
+/// The listener is removed when the stream is dropped.
+pub fn listen<E: JsCast + 'static>(target: &EventTarget, name: &str) -> EventStream<E> {
+    let manager = EventsManager::<E>::new();
+    let handler = {
+        let manager = manager.clone();
+        EventHandler::new(move |event: Event| {
+            if let Ok(event) = event.dyn_into::<E>() {
+                manager.add_event(event);
+            }
+        })
+    };
+    target
+        .add_event_listener_with_callback(name, handler.get_function())
+        .expect("add event listener failed");
+    EventStream {
+        target: target.clone(),
+        name: name.to_owned(),
+        handler,
+        manager,
+    }
+}
+
+/// A subscription made with [listen].
+pub struct EventStream<E> {
+    target: EventTarget,
+    name: String,
+    handler: EventHandler<'static>,
+    manager: Rc<EventsManager<E>>,
+}
+impl<E> Stream for EventStream<E> {
+    type Item = E;
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<E>> {
+        self.manager.poll_event(cx).map(Some)
+    }
+}
+impl<E> Drop for EventStream<E> {
+    fn drop(&mut self) {
+        let _ = self
+            .target
+            .remove_event_listener_with_callback(&self.name, self.handler.get_function());
+    }
+}
+
+/// The events a component forwards to its handle, like [Button::clicks][super::Button::clicks].
+///
+/// Only events that happen while a stream exists are delivered; the ones before are dropped.
+/// Each event goes to only one stream, so keep one stream per kind of event at a time.
+pub struct Events<'h, T> {
+    manager: &'h EventsManager<T>,
+}
+impl<'h, T> Events<'h, T> {
+    pub(super) fn new(manager: &'h EventsManager<T>) -> Self {
+        manager.inner.borrow_mut().streams += 1;
+        Self { manager }
+    }
+}
+impl<'h, T> Drop for Events<'h, T> {
+    fn drop(&mut self) {
+        let mut inner = self.manager.inner.borrow_mut();
+        inner.streams -= 1;
+        if inner.streams == 0 {
+            inner.events.clear();
+        }
+    }
+}
+impl<'h, T> Stream for Events<'h, T> {
+    type Item = T;
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.manager.poll_event(cx).map(Some)
+    }
+}
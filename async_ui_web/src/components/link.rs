@@ -1,10 +1,19 @@
+use async_ui_core::vnode::node_context::try_get_context;
 use futures_lite::FutureExt;
 use observables::{ObservableAs, ObservableAsExt};
 use smallvec::SmallVec;
-use wasm_bindgen::JsCast;
-use web_sys::HtmlAnchorElement;
+use web_sys::{HtmlAnchorElement, MouseEvent};
 
-use crate::{utils::class_list::ClassList, window::DOCUMENT, Fragment};
+use crate::{
+    backend::Backend,
+    dom::create_element,
+    router::{intercept, Router},
+    utils::{
+        bindings::{sync_bindings, Binding},
+        class_list::ClassList,
+    },
+    Fragment,
+};
 
 use super::{
     button::PressEvent,
@@ -18,6 +27,8 @@ pub struct LinkProps<'c> {
     pub href: Option<&'c dyn ObservableAs<str>>,
     pub on_press: Option<&'c mut dyn FnMut(PressEvent)>,
     pub class: Option<&'c ClassList<'c>>,
+    /// Attributes and styles to keep in sync, for what has no prop of its own.
+    pub attributes: &'c [Binding<'c>],
 }
 
 pub async fn link<'c>(
@@ -26,29 +37,38 @@ pub async fn link<'c>(
         mut on_press,
         class,
         children,
+        attributes,
     }: LinkProps<'c>,
 ) {
-    let anchor = DOCUMENT.with(|doc| {
-        let elem = doc.create_element("a").expect("create element failed");
-        let elem: HtmlAnchorElement = elem.unchecked_into();
-        elem
-    });
+    let anchor = create_element("a");
     let href = href.unwrap_or(&"");
 
     let mut handlers = SmallVec::<[_; 1]>::new();
     let manager = EventsManager::new();
 
-    if on_press.is_some() {
-        let h = create_handler(&manager, |e| QueuedEvent::Click(e));
-        anchor.set_onclick(Some(h.get_function()));
+    let router = try_get_context::<Backend, Router>();
+    if let (true, Some(elem)) = (
+        on_press.is_some() || router.is_some(),
+        anchor.web_as::<HtmlAnchorElement>(),
+    ) {
+        let elem_1 = elem.clone();
+        let h = create_handler(&manager, move |e: MouseEvent| {
+            // This has to happen in the handler itself, or it would be too late to prevent the page load.
+            if let Some(router) = router.as_ref() {
+                intercept(router, &elem_1, &e);
+            }
+            QueuedEvent::Click(e)
+        });
+        elem.set_onclick(Some(h.get_function()));
         handlers.push(h);
     }
     if let Some(class) = class {
-        class.set_dom(anchor.class_list());
+        class.set_dom(&anchor);
     }
     let anchor_copy = anchor.clone();
 
     let future = (children)
+        .or(sync_bindings(&anchor_copy, attributes))
         .or(async {
             manager.grab_waker().await;
             loop {
@@ -67,14 +87,12 @@ pub async fn link<'c>(
             loop {
                 let s = &*href.borrow_observable_as();
                 if !s.is_empty() {
-                    anchor_copy.set_href(s);
+                    anchor_copy.set_attribute("href", s);
                 } else {
-                    anchor_copy
-                        .remove_attribute("href")
-                        .expect("anchor remove attribute failed");
+                    anchor_copy.remove_attribute("href");
                 }
                 href.until_change().await;
             }
         });
-    ElementFuture::new(future, anchor.into()).await
+    ElementFuture::new(future, anchor).await
 }
@@ -1,11 +1,11 @@
 use std::{future::IntoFuture, rc::Rc};
 
 use async_task::Task;
-pub use async_ui_core::list::ListModel;
+pub use async_ui_core::list::{ListModel, ListObservableExt};
 use async_ui_core::{
     backend::BackendTrait,
-    executor::spawn_local,
-    list::{Change, ListModelPrivateAPIs},
+    executor::{spawn_local, spawn_local_in},
+    list::{Change, Changes, ListModelPrivateAPIs},
     vnode::{
         node_concrete::{ConcreteNodeVNode, RefNode},
         VNodeTrait, WithVNode,
@@ -16,17 +16,45 @@ use im_rc::Vector;
 use observables::{ObservableAs, ObservableAsExt};
 use scoped_async_spawn::SpawnGuard;
 use slab::Slab;
-use web_sys::Node;
 
-use crate::{backend::Backend, utils::class_list::ClassList, window::DOCUMENT};
+use crate::{
+    backend::Backend,
+    dom::{create_comment, create_element, Node},
+    hydrate::{adopt, is_hydrating, list_markers, report_mismatch},
+    utils::class_list::ClassList,
+};
 
 use super::ElementFuture;
 
 fn insert_after(parent: &Node, child: &Node, after: Option<&Node>) {
     let before = after.map_or_else(|| parent.first_child(), |after| after.next_sibling());
-    parent
-        .insert_before(child, before.as_ref())
-        .expect("insert failed");
+    parent.insert_before(child, before.as_ref());
+}
+
+/// Move the nodes of an item (everything after `start_after` up to and including `marker`) to after `after`.
+fn move_item(parent: &Node, start_after: Option<&Node>, marker: &Node, after: Option<&Node>) {
+    let before = after.map_or_else(|| parent.first_child(), |after| after.next_sibling());
+    let mut next = start_after.map_or_else(|| parent.first_child(), |start| start.next_sibling());
+    while let Some(node) = next {
+        next = node.next_sibling();
+        parent.insert_before(&node, before.as_ref());
+        if &node == marker {
+            break;
+        }
+    }
+}
+
+/// Drop the item's task, then remove its marker.
+///
+/// The marker stays until the task's future is actually dropped,
+/// so that nodes animating out (see [transition][crate::transition]) can be kept in place before it.
+fn remove_item(parent: &Node, marker: Node, task: Task<()>) {
+    let parent = parent.clone();
+    spawn_local(async move {
+        task.cancel().await;
+        parent.remove_child(&marker);
+    })
+    .detach();
 }
 
 pub struct ListProps<'c, T: Clone, F: IntoFuture<Output = ()>> {
@@ -51,11 +79,10 @@ pub async fn list<'c, T: Clone + 'c, F: IntoFuture<Output = ()> + 'c>(
         class,
     }: ListProps<'c, T, F>,
 ) {
-    let container_node =
-        DOCUMENT.with(|doc| doc.create_element("div").expect("create element failed"));
+    let container_node = create_element("div");
 
     if let Some(class) = class {
-        class.set_dom(container_node.class_list());
+        class.set_dom(&container_node);
     }
     let (data, render) = match (data, render) {
         (Some(d), Some(r)) => (d, r),
@@ -63,7 +90,6 @@ pub async fn list<'c, T: Clone + 'c, F: IntoFuture<Output = ()> + 'c>(
             return;
         }
     };
-    let container_node: Node = container_node.clone().into();
     let container_node_copy = container_node.clone();
     let inside = async move {
         let parent_vnode = Backend::get_vnode_key().with(Clone::clone);
@@ -73,59 +99,86 @@ pub async fn list<'c, T: Clone + 'c, F: IntoFuture<Output = ()> + 'c>(
         let guard = SpawnGuard::new();
         pin!(guard);
         let mut nodes: Vector<(Node, usize)> = Vector::new();
-        let mut create_item_task = |fut: F::IntoFuture, after_this: Option<&Node>| {
-            let reference_node: Node = DOCUMENT.with(|doc| doc.create_comment("")).into();
-            insert_after(&container_node, &reference_node, after_this);
-            let fut = {
-                WithVNode::new(
-                    fut,
-                    Rc::new(
-                        ConcreteNodeVNode::new(
-                            RefNode::<Backend>::Sibling {
-                                parent: container_node.clone(),
-                                sibling: reference_node.clone(),
-                            },
-                            parent_context.clone(),
-                        )
-                        .into(),
-                    ),
-                )
+        let mut create_item_task =
+            |fut: F::IntoFuture, after_this: Option<&Node>, existing: Option<Node>| {
+                let reference_node = existing.unwrap_or_else(|| {
+                    let node = create_comment("");
+                    insert_after(&container_node, &node, after_this);
+                    node
+                });
+                let fut = {
+                    WithVNode::new(
+                        fut,
+                        Rc::new(
+                            ConcreteNodeVNode::new(
+                                RefNode::<Backend>::Sibling {
+                                    parent: container_node.clone(),
+                                    sibling: reference_node.clone(),
+                                },
+                                parent_context.clone(),
+                            )
+                            .into(),
+                        ),
+                    )
+                };
+                let fut = guard.as_mut().convert_future(fut);
+                let task = spawn_local_in(parent_context, fut);
+                (reference_node, task)
             };
-            let fut = guard.as_mut().convert_future(fut);
-            let task = spawn_local(fut);
-            (reference_node, task)
-        };
+        let listener;
+        let mut seen_ids;
         let mut last_version = {
             let model = &*data.borrow_observable_as();
 
             let start = model.underlying_vector();
+            let mut existing_markers = if is_hydrating() {
+                list_markers(&container_node)
+            } else {
+                Vec::new()
+            };
+            if existing_markers.len() > start.len() {
+                report_mismatch(&format!(
+                    "expected {} list items, found {}",
+                    start.len(),
+                    existing_markers.len()
+                ));
+            }
+            existing_markers.truncate(start.len());
+            existing_markers.iter().for_each(adopt);
+            let mut existing_markers = existing_markers.into_iter();
             let mut last_node = None;
             for item in start.iter() {
                 let fut = render(item.to_owned()).into_future();
-                let (node, task) = create_item_task(fut, last_node.as_ref());
+                let (node, task) =
+                    create_item_task(fut, last_node.as_ref(), existing_markers.next());
                 last_node = Some(node.to_owned());
                 let task_id = tasks.insert(task);
                 nodes.push_back((node, task_id));
             }
             let model = ListModelPrivateAPIs(model);
-            model
-                .total_listeners()
-                .set(model.total_listeners().get() + 1);
+            listener = model.add_listener();
+            seen_ids = model.item_ids().clone();
             model.get_version()
         };
         let _guard = scopeguard::guard((), |_| {
             let b = data.borrow_observable_as();
-            let model = ListModelPrivateAPIs(&*b);
-            model
-                .total_listeners()
-                .set(model.total_listeners().get() - 1);
+            ListModelPrivateAPIs(&*b).remove_listener(listener);
         });
         loop {
             data.until_change().await;
             {
                 let model = &*data.borrow_observable_as();
                 let model_priv = ListModelPrivateAPIs(model);
-                let changes = model_priv.changes_since_version(last_version);
+                let catch_up;
+                let changes: Box<dyn Iterator<Item = &Change<T>>> =
+                    match model_priv.changes_since_version(last_version) {
+                        Changes::Incremental(changes) => Box::new(changes),
+                        // Items still in the list keep their rows.
+                        Changes::Reset => {
+                            catch_up = model_priv.changes_from_ids(&seen_ids);
+                            Box::new(catch_up.iter())
+                        }
+                    };
                 for change in changes {
                     match change {
                         Change::Splice {
@@ -136,14 +189,15 @@ pub async fn list<'c, T: Clone + 'c, F: IntoFuture<Output = ()> + 'c>(
                             let mut right = nodes.split_off(remove_range.start);
                             let new_right = right.split_off(n_items);
                             for (node, task_id) in right.into_iter() {
-                                std::mem::drop(tasks.remove(task_id));
-                                container_node.remove_child(&node).ok();
+                                remove_item(&container_node, node, tasks.remove(task_id));
                             }
-                            let insert_after: Option<Node> =
+                            let mut insert_after: Option<Node> =
                                 nodes.back().map(|(node, _)| node).cloned();
                             nodes.extend(replace_with.iter().map(|t| {
                                 let fut = render(t.to_owned()).into_future();
-                                let (node, task) = create_item_task(fut, insert_after.as_ref());
+                                let (node, task) =
+                                    create_item_task(fut, insert_after.as_ref(), None);
+                                insert_after = Some(node.clone());
                                 let task_id = tasks.insert(task);
                                 (node, task_id)
                             }));
@@ -151,25 +205,39 @@ pub async fn list<'c, T: Clone + 'c, F: IntoFuture<Output = ()> + 'c>(
                         }
                         Change::Remove { index } => {
                             let (node, task_id) = nodes.remove(*index);
-                            std::mem::drop(tasks.remove(task_id));
-                            container_node.remove_child(&node).ok();
+                            remove_item(&container_node, node, tasks.remove(task_id));
                         }
                         Change::Insert { index, value } => {
                             let fut = render(value.to_owned()).into_future();
-                            let (node, task) = create_item_task(fut, {
-                                (*index > 0)
-                                    .then(|| nodes.get(index - 1).map(|(node, _task_id)| node))
-                                    .flatten()
-                            });
+                            let (node, task) = create_item_task(
+                                fut,
+                                {
+                                    (*index > 0)
+                                        .then(|| nodes.get(index - 1).map(|(node, _task_id)| node))
+                                        .flatten()
+                                },
+                                None,
+                            );
                             let task_id = tasks.insert(task);
                             nodes.insert(*index, (node, task_id));
                         }
+                        Change::Move { from, to } => {
+                            let start_after = (*from > 0).then(|| nodes[from - 1].0.clone());
+                            let item = nodes.remove(*from);
+                            let after = (*to > 0).then(|| nodes[to - 1].0.clone());
+                            move_item(
+                                &container_node,
+                                start_after.as_ref(),
+                                &item.0,
+                                after.as_ref(),
+                            );
+                            nodes.insert(*to, item);
+                        }
                     }
                 }
                 last_version = model_priv.get_version();
-                model_priv
-                    .pending_listeners()
-                    .set(model_priv.pending_listeners().get() - 1);
+                seen_ids = model_priv.item_ids().clone();
+                model_priv.set_listener_version(listener, last_version);
             }
         }
     };
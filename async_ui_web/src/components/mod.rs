@@ -13,7 +13,6 @@ use async_ui_core::{
     },
 };
 use pin_project_lite::pin_project;
-use web_sys::Node;
 
 mod events;
 
@@ -21,26 +20,38 @@ mod button;
 mod checkbox;
 mod link;
 mod list;
+mod number_input;
+mod progress;
 mod radio;
+mod select;
+mod slider;
 mod text;
 mod text_input;
 mod view;
-pub use button::{button, ButtonProps};
-pub use checkbox::{checkbox, CheckboxProps};
+mod virtual_list;
+pub use button::{button, Button, ButtonProps, PressEvent};
+pub use checkbox::{checkbox, CheckboxChangeEvent, CheckboxProps};
+pub use events::{listen, EventStream, Events};
 pub use link::{link, LinkProps};
-pub use list::{list, ListModel, ListProps};
+pub use list::{list, ListModel, ListObservableExt, ListProps};
+pub use number_input::{number_input, NumberInputEvent, NumberInputProps};
+pub use progress::{progress, ProgressProps};
 pub use radio::{radio_button, radio_group, RadioGroupProps, RadioProps};
+pub use select::{multi_select, select, MultiSelectProps, SelectOptions, SelectProps};
+pub use slider::{slider, SliderChangeEvent, SliderProps};
 pub use text::text;
-pub use text_input::{text_input, TextInputProps};
+pub use text_input::{text_input, TextInput, TextInputEvent, TextInputProps};
 pub use view::{view, ViewProps};
+pub use virtual_list::{virtual_list, ItemHeight, VirtualListProps};
 
-use crate::backend::Backend;
+use crate::{backend::Backend, dom::Node};
 
 pin_project! {
     pub struct ElementFuture<F: Future> {
+        // Dropped first, so the node is removed whole before its children drop.
+        inner: ElementFutureInner,
         #[pin]
         future: F,
-        inner: ElementFutureInner
     }
 }
 struct ElementFutureInner {
@@ -54,7 +65,10 @@ struct MyAndParentVNodes {
 
 impl Drop for ElementFutureInner {
     fn drop(&mut self) {
-        if let Some(MyAndParentVNodes { parent, .. }) = &self.vnodes {
+        if let Some(MyAndParentVNodes { my, parent }) = &self.vnodes {
+            if let VNode::ConcreteNode(my) = &**my {
+                my.detach();
+            }
             parent.del_child_node(Default::default());
         }
     }
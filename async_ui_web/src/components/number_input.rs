@@ -0,0 +1,132 @@
+use std::num::ParseFloatError;
+
+use futures_lite::FutureExt;
+use observables::{ObservableAs, ObservableAsExt};
+use smallvec::SmallVec;
+use web_sys::{FocusEvent, HtmlInputElement, InputEvent};
+
+use crate::{
+    dom::create_element,
+    utils::{
+        bindings::{sync_bindings, Binding},
+        class_list::ClassList,
+    },
+};
+
+use super::{
+    events::{create_handler, EventsManager, QueuedEvent},
+    slider::{set_limits, set_number},
+    ElementFuture,
+};
+
+pub struct NumberInputEvent {
+    node: HtmlInputElement,
+}
+impl NumberInputEvent {
+    /// The number typed, or why it isn't one.
+    ///
+    /// Browsers report what isn't a number as empty, so an unfinished or invalid entry
+    /// gives the same error as an empty input.
+    pub fn get_value(&self) -> Result<f64, ParseFloatError> {
+        self.node.value().parse()
+    }
+    pub fn get_text(&self) -> String {
+        self.node.value()
+    }
+}
+
+#[derive(Default)]
+pub struct NumberInputProps<'c> {
+    pub value: Option<&'c dyn ObservableAs<f64>>,
+    pub on_change: Option<&'c mut dyn FnMut(NumberInputEvent)>,
+    pub on_blur: Option<&'c mut dyn FnMut(NumberInputEvent)>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub step: Option<f64>,
+    pub placeholder: Option<&'c dyn ObservableAs<str>>,
+    pub class: Option<&'c ClassList<'c>>,
+    /// Attributes and styles to keep in sync, for what has no prop of its own.
+    pub attributes: &'c [Binding<'c>],
+}
+
+/// An `<input type="number">`.
+///
+/// While what the user typed doesn't parse, `value` is not written back into the input,
+/// so that their entry isn't replaced under them.
+pub async fn number_input<'c>(
+    NumberInputProps {
+        value,
+        mut on_change,
+        mut on_blur,
+        min,
+        max,
+        step,
+        placeholder,
+        class,
+        attributes,
+    }: NumberInputProps<'c>,
+) {
+    let elem = create_element("input");
+    elem.set_attribute("type", "number");
+    set_limits(&elem, min, max, step);
+    let placeholder = placeholder.unwrap_or(&"");
+    let mut handlers = SmallVec::<[_; 2]>::new();
+    let manager = EventsManager::new();
+    if let Some(input) = elem.web_as::<HtmlInputElement>() {
+        if on_change.is_some() {
+            let h = create_handler(&manager, |e: InputEvent| QueuedEvent::Input(e));
+            input.set_oninput(Some(h.get_function()));
+            handlers.push(h);
+        }
+        if on_blur.is_some() {
+            let h = create_handler(&manager, |e: FocusEvent| QueuedEvent::Blur(e));
+            input.set_onblur(Some(h.get_function()));
+            handlers.push(h);
+        }
+    }
+    if let Some(cl) = class {
+        cl.set_dom(&elem);
+    }
+    let future = (async {
+        manager.grab_waker().await;
+        loop {
+            let mut events = manager.get_queue().await;
+            for event in events.drain(..) {
+                let callback = match event {
+                    QueuedEvent::Input(_) => &mut on_change,
+                    QueuedEvent::Blur(_) => &mut on_blur,
+                    _ => continue,
+                };
+                if let Some(f) = callback {
+                    // Events only come from handlers, which only exist on browser nodes.
+                    f(NumberInputEvent {
+                        node: elem.web_as::<HtmlInputElement>().unwrap().clone(),
+                    });
+                }
+            }
+        }
+    })
+    .or(async {
+        let value = match value {
+            Some(value) => value,
+            None => return std::future::pending().await,
+        };
+        loop {
+            let bad_input = elem
+                .web_as::<HtmlInputElement>()
+                .is_some_and(|input| input.validity().bad_input());
+            if !bad_input {
+                set_number(&elem, *value.borrow_observable_as());
+            }
+            value.until_change().await;
+        }
+    })
+    .or(async {
+        loop {
+            elem.set_attribute("placeholder", &placeholder.borrow_observable_as());
+            placeholder.until_change().await;
+        }
+    })
+    .or(sync_bindings(&elem, attributes));
+    ElementFuture::new(future, elem.clone()).await;
+}
@@ -0,0 +1,54 @@
+use futures_lite::FutureExt;
+use observables::{ObservableAs, ObservableAsExt};
+
+use crate::{
+    dom::create_element,
+    utils::{
+        bindings::{sync_bindings, Binding},
+        class_list::ClassList,
+    },
+};
+
+use super::ElementFuture;
+
+#[derive(Default)]
+pub struct ProgressProps<'c> {
+    /// How much is done, out of `max`.
+    /// Without it, the bar shows that work is ongoing without saying how far along it is.
+    pub value: Option<&'c dyn ObservableAs<f64>>,
+    /// Defaults to 1.
+    pub max: Option<f64>,
+    pub class: Option<&'c ClassList<'c>>,
+    /// Attributes and styles to keep in sync, for what has no prop of its own.
+    pub attributes: &'c [Binding<'c>],
+}
+
+/// A `<progress>` bar.
+pub async fn progress<'c>(
+    ProgressProps {
+        value,
+        max,
+        class,
+        attributes,
+    }: ProgressProps<'c>,
+) {
+    let elem = create_element("progress");
+    if let Some(max) = max {
+        elem.set_attribute("max", &max.to_string());
+    }
+    if let Some(cl) = class {
+        cl.set_dom(&elem);
+    }
+    let future = (async {
+        let value = match value {
+            Some(value) => value,
+            None => return std::future::pending().await,
+        };
+        loop {
+            elem.set_attribute("value", &value.borrow_observable_as().to_string());
+            value.until_change().await;
+        }
+    })
+    .or(sync_bindings(&elem, attributes));
+    ElementFuture::new(future, elem.clone()).await;
+}
@@ -1,13 +1,16 @@
 use std::rc::Rc;
 
 use futures_lite::FutureExt;
-use observables::{cell::ReactiveCell, ObservableAs, ObservableAsExt};
+use observables::{cell::ReactiveCell, Observable, ObservableAs, ObservableAsExt};
 use wasm_bindgen::{prelude::Closure, JsCast};
 use web_sys::{Event, HtmlInputElement};
 
-use crate::{get_context, window::DOCUMENT, with_context, Fragment};
+use crate::{
+    dom::create_element, get_context, get_keyed_observable_context,
+    provide_keyed_observable_context, with_context, Fragment,
+};
 
-use super::ElementFuture;
+use super::{checkbox::set_checked, ElementFuture};
 
 #[derive(Default)]
 pub struct RadioProps<E: Clone + PartialEq + 'static> {
@@ -21,9 +24,14 @@ pub struct RadioGroupProps<'c, E: Clone + PartialEq + 'static> {
     pub on_change: Option<&'c mut dyn FnMut(E)>,
 }
 
+/// Context the group provides to its buttons.
+///
+/// The group's `value` is provided as an observable context keyed by this private type,
+/// so it can't clash with other contexts of type `E`.
 struct RadioGroup<E: 'static> {
     name: String,
-    value: ReactiveCell<E>,
+    /// The value of the button the user last checked, for the group to report.
+    picked: ReactiveCell<Option<E>>,
 }
 
 pub async fn radio_group<'c, E: Clone + PartialEq + 'static>(
@@ -36,16 +44,20 @@ pub async fn radio_group<'c, E: Clone + PartialEq + 'static>(
     let ptr = &children as *const Fragment<'_>;
     let name = format!("radio-{ptr:x?}");
     let value = if let Some(v) = value { v } else { return };
-    let value = ReactiveCell::new(value.borrow_observable_as().clone());
-    let group = Rc::new(RadioGroup { name, value });
+    let group = Rc::new(RadioGroup {
+        name,
+        picked: ReactiveCell::new(None),
+    });
     let group_1 = group.clone();
-    with_context::<_, RadioGroup<E>>(children, group)
+    provide_keyed_observable_context::<RadioGroup<E>, _, E>(with_context(children, group), value)
         .or(async {
             loop {
-                group_1.value.as_observable().until_change().await;
-                on_change
-                    .as_mut()
-                    .map(|f| f(group_1.value.as_observable().borrow_observable_as().clone()));
+                // Read without taking, since taking would be another change to wake up for.
+                group_1.picked.as_observable().until_change().await;
+                let picked = group_1.picked.as_observable().borrow_observable().clone();
+                if let (Some(picked), Some(on_change)) = (picked, on_change.as_mut()) {
+                    on_change(picked);
+                }
             }
         })
         .await;
@@ -53,33 +65,36 @@ pub async fn radio_group<'c, E: Clone + PartialEq + 'static>(
 
 pub async fn radio_button<E: Clone + PartialEq + 'static>(RadioProps { value }: RadioProps<E>) {
     let ctx = get_context::<RadioGroup<E>>();
-    let elem: HtmlInputElement = DOCUMENT.with(|doc| {
-        let elem = doc.create_element("input").expect("create element failed");
-        elem.unchecked_into()
-    });
-    elem.set_type("radio");
-    elem.set_name(&ctx.name);
+    let selected = get_keyed_observable_context::<RadioGroup<E>, E>();
+    let elem = create_element("input");
+    elem.set_attribute("type", "radio");
+    elem.set_attribute("name", &ctx.name);
 
-    let ctx_1 = ctx.clone();
-    let elem_1 = elem.clone();
-    let value_1 = value.clone();
-    let func: Closure<dyn Fn(Event)> = Closure::new(move |_ev: Event| {
-        if elem_1.checked() && *ctx_1.value.as_observable().borrow_observable_as() != value_1 {
-            ctx_1.value.set(value_1.clone());
-        }
-    });
-    elem.set_onchange(Some(func.as_ref().unchecked_ref()));
+    let mut _func = None;
+    if let Some(input) = elem.web_as::<HtmlInputElement>() {
+        let ctx_1 = ctx.clone();
+        let selected_1 = selected.clone();
+        let input_1 = input.clone();
+        let value_1 = value.clone();
+        let func: Closure<dyn Fn(Event)> = Closure::new(move |_ev: Event| {
+            if input_1.checked() && *selected_1.borrow_observable_as() != value_1 {
+                ctx_1.picked.set(Some(value_1.clone()));
+            }
+        });
+        input.set_onchange(Some(func.as_ref().unchecked_ref()));
+        _func = Some(func);
+    }
 
     let elem_2 = elem.clone();
 
     ElementFuture::new(
         async {
             loop {
-                elem_2.set_checked(*ctx.value.as_observable().borrow_observable_as() == value);
-                ctx.value.as_observable().until_change().await;
+                set_checked(&elem_2, *selected.borrow_observable_as() == value);
+                selected.until_change().await;
             }
         },
-        elem.into(),
+        elem,
     )
     .await;
 }
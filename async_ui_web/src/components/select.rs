@@ -0,0 +1,300 @@
+use std::cell::RefCell;
+
+use async_ui_core::list::{Change, Changes, ListModel, ListModelPrivateAPIs};
+use futures_lite::FutureExt;
+use observables::{ObservableAs, ObservableAsExt};
+use smallvec::SmallVec;
+use web_sys::{Event, HtmlElement, HtmlOptionElement};
+
+use crate::{
+    dom::{create_element, create_text_node, Node},
+    utils::{
+        bindings::{sync_bindings, Binding},
+        class_list::ClassList,
+    },
+};
+
+use super::{
+    events::{create_handler, EventsManager, QueuedEvent},
+    ElementFuture,
+};
+
+/// The options of a [select] or [multi_select]: each is a value and the label to show for it.
+pub enum SelectOptions<'c, E: Clone> {
+    Slice(&'c [(E, &'c str)]),
+    /// Options that can change. Only the `<option>`s of changed items are touched.
+    List(&'c dyn ObservableAs<ListModel<(E, String)>>),
+}
+impl<'c, E: Clone> Default for SelectOptions<'c, E> {
+    fn default() -> Self {
+        Self::Slice(&[])
+    }
+}
+
+#[derive(Default)]
+pub struct SelectProps<'c, E: Clone + PartialEq + 'static> {
+    pub options: SelectOptions<'c, E>,
+    pub value: Option<&'c dyn ObservableAs<E>>,
+    pub on_change: Option<&'c mut dyn FnMut(E)>,
+    pub class: Option<&'c ClassList<'c>>,
+    /// Attributes and styles to keep in sync, for what has no prop of its own.
+    pub attributes: &'c [Binding<'c>],
+}
+
+#[derive(Default)]
+pub struct MultiSelectProps<'c, E: Clone + PartialEq + 'static> {
+    pub options: SelectOptions<'c, E>,
+    pub value: Option<&'c dyn ObservableAs<[E]>>,
+    /// Called with all the selected values.
+    pub on_change: Option<&'c mut dyn FnMut(Vec<E>)>,
+    pub class: Option<&'c ClassList<'c>>,
+    /// Attributes and styles to keep in sync, for what has no prop of its own.
+    pub attributes: &'c [Binding<'c>],
+}
+
+/// A dropdown to pick one of the `options`.
+pub async fn select<'c, E: Clone + PartialEq + 'static>(
+    SelectProps {
+        options,
+        value,
+        mut on_change,
+        class,
+        attributes,
+    }: SelectProps<'c, E>,
+) {
+    let value = if let Some(v) = value { v } else { return };
+    render_select(
+        options,
+        false,
+        value,
+        |value, option| value == option,
+        |picked| {
+            if let (Some(picked), Some(f)) = (picked.into_iter().next(), on_change.as_mut()) {
+                f(picked);
+            }
+        },
+        class,
+        attributes,
+    )
+    .await
+}
+
+/// A list box to pick any number of the `options`.
+pub async fn multi_select<'c, E: Clone + PartialEq + 'static>(
+    MultiSelectProps {
+        options,
+        value,
+        mut on_change,
+        class,
+        attributes,
+    }: MultiSelectProps<'c, E>,
+) {
+    let value = if let Some(v) = value { v } else { return };
+    render_select(
+        options,
+        true,
+        value,
+        |value, option| value.contains(option),
+        |picked| {
+            if let Some(f) = on_change.as_mut() {
+                f(picked);
+            }
+        },
+        class,
+        attributes,
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn render_select<'c, E: Clone + PartialEq, Z: ?Sized>(
+    options: SelectOptions<'c, E>,
+    multiple: bool,
+    value: &'c dyn ObservableAs<Z>,
+    is_selected: impl Fn(&Z, &E) -> bool,
+    mut on_change: impl FnMut(Vec<E>),
+    class: Option<&'c ClassList<'c>>,
+    attributes: &'c [Binding<'c>],
+) {
+    let elem = create_element("select");
+    if multiple {
+        elem.set_attribute("multiple", "");
+    }
+    if let Some(class) = class {
+        class.set_dom(&elem);
+    }
+    let mut handlers = SmallVec::<[_; 1]>::new();
+    let manager = EventsManager::new();
+    if let Some(select_elem) = elem.web_as::<HtmlElement>() {
+        let h = create_handler(&manager, |_e: Event| QueuedEvent::Change());
+        select_elem.set_onchange(Some(h.get_function()));
+        handlers.push(h);
+    }
+
+    let rendered = RefCell::new(Vec::new());
+    let sync_selected = || {
+        let value = value.borrow_observable_as();
+        for (option, node) in rendered.borrow().iter() {
+            set_selected(node, is_selected(&value, option));
+        }
+    };
+    let update_options = async {
+        match options {
+            SelectOptions::Slice(slice) => {
+                *rendered.borrow_mut() = render_options(
+                    &elem,
+                    slice.iter().map(|(option, label)| (option.clone(), *label)),
+                );
+                sync_selected();
+            }
+            SelectOptions::List(list) => {
+                let (listener, mut last_version, mut seen_ids) = {
+                    let model = &*list.borrow_observable_as();
+                    *rendered.borrow_mut() = render_options(
+                        &elem,
+                        model
+                            .underlying_vector()
+                            .iter()
+                            .map(|(option, label)| (option.clone(), label.as_str())),
+                    );
+                    let model = ListModelPrivateAPIs(model);
+                    (
+                        model.add_listener(),
+                        model.get_version(),
+                        model.item_ids().clone(),
+                    )
+                };
+                let _guard = scopeguard::guard((), |_| {
+                    let b = list.borrow_observable_as();
+                    ListModelPrivateAPIs(&*b).remove_listener(listener);
+                });
+                // Values of new options continue after the first ones, so each stays unique.
+                let mut next_value = rendered.borrow().len();
+                let mut new_option = |(option, label): &(E, String)| {
+                    next_value += 1;
+                    (option.clone(), create_option(next_value - 1, label))
+                };
+                loop {
+                    sync_selected();
+                    list.until_change().await;
+                    let model = &*list.borrow_observable_as();
+                    let model_priv = ListModelPrivateAPIs(model);
+                    let catch_up;
+                    let changes: Box<dyn Iterator<Item = &Change<(E, String)>>> =
+                        match model_priv.changes_since_version(last_version) {
+                            Changes::Incremental(changes) => Box::new(changes),
+                            // Options still in the list keep their `<option>`s.
+                            Changes::Reset => {
+                                catch_up = model_priv.changes_from_ids(&seen_ids);
+                                Box::new(catch_up.iter())
+                            }
+                        };
+                    let rendered = &mut *rendered.borrow_mut();
+                    for change in changes {
+                        match change {
+                            Change::Splice {
+                                remove_range,
+                                replace_with,
+                            } => {
+                                for (_, node) in rendered.drain(remove_range.clone()) {
+                                    elem.remove_child(&node);
+                                }
+                                let before = rendered.get(remove_range.start).map(|(_, n)| n);
+                                let inserted: Vec<_> =
+                                    replace_with.iter().map(&mut new_option).collect();
+                                for (_, node) in inserted.iter() {
+                                    elem.insert_before(node, before);
+                                }
+                                rendered.splice(remove_range.start..remove_range.start, inserted);
+                            }
+                            Change::Remove { index } => {
+                                let (_, node) = rendered.remove(*index);
+                                elem.remove_child(&node);
+                            }
+                            Change::Insert { index, value } => {
+                                let (option, node) = new_option(value);
+                                elem.insert_before(&node, rendered.get(*index).map(|(_, n)| n));
+                                rendered.insert(*index, (option, node));
+                            }
+                            Change::Move { from, to } => {
+                                let item = rendered.remove(*from);
+                                elem.insert_before(&item.1, rendered.get(*to).map(|(_, n)| n));
+                                rendered.insert(*to, item);
+                            }
+                        }
+                    }
+                    last_version = model_priv.get_version();
+                    seen_ids = model_priv.item_ids().clone();
+                    model_priv.set_listener_version(listener, last_version);
+                }
+            }
+        }
+        std::future::pending::<()>().await
+    };
+    let future = update_options
+        .or(async {
+            loop {
+                value.until_change().await;
+                sync_selected();
+            }
+        })
+        .or(async {
+            manager.grab_waker().await;
+            loop {
+                let mut events = manager.get_queue().await;
+                for event in events.drain(..) {
+                    if let QueuedEvent::Change() = event {
+                        let picked = rendered
+                            .borrow()
+                            .iter()
+                            .filter(|(_, node)| is_option_selected(node))
+                            .map(|(option, _)| option.clone())
+                            .collect();
+                        on_change(picked);
+                        // Put back what `value` says, in case the change is not taken.
+                        sync_selected();
+                    }
+                }
+            }
+        })
+        .or(sync_bindings(&elem, attributes));
+    ElementFuture::new(future, elem.clone()).await;
+}
+
+/// Replace the `<option>`s in `select`, labelling each with its index as its value.
+fn render_options<'a, E: 'a>(
+    select: &Node,
+    options: impl Iterator<Item = (E, &'a str)>,
+) -> Vec<(E, Node)> {
+    while let Some(child) = select.first_child() {
+        select.remove_child(&child);
+    }
+    options
+        .enumerate()
+        .map(|(index, (option, label))| {
+            let node = create_option(index, label);
+            select.insert_before(&node, None);
+            (option, node)
+        })
+        .collect()
+}
+
+fn create_option(value: usize, label: &str) -> Node {
+    let node = create_element("option");
+    node.set_attribute("value", &value.to_string());
+    node.insert_before(&create_text_node(label), None);
+    node
+}
+
+fn set_selected(node: &Node, selected: bool) {
+    match node.web_as::<HtmlOptionElement>() {
+        Some(option) => option.set_selected(selected),
+        None if selected => node.set_attribute("selected", ""),
+        None => node.remove_attribute("selected"),
+    }
+}
+
+fn is_option_selected(node: &Node) -> bool {
+    node.web_as::<HtmlOptionElement>()
+        .is_some_and(|option| option.selected())
+}
@@ -0,0 +1,107 @@
+use futures_lite::FutureExt;
+use observables::{ObservableAs, ObservableAsExt};
+use smallvec::SmallVec;
+use web_sys::{HtmlInputElement, InputEvent};
+
+use crate::{
+    dom::{create_element, Node},
+    utils::{
+        bindings::{sync_bindings, Binding},
+        class_list::ClassList,
+    },
+};
+
+use super::{
+    events::{create_handler, EventsManager, QueuedEvent},
+    ElementFuture,
+};
+
+pub struct SliderChangeEvent {
+    node: HtmlInputElement,
+}
+impl SliderChangeEvent {
+    pub fn get_value(&self) -> f64 {
+        self.node.value_as_number()
+    }
+}
+
+#[derive(Default)]
+pub struct SliderProps<'c> {
+    pub value: Option<&'c dyn ObservableAs<f64>>,
+    /// Called as the user drags, not only when they let go.
+    pub on_change: Option<&'c mut dyn FnMut(SliderChangeEvent)>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub step: Option<f64>,
+    pub class: Option<&'c ClassList<'c>>,
+    /// Attributes and styles to keep in sync, for what has no prop of its own.
+    pub attributes: &'c [Binding<'c>],
+}
+
+/// An `<input type="range">`. The browser's range is 0 to 100 unless `min` and `max` are given.
+pub async fn slider<'c>(
+    SliderProps {
+        value,
+        mut on_change,
+        min,
+        max,
+        step,
+        class,
+        attributes,
+    }: SliderProps<'c>,
+) {
+    let elem = create_element("input");
+    elem.set_attribute("type", "range");
+    set_limits(&elem, min, max, step);
+    let value = value.unwrap_or(&0.0);
+    let mut handlers = SmallVec::<[_; 1]>::new();
+    let manager = EventsManager::new();
+    if let (Some(_), Some(input)) = (&on_change, elem.web_as::<HtmlInputElement>()) {
+        let h = create_handler(&manager, |e: InputEvent| QueuedEvent::Input(e));
+        input.set_oninput(Some(h.get_function()));
+        handlers.push(h);
+    }
+    if let Some(cl) = class {
+        cl.set_dom(&elem);
+    }
+    let future = (async {
+        manager.grab_waker().await;
+        loop {
+            let mut events = manager.get_queue().await;
+            for event in events.drain(..) {
+                // Events only come from handlers, which only exist on browser nodes.
+                let slider_change_event = SliderChangeEvent {
+                    node: elem.web_as::<HtmlInputElement>().unwrap().clone(),
+                };
+                if let (QueuedEvent::Input(_), Some(f)) = (event, on_change.as_mut()) {
+                    f(slider_change_event);
+                }
+            }
+        }
+    })
+    .or(async {
+        loop {
+            set_number(&elem, *value.borrow_observable_as());
+            value.until_change().await;
+        }
+    })
+    .or(sync_bindings(&elem, attributes));
+    ElementFuture::new(future, elem.clone()).await;
+}
+
+pub(super) fn set_limits(elem: &Node, min: Option<f64>, max: Option<f64>, step: Option<f64>) {
+    for (name, limit) in [("min", min), ("max", max), ("step", step)] {
+        if let Some(limit) = limit {
+            elem.set_attribute(name, &limit.to_string());
+        }
+    }
+}
+
+/// Set the value of a numeric input, unless it already reads as `value`.
+pub(super) fn set_number(elem: &Node, value: f64) {
+    match elem.web_as::<HtmlInputElement>() {
+        Some(input) if input.value().parse::<f64>() == Ok(value) => {}
+        Some(input) => input.set_value(&value.to_string()),
+        None => elem.set_attribute("value", &value.to_string()),
+    }
+}
@@ -6,14 +6,14 @@ use std::{
 
 use observables::{NextChangeFuture, ObservableAs, ObservableAsExt};
 
-use crate::window::DOCUMENT;
+use crate::dom::{create_text_node, Node};
 
 use super::ElementFuture;
 
 pub struct TextFuture<'c> {
     obs: &'c (dyn ObservableAs<str> + 'c),
     change_fut: NextChangeFuture<dyn ObservableAs<str> + 'c, &'c (dyn ObservableAs<str> + 'c)>,
-    node: web_sys::Text,
+    node: Node,
     set: bool,
 }
 
@@ -39,12 +39,12 @@ impl<'c> Future for TextFuture<'c> {
     }
 }
 pub async fn text<'c>(text: &'c dyn ObservableAs<str>) {
-    let node: web_sys::Text = DOCUMENT.with(|doc| doc.create_text_node(""));
+    let node = create_text_node(&text.borrow_observable_as());
     let fut = TextFuture {
         change_fut: NextChangeFuture::new(text),
         obs: text,
         node: node.clone(),
         set: false,
     };
-    ElementFuture::new(fut, node.into()).await;
+    ElementFuture::new(fut, node).await;
 }
@@ -1,38 +1,90 @@
+use std::{cell::Cell, rc::Rc};
+
+#[cfg(feature = "ssr")]
+use async_ui_headless::Node as ServerNode;
 use futures_lite::FutureExt;
-use observables::{ObservableAs, ObservableAsExt};
+use observables::{cell::ReactiveCell, ObservableAs, ObservableAsExt};
 use smallvec::SmallVec;
 use wasm_bindgen::JsCast;
-use web_sys::{HtmlElement, HtmlInputElement, HtmlTextAreaElement};
+use web_sys::{CompositionEvent, HtmlElement, HtmlInputElement, HtmlTextAreaElement};
 
-use crate::{utils::class_list::ClassList, window::DOCUMENT};
+use crate::{
+    dom::{create_element, Node},
+    utils::{
+        bindings::{sync_bindings, Binding},
+        class_list::ClassList,
+    },
+};
 
 use super::{
-    events::{create_handler, EventsManager, QueuedEvent},
+    events::{create_handler, Events, EventsManager, QueuedEvent},
     ElementFuture,
 };
 #[derive(Clone)]
 enum InputNode {
     OneLine(HtmlInputElement),
     MultiLine(HtmlTextAreaElement),
+    #[cfg(feature = "ssr")]
+    Server {
+        node: ServerNode,
+        multiline: bool,
+    },
 }
 
 impl InputNode {
-    fn as_elem(&self) -> &HtmlElement {
-        match self {
-            InputNode::OneLine(e) => e.unchecked_ref(),
-            InputNode::MultiLine(e) => e.unchecked_ref(),
-        }
-    }
     fn get_value(&self) -> String {
         match self {
             InputNode::OneLine(e) => e.value(),
-            InputNode::MultiLine(e) => e.inner_text(),
+            InputNode::MultiLine(e) => e.value(),
+            #[cfg(feature = "ssr")]
+            InputNode::Server {
+                node,
+                multiline: false,
+            } => node.attribute("value").unwrap_or_default(),
+            #[cfg(feature = "ssr")]
+            InputNode::Server {
+                node,
+                multiline: true,
+            } => node.text_content(),
         }
     }
+    /// Set the value, unless it is already `value`, keeping the caret where it was.
     fn set_value(&self, value: &str) {
+        if self.get_value() == value {
+            return;
+        }
         match self {
-            InputNode::OneLine(e) => e.set_value(value),
-            InputNode::MultiLine(e) => e.set_inner_text(value),
+            InputNode::OneLine(e) => {
+                let selection = (e.selection_start(), e.selection_end());
+                e.set_value(value);
+                if let (Ok(Some(start)), Ok(Some(end))) = selection {
+                    let len = value.encode_utf16().count() as u32;
+                    let _ = e.set_selection_range(start.min(len), end.min(len));
+                }
+            }
+            InputNode::MultiLine(e) => {
+                let selection = (e.selection_start(), e.selection_end());
+                e.set_value(value);
+                if let (Ok(Some(start)), Ok(Some(end))) = selection {
+                    let len = value.encode_utf16().count() as u32;
+                    let _ = e.set_selection_range(start.min(len), end.min(len));
+                }
+            }
+            #[cfg(feature = "ssr")]
+            InputNode::Server {
+                node,
+                multiline: false,
+            } => node.set_attribute("value", value),
+            #[cfg(feature = "ssr")]
+            InputNode::Server {
+                node,
+                multiline: true,
+            } => {
+                node.children()
+                    .iter()
+                    .for_each(|child| node.remove_child(child));
+                node.append_child(&ServerNode::new_text(value));
+            }
         }
     }
 }
@@ -56,9 +108,60 @@ pub struct TextInputProps<'c> {
     pub multiline: Option<bool>,
     pub class: Option<&'c ClassList<'c>>,
     pub placeholder: Option<&'c dyn ObservableAs<str>>,
+    /// The `type` of a one-line input, like `"password"`, `"email"`, or `"number"`.
+    pub input_type: Option<&'c str>,
+    pub max_length: Option<u32>,
+    pub readonly: Option<&'c dyn ObservableAs<bool>>,
+    /// Attributes and styles to keep in sync, for what has no prop of its own.
+    pub attributes: &'c [Binding<'c>],
+}
+
+/// A text input whose events can be awaited, instead of handled in the `on_*` props.
+pub struct TextInput {
+    changes: Rc<EventsManager<TextInputEvent>>,
+    submits: Rc<EventsManager<TextInputEvent>>,
+    blurs: Rc<EventsManager<TextInputEvent>>,
+    focuses: Rc<EventsManager<TextInputEvent>>,
+}
+impl Default for TextInput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl TextInput {
+    pub fn new() -> Self {
+        Self {
+            changes: EventsManager::new(),
+            submits: EventsManager::new(),
+            blurs: EventsManager::new(),
+            focuses: EventsManager::new(),
+        }
+    }
+    /// Render the input. The `on_*` props, if given, are still called too.
+    pub async fn render<'c>(&self, props: TextInputProps<'c>) {
+        render_text_input(props, Some(self)).await
+    }
+    /// Each time the user edits the text.
+    pub fn changes(&self) -> Events<'_, TextInputEvent> {
+        Events::new(&self.changes)
+    }
+    /// Each time the user presses Enter.
+    pub fn submits(&self) -> Events<'_, TextInputEvent> {
+        Events::new(&self.submits)
+    }
+    pub fn blurs(&self) -> Events<'_, TextInputEvent> {
+        Events::new(&self.blurs)
+    }
+    pub fn focuses(&self) -> Events<'_, TextInputEvent> {
+        Events::new(&self.focuses)
+    }
 }
 
-pub async fn text_input<'c>(
+pub async fn text_input<'c>(props: TextInputProps<'c>) {
+    render_text_input(props, None).await
+}
+
+async fn render_text_input<'c>(
     TextInputProps {
         text,
         mut on_change_text,
@@ -68,51 +171,89 @@ pub async fn text_input<'c>(
         multiline,
         class,
         placeholder,
+        input_type,
+        max_length,
+        readonly,
+        attributes,
     }: TextInputProps<'c>,
+    handle: Option<&TextInput>,
 ) {
     let text = text.unwrap_or(&"");
     let placeholder = placeholder.unwrap_or(&"");
+    let readonly = [Binding::Flag("readonly", readonly.unwrap_or(&false))];
     let multiline = multiline.unwrap_or_default();
 
-    let input = DOCUMENT.with(|doc| {
-        let elem = doc
-            .create_element(match multiline {
-                true => "textarea",
-                false => "input",
-            })
-            .expect("create element failed");
-        match multiline {
-            true => InputNode::MultiLine(elem.unchecked_into()),
-            false => InputNode::OneLine(elem.unchecked_into()),
-        }
+    let elem = create_element(match multiline {
+        true => "textarea",
+        false => "input",
     });
+    let input = match &elem {
+        Node::Web(e) if multiline => InputNode::MultiLine(e.clone().unchecked_into()),
+        Node::Web(e) => InputNode::OneLine(e.clone().unchecked_into()),
+        #[cfg(feature = "ssr")]
+        Node::Server(node) => InputNode::Server {
+            node: node.clone(),
+            multiline,
+        },
+    };
 
-    let mut handlers = SmallVec::<[_; 5]>::new();
-    let manager = EventsManager::new();
-    let input_elem = input.as_elem();
-
-    if on_change_text.is_some() {
-        let h = create_handler(&manager, |e| QueuedEvent::Input(e));
-        input_elem.set_oninput(Some(h.get_function()));
-        handlers.push(h);
+    if let (Some(input_type), false) = (input_type, multiline) {
+        elem.set_attribute("type", input_type);
     }
-    if on_submit.is_some() {
-        let h = create_handler(&manager, |e| QueuedEvent::KeyPress(e));
-        input_elem.set_onkeypress(Some(h.get_function()));
-        handlers.push(h);
+    if let Some(max_length) = max_length {
+        elem.set_attribute("maxlength", &max_length.to_string());
     }
-    if on_blur.is_some() {
-        let h = create_handler(&manager, |e| QueuedEvent::Blur(e));
-        input_elem.set_onblur(Some(h.get_function()));
+
+    let mut handlers = SmallVec::<[_; 7]>::new();
+    let manager = EventsManager::new();
+    // Set right in the handlers, so the text is never written in the middle of a composition.
+    let composing = Rc::new(Cell::new(false));
+    // Bumped when a composition ends, to write the text that was held back.
+    let composition_ends = ReactiveCell::new(0u32);
+
+    if let Some(input_elem) = elem.web_as::<HtmlElement>() {
+        let composing_1 = composing.clone();
+        let h = create_handler(&manager, move |_e: CompositionEvent| {
+            composing_1.set(true);
+            QueuedEvent::CompositionStart()
+        });
+        input_elem
+            .add_event_listener_with_callback("compositionstart", h.get_function())
+            .expect("add compositionstart listener failed");
         handlers.push(h);
-    }
-    if on_focus.is_some() {
-        let h = create_handler(&manager, |e| QueuedEvent::Focus(e));
-        input_elem.set_onfocus(Some(h.get_function()));
+        let composing_1 = composing.clone();
+        let h = create_handler(&manager, move |_e: CompositionEvent| {
+            composing_1.set(false);
+            QueuedEvent::CompositionEnd()
+        });
+        input_elem
+            .add_event_listener_with_callback("compositionend", h.get_function())
+            .expect("add compositionend listener failed");
         handlers.push(h);
+
+        if on_change_text.is_some() || handle.is_some() {
+            let h = create_handler(&manager, QueuedEvent::Input);
+            input_elem.set_oninput(Some(h.get_function()));
+            handlers.push(h);
+        }
+        if on_submit.is_some() || handle.is_some() {
+            let h = create_handler(&manager, QueuedEvent::KeyPress);
+            input_elem.set_onkeypress(Some(h.get_function()));
+            handlers.push(h);
+        }
+        if on_blur.is_some() || handle.is_some() {
+            let h = create_handler(&manager, QueuedEvent::Blur);
+            input_elem.set_onblur(Some(h.get_function()));
+            handlers.push(h);
+        }
+        if on_focus.is_some() || handle.is_some() {
+            let h = create_handler(&manager, QueuedEvent::Focus);
+            input_elem.set_onfocus(Some(h.get_function()));
+            handlers.push(h);
+        }
     }
     if let Some(class) = class {
-        class.set_dom(input.as_elem().class_list());
+        class.set_dom(&elem);
     }
 
     let future = (async {
@@ -120,44 +261,51 @@ pub async fn text_input<'c>(
         loop {
             let mut events = manager.get_queue().await;
             for event in events.drain(..) {
-                let text_input_event = TextInputEvent {
-                    node: input.clone(),
-                };
-                match event {
-                    QueuedEvent::Input(_e) => {
-                        on_change_text.as_mut().map(|f| f(text_input_event));
-                    }
-                    QueuedEvent::KeyPress(e) => {
-                        if e.key() == "Enter" {
-                            e.prevent_default();
-                            on_submit.as_mut().map(|f| f(text_input_event));
-                        }
-                    }
-                    QueuedEvent::Blur(_e) => {
-                        on_blur.as_mut().map(|f| f(text_input_event));
+                let (callback, queue) = match event {
+                    QueuedEvent::Input(_e) => (&mut on_change_text, handle.map(|h| &h.changes)),
+                    QueuedEvent::KeyPress(e) if e.key() == "Enter" => {
+                        e.prevent_default();
+                        (&mut on_submit, handle.map(|h| &h.submits))
                     }
-                    QueuedEvent::Focus(_e) => {
-                        on_focus.as_mut().map(|f| f(text_input_event));
+                    QueuedEvent::Blur(_e) => (&mut on_blur, handle.map(|h| &h.blurs)),
+                    QueuedEvent::Focus(_e) => (&mut on_focus, handle.map(|h| &h.focuses)),
+                    QueuedEvent::CompositionEnd() => {
+                        *composition_ends.borrow_mut() += 1;
+                        continue;
                     }
-                    _ => {}
+                    _ => continue,
+                };
+                if let Some(queue) = queue {
+                    queue.forward(TextInputEvent {
+                        node: input.clone(),
+                    });
+                }
+                if let Some(f) = callback {
+                    f(TextInputEvent {
+                        node: input.clone(),
+                    });
                 }
             }
         }
     })
     .or(async {
         loop {
-            input.set_value(&*text.borrow_observable_as());
-            text.until_change().await;
+            if !composing.get() {
+                input.set_value(&text.borrow_observable_as());
+            }
+            text.until_change()
+                .or(composition_ends.as_observable().until_change())
+                .await;
         }
     })
     .or(async {
         loop {
-            input_elem
-                .set_attribute("placeholder", &*placeholder.borrow_observable_as())
-                .expect("set placeholder failed");
+            elem.set_attribute("placeholder", &placeholder.borrow_observable_as());
             placeholder.until_change().await;
         }
-    });
+    })
+    .or(sync_bindings(&elem, attributes))
+    .or(sync_bindings(&elem, &readonly));
 
-    ElementFuture::new(future, input.as_elem().clone().into()).await;
+    ElementFuture::new(future, elem.clone()).await;
 }
@@ -1,4 +1,13 @@
-use crate::{utils::class_list::ClassList, window::DOCUMENT, Fragment};
+use futures_lite::FutureExt;
+
+use crate::{
+    dom::create_element,
+    utils::{
+        bindings::{sync_bindings, Binding},
+        class_list::ClassList,
+    },
+    Fragment,
+};
 
 use super::ElementFuture;
 
@@ -6,6 +15,8 @@ pub struct ViewProps<'c> {
     pub children: Fragment<'c>,
     pub class: Option<&'c ClassList<'c>>,
     pub element_tag: &'c str,
+    /// Attributes and styles to keep in sync, for what has no prop of its own.
+    pub attributes: &'c [Binding<'c>],
 }
 impl<'c> Default for ViewProps<'c> {
     fn default() -> Self {
@@ -13,6 +24,7 @@ impl<'c> Default for ViewProps<'c> {
             children: Default::default(),
             class: Default::default(),
             element_tag: "div",
+            attributes: &[],
         }
     }
 }
@@ -21,14 +33,13 @@ pub async fn view<'c>(
         children,
         class,
         element_tag,
+        attributes,
     }: ViewProps<'c>,
 ) {
-    let elem = DOCUMENT.with(|doc| {
-        doc.create_element(element_tag)
-            .expect("create element failed")
-    });
+    let elem = create_element(element_tag);
     if let Some(class) = class {
-        class.set_dom(elem.class_list());
+        class.set_dom(&elem);
     }
-    ElementFuture::new(children, elem.into()).await;
+    let future = children.or(sync_bindings(&elem, attributes));
+    ElementFuture::new(future, elem.clone()).await;
 }
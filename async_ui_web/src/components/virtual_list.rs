@@ -0,0 +1,513 @@
+use std::{collections::BTreeMap, future::IntoFuture, ops::Range, rc::Rc};
+
+use async_task::Task;
+use async_ui_core::{
+    backend::BackendTrait,
+    executor::spawn_local_in,
+    list::{Change, Changes, ListModelPrivateAPIs},
+    vnode::{
+        node_concrete::{ConcreteNodeVNode, RefNode},
+        VNodeTrait, WithVNode,
+    },
+};
+use futures_lite::{pin, FutureExt};
+use observables::{ObservableAs, ObservableAsExt};
+use scoped_async_spawn::SpawnGuard;
+use smallvec::SmallVec;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, Event, ResizeObserver, ResizeObserverEntry};
+
+use crate::{
+    backend::Backend,
+    dom::{create_element, new_element, Node},
+    hydrate::is_hydrating,
+    utils::class_list::ClassList,
+};
+
+use super::{
+    events::{create_handler, EventsManager, QueuedEvent},
+    ElementFuture, ListModel,
+};
+
+#[derive(Clone, Copy)]
+pub enum ItemHeight {
+    /// Every row is exactly this many pixels tall.
+    Fixed(f64),
+    /// Rows are measured once rendered.
+    /// Rows that haven't been rendered yet are assumed to be `estimate` pixels tall.
+    Measured { estimate: f64 },
+}
+
+pub struct VirtualListProps<'c, T: Clone, F: IntoFuture<Output = ()>> {
+    pub data: Option<&'c dyn ObservableAs<ListModel<T>>>,
+    pub render: Option<&'c dyn Fn(T) -> F>,
+    pub class: Option<&'c ClassList<'c>>,
+    pub item_height: ItemHeight,
+    /// The scrolling element the list is in.
+    /// If `None`, the list scrolls by itself; give it a height through `class`.
+    pub scroll_container: Option<&'c Element>,
+    /// How many rows to keep rendered above and below the visible ones.
+    pub overscan: usize,
+}
+impl<'c, T: Clone + 'c, F: IntoFuture<Output = ()>> Default for VirtualListProps<'c, T, F> {
+    fn default() -> Self {
+        Self {
+            data: Default::default(),
+            render: Default::default(),
+            class: Default::default(),
+            item_height: ItemHeight::Measured { estimate: 24.0 },
+            scroll_container: Default::default(),
+            overscan: 4,
+        }
+    }
+}
+
+/// The height of every item of the list.
+struct Heights {
+    item_height: ItemHeight,
+    /// Only used for [ItemHeight::Measured].
+    measured: Vec<f64>,
+    /// Prefix sums of `measured`, so finding the row at a scroll position doesn't walk the list.
+    sums: Fenwick,
+}
+impl Heights {
+    fn new(item_height: ItemHeight) -> Self {
+        Self {
+            item_height,
+            measured: Vec::new(),
+            sums: Fenwick::new(&[]),
+        }
+    }
+    fn get(&self, index: usize) -> f64 {
+        match self.item_height {
+            ItemHeight::Fixed(height) => height,
+            ItemHeight::Measured { .. } => self.measured[index],
+        }
+    }
+    fn set(&mut self, index: usize, height: f64) {
+        let old = std::mem::replace(&mut self.measured[index], height);
+        if old != height {
+            self.sums.add(index, height - old);
+        }
+    }
+    fn splice(&mut self, range: Range<usize>, inserted: usize) {
+        if let ItemHeight::Measured { estimate } = self.item_height {
+            self.measured
+                .splice(range, std::iter::repeat_n(estimate, inserted));
+            self.sums = Fenwick::new(&self.measured);
+        }
+    }
+    fn move_item(&mut self, from: usize, to: usize) {
+        if let ItemHeight::Measured { .. } = self.item_height {
+            let height = self.measured.remove(from);
+            self.measured.insert(to, height);
+            self.sums = Fenwick::new(&self.measured);
+        }
+    }
+    /// Where item `index` starts.
+    fn top(&self, index: usize) -> f64 {
+        match self.item_height {
+            ItemHeight::Fixed(height) => height * index as f64,
+            ItemHeight::Measured { .. } => self.sums.sum_before(index),
+        }
+    }
+    fn total(&self, len: usize) -> f64 {
+        self.top(len)
+    }
+    /// The first item that ends below `y`, and where it starts.
+    fn item_at(&self, y: f64, len: usize) -> (usize, f64) {
+        match self.item_height {
+            ItemHeight::Fixed(height) => {
+                let index = ((y / height).floor().max(0.0) as usize).min(len);
+                (index, index as f64 * height)
+            }
+            ItemHeight::Measured { .. } => self.sums.find(y),
+        }
+    }
+}
+
+/// Fenwick tree summing item heights.
+struct Fenwick(Vec<f64>);
+impl Fenwick {
+    fn new(heights: &[f64]) -> Self {
+        let mut tree = vec![0.0; heights.len() + 1];
+        for i in 1..tree.len() {
+            tree[i] += heights[i - 1];
+            let parent = i + (i & i.wrapping_neg());
+            if parent < tree.len() {
+                tree[parent] += tree[i];
+            }
+        }
+        Self(tree)
+    }
+    fn add(&mut self, at: usize, delta: f64) {
+        let mut i = at + 1;
+        while i < self.0.len() {
+            self.0[i] += delta;
+            i += i & i.wrapping_neg();
+        }
+    }
+    fn sum_before(&self, at: usize) -> f64 {
+        let (mut i, mut sum) = (at, 0.0);
+        while i > 0 {
+            sum += self.0[i];
+            i -= i & i.wrapping_neg();
+        }
+        sum
+    }
+    /// How many items end at or above `y`, and where the next one starts.
+    fn find(&self, y: f64) -> (usize, f64) {
+        let len = self.0.len() - 1;
+        let (mut index, mut top) = (0, 0.0);
+        let mut step = if len == 0 { 0 } else { 1 << len.ilog2() };
+        while step > 0 {
+            if index + step <= len && top + self.0[index + step] <= y {
+                index += step;
+                top += self.0[index];
+            }
+            step >>= 1;
+        }
+        (index, top)
+    }
+}
+
+struct Row {
+    node: Node,
+    _task: Task<()>,
+    top: f64,
+    /// Whether `heights` has this row's current height.
+    measured: bool,
+}
+
+struct Rows {
+    spacer: Node,
+    observer: Option<ResizeObserver>,
+    mounted: BTreeMap<usize, Row>,
+}
+impl Rows {
+    fn unmount(&self, row: Row) {
+        if let (Some(observer), Some(elem)) = (&self.observer, row.node.web_as::<Element>()) {
+            observer.unobserve(elem);
+        }
+        self.spacer.remove_child(&row.node);
+    }
+    /// Give every mounted row its new index, unmounting those that map to `None`.
+    fn remap(&mut self, map: impl Fn(usize) -> Option<usize>) {
+        let old = std::mem::take(&mut self.mounted);
+        for (index, row) in old {
+            match map(index) {
+                Some(new_index) => {
+                    self.mounted.insert(new_index, row);
+                }
+                None => self.unmount(row),
+            }
+        }
+    }
+}
+
+impl Drop for Rows {
+    fn drop(&mut self) {
+        if let Some(observer) = self.observer.as_ref() {
+            observer.disconnect();
+        }
+    }
+}
+
+fn row_style(top: f64, item_height: ItemHeight) -> String {
+    match item_height {
+        ItemHeight::Fixed(height) => {
+            format!("position: absolute; left: 0; right: 0; top: {top}px; height: {height}px;")
+        }
+        ItemHeight::Measured { .. } => {
+            format!("position: absolute; left: 0; right: 0; top: {top}px;")
+        }
+    }
+}
+
+/// Like [list][super::list], but only renders the items that are scrolled into view.
+///
+/// Items are laid out with absolute positioning inside a spacer as tall as the whole list,
+/// so rows should not rely on margins collapsing with their neighbors.
+/// When rendering on the server there is no viewport, so only the first `overscan` items are rendered.
+pub async fn virtual_list<'c, T: Clone + 'c, F: IntoFuture<Output = ()> + 'c>(
+    VirtualListProps {
+        data,
+        render,
+        class,
+        item_height,
+        scroll_container,
+        overscan,
+    }: VirtualListProps<'c, T, F>,
+) {
+    let container_node = create_element("div");
+    if let Some(class) = class {
+        class.set_dom(&container_node);
+    }
+    if scroll_container.is_none() {
+        container_node.set_attribute("style", "overflow-y: auto;");
+    }
+    let (data, render) = match (data, render) {
+        (Some(d), Some(r)) => (d, r),
+        _ => {
+            return;
+        }
+    };
+    // Which rows the server rendered depends on a viewport it didn't have, so start over.
+    if is_hydrating() {
+        while let Some(child) = container_node.first_child() {
+            container_node.remove_child(&child);
+        }
+    }
+    let spacer = new_element("div");
+    container_node.insert_before(&spacer, None);
+
+    let manager = EventsManager::new();
+    let mut handlers = SmallVec::<[_; 2]>::new();
+    let scroll_target = scroll_container
+        .cloned()
+        .or_else(|| container_node.web_as::<Element>().cloned());
+    let mut observer = None;
+    if let Some(target) = scroll_target.as_ref() {
+        let h = create_handler(&manager, |_ev: Event| QueuedEvent::Scroll());
+        target
+            .add_event_listener_with_callback("scroll", h.get_function())
+            .expect("add scroll listener failed");
+        handlers.push(h);
+        // Watches the scroll target and every mounted row.
+        let h = create_handler(&manager, QueuedEvent::Resize);
+        let resize_observer =
+            ResizeObserver::new(h.get_function()).expect("create resize observer failed");
+        resize_observer.observe(target);
+        observer = Some(resize_observer);
+        handlers.push(h);
+    }
+    let _cleanup = scopeguard::guard((), |_| {
+        if let (Some(target), Some(h)) = (scroll_target.as_ref(), handlers.first()) {
+            let _ = target.remove_event_listener_with_callback("scroll", h.get_function());
+        }
+    });
+
+    let container_node_copy = container_node.clone();
+    let inside = async move {
+        let parent_vnode = Backend::get_vnode_key().with(Clone::clone);
+        let parent_context = parent_vnode.get_context_map();
+        let guard = SpawnGuard::new();
+        pin!(guard);
+        let mut create_row = |value: T| {
+            let node = new_element("div");
+            let fut = WithVNode::new(
+                render(value).into_future(),
+                Rc::new(
+                    ConcreteNodeVNode::new(
+                        RefNode::<Backend>::Parent {
+                            parent: node.clone(),
+                        },
+                        parent_context.clone(),
+                    )
+                    .into(),
+                ),
+            );
+            let task = spawn_local_in(parent_context, guard.as_mut().convert_future(fut));
+            (node, task)
+        };
+        let mut rows = Rows {
+            spacer,
+            observer,
+            mounted: BTreeMap::new(),
+        };
+        let mut heights = Heights::new(item_height);
+        // Elements the resize observer reported since the last update.
+        let mut resized = Vec::<Element>::new();
+        let (listener, mut last_version, mut seen_ids) = {
+            let model = ListModelPrivateAPIs(&*data.borrow_observable_as());
+            heights.splice(0..0, model.0.len());
+            (
+                model.add_listener(),
+                model.get_version(),
+                model.item_ids().clone(),
+            )
+        };
+        let _guard = scopeguard::guard((), |_| {
+            let b = data.borrow_observable_as();
+            ListModelPrivateAPIs(&*b).remove_listener(listener);
+        });
+        manager.grab_waker().await;
+        loop {
+            {
+                let model = &*data.borrow_observable_as();
+                let model_priv = ListModelPrivateAPIs(model);
+                let len = model.len();
+
+                // Rows stay mounted through changes elsewhere in the list; only their index moves.
+                if model_priv.get_version() != last_version {
+                    let catch_up;
+                    let changes: Box<dyn Iterator<Item = &Change<T>>> =
+                        match model_priv.changes_since_version(last_version) {
+                            Changes::Incremental(changes) => Box::new(changes),
+                            // Items still in the list keep their rows.
+                            Changes::Reset => {
+                                catch_up = model_priv.changes_from_ids(&seen_ids);
+                                Box::new(catch_up.iter())
+                            }
+                        };
+                    for change in changes {
+                        match change {
+                            Change::Splice {
+                                remove_range,
+                                replace_with,
+                            } => {
+                                let (range, inserted) = (remove_range.clone(), replace_with.len());
+                                rows.remap(|i| {
+                                    if i < range.start {
+                                        Some(i)
+                                    } else if i < range.end {
+                                        None
+                                    } else {
+                                        Some(i - range.len() + inserted)
+                                    }
+                                });
+                                heights.splice(range, inserted);
+                            }
+                            Change::Remove { index } => {
+                                let index = *index;
+                                rows.remap(|i| match i.cmp(&index) {
+                                    std::cmp::Ordering::Less => Some(i),
+                                    std::cmp::Ordering::Equal => None,
+                                    std::cmp::Ordering::Greater => Some(i - 1),
+                                });
+                                heights.splice(index..index + 1, 0);
+                            }
+                            Change::Insert { index, .. } => {
+                                let index = *index;
+                                rows.remap(|i| Some(if i < index { i } else { i + 1 }));
+                                heights.splice(index..index, 1);
+                            }
+                            Change::Move { from, to } => {
+                                let (from, to) = (*from, *to);
+                                rows.remap(|i| {
+                                    if i == from {
+                                        return Some(to);
+                                    }
+                                    let i = if i > from { i - 1 } else { i };
+                                    Some(if i >= to { i + 1 } else { i })
+                                });
+                                heights.move_item(from, to);
+                            }
+                        }
+                    }
+                    last_version = model_priv.get_version();
+                    seen_ids = model_priv.item_ids().clone();
+                    model_priv.set_listener_version(listener, last_version);
+                }
+
+                if let ItemHeight::Measured { .. } = item_height {
+                    // Only rows that are new or resized, since measuring forces a layout.
+                    for (index, row) in rows.mounted.iter_mut() {
+                        let elem = match row.node.web_as::<Element>() {
+                            Some(elem) => elem,
+                            None => continue,
+                        };
+                        if !row.measured || resized.contains(elem) {
+                            row.measured = true;
+                            heights.set(*index, elem.get_bounding_client_rect().height());
+                        }
+                    }
+                }
+                resized.clear();
+
+                // The part of the list that is scrolled into view, in the list's own coordinates.
+                let (view_top, view_height) = match (scroll_container, &container_node) {
+                    (Some(container), Node::Web(_)) => {
+                        let spacer_top = rows
+                            .spacer
+                            .web_as::<Element>()
+                            .map_or(0.0, |s| s.get_bounding_client_rect().top());
+                        (
+                            container.get_bounding_client_rect().top() - spacer_top,
+                            container.client_height() as f64,
+                        )
+                    }
+                    (None, Node::Web(_)) => {
+                        let elem = container_node.web_as::<Element>().unwrap();
+                        (elem.scroll_top() as f64, elem.client_height() as f64)
+                    }
+                    #[cfg(feature = "ssr")]
+                    (_, Node::Server(_)) => (0.0, 0.0),
+                };
+                let (first, first_top) = heights.item_at(view_top, len);
+                let mut end = first;
+                let mut end_top = first_top;
+                while end < len && end_top < view_top + view_height {
+                    end_top += heights.get(end);
+                    end += 1;
+                }
+                let start = first.saturating_sub(overscan);
+                let end = (end + overscan).min(len);
+
+                rows.remap(|i| (start..end).contains(&i).then_some(i));
+                let mut top = heights.top(start);
+                for index in start..end {
+                    let row = rows.mounted.entry(index).or_insert_with(|| {
+                        let (node, task) = create_row(model.underlying_vector()[index].clone());
+                        if let (Some(observer), Some(elem)) =
+                            (rows.observer.as_ref(), node.web_as::<Element>())
+                        {
+                            observer.observe(elem);
+                        }
+                        rows.spacer.insert_before(&node, None);
+                        Row {
+                            node,
+                            _task: task,
+                            top: f64::NAN,
+                            measured: false,
+                        }
+                    });
+                    if row.top != top {
+                        row.top = top;
+                        row.node
+                            .set_attribute("style", &row_style(top, item_height));
+                    }
+                    top += heights.get(index);
+                }
+                rows.spacer.set_attribute(
+                    "style",
+                    &format!("position: relative; height: {}px;", heights.total(len)),
+                );
+            }
+            data.until_change()
+                .or(async {
+                    for event in manager.get_queue().await.drain(..) {
+                        if let QueuedEvent::Resize(entries) = event {
+                            resized.extend(entries.iter().map(|entry| {
+                                entry.unchecked_into::<ResizeObserverEntry>().target()
+                            }));
+                        }
+                    }
+                })
+                .await;
+        }
+    };
+    ElementFuture::new(inside, container_node_copy).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heights_find_rows_after_changes() {
+        let mut heights = Heights::new(ItemHeight::Measured { estimate: 10.0 });
+        heights.splice(0..0, 5);
+        heights.set(1, 30.0);
+        heights.move_item(1, 3);
+        heights.splice(0..1, 2);
+        // 10, 10, 10, 10, 30, 10
+        assert_eq!(heights.total(6), 80.0);
+        assert_eq!(heights.top(5), 70.0);
+        assert_eq!(heights.item_at(-5.0, 6), (0, 0.0));
+        assert_eq!(heights.item_at(29.0, 6), (2, 20.0));
+        assert_eq!(heights.item_at(30.0, 6), (3, 30.0));
+        assert_eq!(heights.item_at(69.0, 6), (4, 40.0));
+        assert_eq!(heights.item_at(100.0, 6), (6, 80.0));
+    }
+}
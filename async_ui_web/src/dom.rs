@@ -0,0 +1,239 @@
+use std::cell::Cell;
+
+#[cfg(feature = "ssr")]
+use async_ui_headless::Node as ServerNode;
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+
+use crate::{
+    hydrate::{self, claim, Expected},
+    window::DOCUMENT,
+};
+
+thread_local! {
+    static RENDERING_ON_SERVER: Cell<bool> = const { Cell::new(false) };
+}
+#[cfg(feature = "ssr")]
+pub(crate) fn set_rendering_on_server(value: bool) {
+    RENDERING_ON_SERVER.with(|r| r.set(value));
+}
+/// Whether components are currently being rendered to a string by `ssr`.
+///
+/// Always `false` without the `ssr` feature.
+pub fn is_rendering_on_server() -> bool {
+    cfg!(feature = "ssr") && RENDERING_ON_SERVER.with(Cell::get)
+}
+
+/// A node managed by Async UI.
+///
+/// In the browser this is a `web_sys::Node`.
+/// When rendering on the server (with the `ssr` feature) it is an in-memory node that is serialized to HTML.
+#[derive(Clone, PartialEq)]
+pub enum Node {
+    Web(web_sys::Node),
+    #[cfg(feature = "ssr")]
+    Server(ServerNode),
+}
+
+/// Create an element, or adopt the existing one when hydrating.
+pub fn create_element(tag: &str) -> Node {
+    claim(Expected::Element(tag)).unwrap_or_else(|| new_element(tag))
+}
+/// Create an element, never adopting an existing one.
+pub(crate) fn new_element(tag: &str) -> Node {
+    created(|| {
+        #[cfg(feature = "ssr")]
+        if is_rendering_on_server() {
+            return Node::Server(ServerNode::new_element(tag));
+        }
+        DOCUMENT.with(|doc| {
+            Node::Web(
+                doc.create_element(tag)
+                    .expect("create element failed")
+                    .into(),
+            )
+        })
+    })
+}
+/// Create a text node, or adopt the existing one when hydrating.
+pub fn create_text_node(data: &str) -> Node {
+    claim(Expected::Text(data)).unwrap_or_else(|| new_text_node(data))
+}
+/// Create a text node, never adopting an existing one.
+pub(crate) fn new_text_node(data: &str) -> Node {
+    created(|| {
+        #[cfg(feature = "ssr")]
+        if is_rendering_on_server() {
+            return Node::Server(ServerNode::new_text(data));
+        }
+        DOCUMENT.with(|doc| Node::Web(doc.create_text_node(data).into()))
+    })
+}
+pub fn create_comment(data: &str) -> Node {
+    created(|| {
+        #[cfg(feature = "ssr")]
+        if is_rendering_on_server() {
+            return Node::Server(ServerNode::new_comment(data));
+        }
+        DOCUMENT.with(|doc| Node::Web(doc.create_comment(data).into()))
+    })
+}
+/// Tell the hydration pass, if any, that the new node belongs to the client.
+fn created(create: impl FnOnce() -> Node) -> Node {
+    let node = create();
+    hydrate::created(&node);
+    node
+}
+
+impl Node {
+    pub fn web(&self) -> Option<&web_sys::Node> {
+        match self {
+            Node::Web(node) => Some(node),
+            #[cfg(feature = "ssr")]
+            Node::Server(_) => None,
+        }
+    }
+    /// The browser node cast to a more specific type, for setting up event handlers and such.
+    pub fn web_as<T: JsCast>(&self) -> Option<&T> {
+        self.web().map(JsCast::unchecked_ref)
+    }
+    pub fn insert_before(&self, child: &Node, before: Option<&Node>) {
+        match (self, child) {
+            (Node::Web(parent), Node::Web(child)) => {
+                parent
+                    .insert_before(child, before.map(|b| b.web().expect_throw("insert failed")))
+                    .expect_throw("insert failed");
+            }
+            #[cfg(feature = "ssr")]
+            (Node::Server(parent), Node::Server(child)) => parent.insert_before(
+                child,
+                before.map(|b| match b {
+                    Node::Server(b) => b,
+                    Node::Web(_) => panic!("insert failed"),
+                }),
+            ),
+            #[cfg(feature = "ssr")]
+            _ => panic!("cannot mix browser and server nodes"),
+        }
+    }
+    pub fn remove_child(&self, child: &Node) {
+        match (self, child) {
+            (Node::Web(parent), Node::Web(child)) => {
+                parent.remove_child(child).expect_throw("remove failed");
+            }
+            #[cfg(feature = "ssr")]
+            (Node::Server(parent), Node::Server(child)) => parent.remove_child(child),
+            #[cfg(feature = "ssr")]
+            _ => panic!("cannot mix browser and server nodes"),
+        }
+    }
+    pub fn first_child(&self) -> Option<Node> {
+        match self {
+            Node::Web(node) => node.first_child().map(Node::Web),
+            #[cfg(feature = "ssr")]
+            Node::Server(node) => node.first_child().map(Node::Server),
+        }
+    }
+    pub fn previous_sibling(&self) -> Option<Node> {
+        match self {
+            Node::Web(node) => node.previous_sibling().map(Node::Web),
+            #[cfg(feature = "ssr")]
+            Node::Server(node) => node.previous_sibling().map(Node::Server),
+        }
+    }
+    pub fn next_sibling(&self) -> Option<Node> {
+        match self {
+            Node::Web(node) => node.next_sibling().map(Node::Web),
+            #[cfg(feature = "ssr")]
+            Node::Server(node) => node.next_sibling().map(Node::Server),
+        }
+    }
+    pub fn set_attribute(&self, name: &str, value: &str) {
+        hydrate::bound(self, name);
+        match self {
+            Node::Web(node) => node
+                .unchecked_ref::<web_sys::Element>()
+                .set_attribute(name, value)
+                .expect("set attribute failed"),
+            #[cfg(feature = "ssr")]
+            Node::Server(node) => node.set_attribute(name, value),
+        }
+    }
+    pub fn remove_attribute(&self, name: &str) {
+        hydrate::bound(self, name);
+        match self {
+            Node::Web(node) => node
+                .unchecked_ref::<web_sys::Element>()
+                .remove_attribute(name)
+                .expect("remove attribute failed"),
+            #[cfg(feature = "ssr")]
+            Node::Server(node) => node.remove_attribute(name),
+        }
+    }
+    /// Set a property of the inline style, or remove it if `value` is empty.
+    pub fn set_style(&self, name: &str, value: &str) {
+        hydrate::bound(self, "style");
+        match self {
+            Node::Web(node) => {
+                let style = node.unchecked_ref::<web_sys::HtmlElement>().style();
+                if value.is_empty() {
+                    style.remove_property(name).expect("remove style failed");
+                } else {
+                    style.set_property(name, value).expect("set style failed");
+                }
+            }
+            #[cfg(feature = "ssr")]
+            Node::Server(node) => {
+                let style = node.attribute("style").unwrap_or_default();
+                let mut properties: Vec<&str> = style
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|p| !p.is_empty() && p.split(':').next().map(str::trim) != Some(name))
+                    .collect();
+                let new_property = format!("{name}: {value}");
+                if !value.is_empty() {
+                    properties.push(&new_property);
+                }
+                if properties.is_empty() {
+                    node.remove_attribute("style");
+                } else {
+                    node.set_attribute("style", &format!("{};", properties.join("; ")));
+                }
+            }
+        }
+    }
+    /// Set the content of a text node.
+    pub fn set_data(&self, data: &str) {
+        match self {
+            Node::Web(node) => node
+                .unchecked_ref::<web_sys::CharacterData>()
+                .set_data(data),
+            #[cfg(feature = "ssr")]
+            Node::Server(node) => node.set_data(data),
+        }
+    }
+}
+
+macro_rules! impl_from_web {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Node {
+                fn from(node: $ty) -> Self {
+                    Node::Web(node.into())
+                }
+            }
+        )*
+    };
+}
+impl_from_web!(
+    web_sys::Node,
+    web_sys::Element,
+    web_sys::HtmlElement,
+    web_sys::Text,
+    web_sys::Comment
+);
+#[cfg(feature = "ssr")]
+impl From<ServerNode> for Node {
+    fn from(node: ServerNode) -> Self {
+        Node::Server(node)
+    }
+}
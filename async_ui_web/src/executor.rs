@@ -1,13 +1,67 @@
+//! Runs the mounted UI in the browser's event loop.
+//!
+//! A wake doesn't poll right away: it asks the browser to run the executor later,
+//! at a time chosen by the [SchedulePolicy].
+//! A run keeps polling until nothing is awake, but yields back to the browser
+//! once it has run for longer than the [time slice][set_time_slice], so long updates don't block input.
+//! A run in a microtask polls only once, since the browser can't handle input or paint until it returns.
 use std::{
     cell::{Cell, RefCell},
     future::Future,
     pin::Pin,
     task::{Context, Waker},
+    time::Duration,
 };
 
-use wasm_bindgen::{closure::Closure, JsCast, UnwrapThrowExt};
+use browser::{now, request_run};
 
-use crate::window::WINDOW;
+/// When the executor runs after a task is woken.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum SchedulePolicy {
+    /// In a microtask, as soon as the current event handler returns.
+    /// Input is handled with no added latency.
+    #[default]
+    Microtask,
+    /// Right before the next frame is painted.
+    /// All the wakes of a frame are handled together, so intermediate states are never rendered.
+    AnimationFrame,
+    /// Wakes from event handlers run in a microtask, like [Microtask][SchedulePolicy::Microtask],
+    /// and all other wakes wait for the next frame, like [AnimationFrame][SchedulePolicy::AnimationFrame].
+    ///
+    /// Input is handled right away, while timers, requests, and animations are rendered once per frame.
+    InputMicrotask,
+    /// In a new task, with `setTimeout`.
+    Timeout,
+}
+
+/// How a run of the executor was requested from the browser.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Run {
+    Microtask,
+    AnimationFrame,
+    Timeout,
+}
+
+impl SchedulePolicy {
+    /// How to run after a wake. `input` if the wake came from an event handler.
+    fn run_for(self, input: bool) -> Run {
+        match self {
+            SchedulePolicy::Microtask => Run::Microtask,
+            SchedulePolicy::AnimationFrame => Run::AnimationFrame,
+            SchedulePolicy::InputMicrotask if input => Run::Microtask,
+            SchedulePolicy::InputMicrotask => Run::AnimationFrame,
+            SchedulePolicy::Timeout => Run::Timeout,
+        }
+    }
+    /// How to continue after a run stopped with tasks still awake.
+    fn run_to_continue(self) -> Run {
+        match self {
+            SchedulePolicy::AnimationFrame | SchedulePolicy::InputMicrotask => Run::AnimationFrame,
+            // A microtask would run before the browser gets a chance to, so use a new task.
+            SchedulePolicy::Microtask | SchedulePolicy::Timeout => Run::Timeout,
+        }
+    }
+}
 
 thread_local! {
     static EXECUTOR: ExecutorSingleton = ExecutorSingleton::new()
@@ -17,6 +71,11 @@ struct ExecutorSingleton {
     future: RefCell<Option<Pin<Box<dyn Future<Output = ()>>>>>,
     scheduled: Cell<bool>,
     active: Cell<bool>,
+    /// Whether a run of each kind has been requested from the browser and hasn't started yet.
+    requested: [Cell<bool>; 3],
+    policy: Cell<SchedulePolicy>,
+    /// In milliseconds.
+    time_slice: Cell<Option<f64>>,
 }
 
 impl ExecutorSingleton {
@@ -27,36 +86,203 @@ impl ExecutorSingleton {
             future: RefCell::new(None),
             scheduled: Cell::new(false),
             active: Cell::new(false),
+            requested: Default::default(),
+            policy: Cell::new(SchedulePolicy::default()),
+            time_slice: Cell::new(Some(50.0)),
+        }
+    }
+    fn request(&self, kind: Run) {
+        if !self.requested[kind as usize].replace(true) {
+            request_run(kind);
         }
     }
 }
 pub(crate) fn set_executor_future(future: Box<dyn Future<Output = ()>>) {
     EXECUTOR.with(|exe| *exe.future.borrow_mut() = Some(future.into()))
 }
+
+/// Choose when the executor runs after a wake. The default is [SchedulePolicy::Microtask].
+pub fn set_schedule_policy(policy: SchedulePolicy) {
+    EXECUTOR.with(|exe| exe.policy.set(policy))
+}
+
+/// How long a run of the executor may keep polling before it yields to the browser.
+///
+/// The rest of the work continues in a new task (or the next frame), after the browser has handled input and painted.
+/// `None` never yields. The default is 50ms.
+pub fn set_time_slice(slice: Option<Duration>) {
+    EXECUTOR.with(|exe| {
+        exe.time_slice
+            .set(slice.map(|slice| slice.as_secs_f64() * 1000.0))
+    })
+}
+
+/// Poll the UI until nothing is awake, or until the time slice is used up.
 pub fn run_now() {
+    run(Run::Timeout)
+}
+
+fn run(kind: Run) {
     EXECUTOR.with(|exe| {
+        exe.requested[kind as usize].set(false);
         exe.active.set(true);
+        let start = now();
         while exe.scheduled.replace(false) {
             let mut cx = Context::from_waker(&exe.waker);
-            match exe.future.borrow_mut().as_mut() {
-                Some(fu) => {
-                    let _ = fu.as_mut().poll(&mut cx);
-                }
-                None => {}
+            if let Some(fu) = exe.future.borrow_mut().as_mut() {
+                let _ = fu.as_mut().poll(&mut cx);
+            }
+            if !exe.scheduled.get() {
+                break;
+            }
+            // Woken again while polling: more work is ready, or a task keeps waking itself.
+            let over_time = kind == Run::Microtask
+                || exe
+                    .time_slice
+                    .get()
+                    .is_some_and(|slice| now() - start > slice);
+            if over_time {
+                exe.request(exe.policy.get().run_to_continue());
+                break;
             }
         }
         exe.active.set(false);
     })
 }
+
+/// Have the executor run, at the time chosen by the [SchedulePolicy].
 pub fn schedule() {
+    schedule_with(false)
+}
+/// Like [schedule], for wakes from event handlers.
+pub(crate) fn schedule_input() {
+    schedule_with(true)
+}
+fn schedule_with(input: bool) {
     EXECUTOR.with(|exe| {
-        if !exe.scheduled.replace(true) && !exe.active.get() {
-            let closure = Closure::once_into_js(run_now);
-            WINDOW.with(|window| {
-                window
-                    .set_timeout_with_callback(&closure.as_ref().unchecked_ref())
-                    .expect_throw("failed to schedule task");
-            })
+        exe.scheduled.set(true);
+        // A running executor polls again before it returns.
+        if !exe.active.get() {
+            exe.request(exe.policy.get().run_for(input));
         }
     })
 }
+
+#[cfg(not(test))]
+mod browser {
+    use wasm_bindgen::{closure::Closure, prelude::wasm_bindgen, JsCast, JsValue, UnwrapThrowExt};
+
+    use super::{run, Run};
+    use crate::window::WINDOW;
+
+    #[wasm_bindgen]
+    extern "C" {
+        #[wasm_bindgen(js_name = queueMicrotask)]
+        fn queue_microtask(callback: &JsValue);
+    }
+
+    pub(super) fn now() -> f64 {
+        js_sys::Date::now()
+    }
+
+    pub(super) fn request_run(kind: Run) {
+        match kind {
+            Run::Microtask => queue_microtask(&Closure::once_into_js(move || run(kind))),
+            Run::AnimationFrame => {
+                let closure = Closure::once_into_js(move |_timestamp: f64| run(kind));
+                WINDOW.with(|window| {
+                    window
+                        .request_animation_frame(closure.unchecked_ref())
+                        .expect_throw("failed to schedule task");
+                })
+            }
+            Run::Timeout => {
+                let closure = Closure::once_into_js(move || run(kind));
+                WINDOW.with(|window| {
+                    window
+                        .set_timeout_with_callback(closure.unchecked_ref())
+                        .expect_throw("failed to schedule task");
+                })
+            }
+        }
+    }
+}
+
+/// Stands in for the browser in tests: records the requested runs, and has a clock the test moves.
+#[cfg(test)]
+mod browser {
+    use std::cell::{Cell, RefCell};
+
+    use super::Run;
+
+    thread_local! {
+        pub(super) static REQUESTED: RefCell<Vec<Run>> = const { RefCell::new(Vec::new()) };
+        /// In milliseconds.
+        pub(super) static TIME: Cell<f64> = const { Cell::new(0.0) };
+    }
+    pub(super) fn now() -> f64 {
+        TIME.with(Cell::get)
+    }
+    pub(super) fn request_run(kind: Run) {
+        REQUESTED.with(|runs| runs.borrow_mut().push(kind))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::poll_fn, rc::Rc, task::Poll};
+
+    use super::{browser::*, *};
+
+    fn requested() -> Vec<Run> {
+        REQUESTED.with(|runs| std::mem::take(&mut *runs.borrow_mut()))
+    }
+    /// Run the executor with a future that counts its polls, waking itself and taking 10ms each time if `busy`.
+    fn set_counting_future(busy: bool) -> Rc<Cell<u32>> {
+        let polls = Rc::new(Cell::new(0));
+        let polls_1 = polls.clone();
+        set_executor_future(Box::new(poll_fn(move |cx| {
+            polls_1.set(polls_1.get() + 1);
+            if busy {
+                TIME.with(|time| time.set(time.get() + 10.0));
+                cx.waker().wake_by_ref();
+            }
+            Poll::Pending
+        })));
+        polls
+    }
+
+    #[test]
+    fn input_in_microtask_and_the_rest_in_a_frame() {
+        set_schedule_policy(SchedulePolicy::InputMicrotask);
+        let polls = set_counting_future(false);
+        schedule();
+        schedule();
+        assert_eq!(requested(), [Run::AnimationFrame]);
+        schedule_input();
+        assert_eq!(requested(), [Run::Microtask]);
+        run(Run::Microtask);
+        assert_eq!(polls.get(), 1);
+        // The frame comes with nothing left to do.
+        run(Run::AnimationFrame);
+        assert_eq!(polls.get(), 1);
+        schedule();
+        assert_eq!(requested(), [Run::AnimationFrame]);
+    }
+
+    #[test]
+    fn busy_tasks_yield_to_the_browser() {
+        set_schedule_policy(SchedulePolicy::Microtask);
+        let polls = set_counting_future(true);
+        schedule_input();
+        assert_eq!(requested(), [Run::Microtask]);
+        // The microtask doesn't spin; the rest continues in a new task.
+        run(Run::Microtask);
+        assert_eq!(polls.get(), 1);
+        assert_eq!(requested(), [Run::Timeout]);
+        // That task polls until its 50ms slice is used up.
+        run(Run::Timeout);
+        assert_eq!(polls.get(), 7);
+        assert_eq!(requested(), [Run::Timeout]);
+    }
+}
@@ -0,0 +1,102 @@
+//! Bind form [Field]s to inputs, and disable the inputs while the [Form] submits.
+//!
+//! ```ignore
+//! let name = ReactiveCell::new(String::new());
+//! let name = Field::from_cell(&name).validate(|v: &str| match v.is_empty() {
+//!     true => Err("Enter a name".into()),
+//!     false => Ok(()),
+//! });
+//! let form = Form::new(&[&name]);
+//! form_view(&form, fragment((
+//!     text_field(&name, Default::default()),
+//!     async {
+//!         loop {
+//!             submit_button.clicks().next().await;
+//!             form.submit(save(&name)).await;
+//!         }
+//!     },
+//! )))
+//! ```
+pub use async_ui_core::form::{Field, Form, FormField};
+use futures_lite::{FutureExt, StreamExt};
+
+use crate::{
+    components::{
+        checkbox, radio_group, view, CheckboxChangeEvent, CheckboxProps, RadioGroupProps,
+        TextInput, TextInputProps, ViewProps,
+    },
+    fragment,
+    utils::bindings::Binding,
+    Fragment,
+};
+
+/// Render `children` in a `<fieldset>`, which is disabled (with every input inside) while `form` submits.
+///
+/// This also keeps the errors of the form's fields up to date as their values change.
+pub async fn form_view<'c>(form: &'c Form<'_>, children: Fragment<'c>) {
+    let submitting = form.submitting();
+    let attributes = [Binding::Flag("disabled", &submitting)];
+    view(ViewProps {
+        // The attributes live shorter than `children`.
+        children: fragment((children,)),
+        element_tag: "fieldset",
+        attributes: &attributes,
+        ..Default::default()
+    })
+    .or(form.run())
+    .await;
+}
+
+/// A [text_input][crate::components::text_input] for `field`. The `text` prop is replaced by the field's value.
+pub async fn text_field<'c>(field: &'c Field<'_, str>, props: TextInputProps<'c>) {
+    let input = TextInput::new();
+    input
+        .render(TextInputProps {
+            text: Some(field.value()),
+            ..props
+        })
+        .or(async {
+            let mut changes = input.changes();
+            while let Some(ev) = changes.next().await {
+                field.input(ev.get_text());
+            }
+        })
+        .or(async {
+            let mut blurs = input.blurs();
+            while blurs.next().await.is_some() {
+                field.touch();
+            }
+        })
+        .await
+}
+
+/// A [checkbox] for `field`.
+pub async fn checkbox_field(field: &Field<'_, bool>) {
+    let mut on_change = |ev: CheckboxChangeEvent| {
+        field.input(ev.get_value());
+        field.touch();
+    };
+    checkbox(CheckboxProps {
+        value: Some(field.value()),
+        on_change: Some(&mut on_change),
+        ..Default::default()
+    })
+    .await
+}
+
+/// A [radio_group] for `field`, with the [radio_button][crate::components::radio_button]s in `children`.
+pub async fn radio_field<'c, E: Clone + PartialEq + 'static>(
+    field: &'c Field<'_, E>,
+    children: Fragment<'c>,
+) {
+    let mut on_change = |value: E| {
+        field.input(value);
+        field.touch();
+    };
+    radio_group(RadioGroupProps {
+        children: fragment((children,)),
+        value: Some(field.value()),
+        on_change: Some(&mut on_change),
+    })
+    .await
+}
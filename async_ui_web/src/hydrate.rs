@@ -0,0 +1,419 @@
+use std::cell::RefCell;
+#[cfg(feature = "ssr")]
+use std::collections::{HashMap, HashSet};
+
+use async_ui_core::{
+    backend::BackendTrait,
+    executor::{spawn_local_with_priority, Priority},
+    vnode::{node_context::try_get_context, VNodeTrait},
+};
+#[cfg(feature = "ssr")]
+use async_ui_headless::{node::NodeKind, Node as ServerNode};
+use wasm_bindgen::{JsCast, JsValue};
+
+use crate::{
+    backend::Backend,
+    dom::{new_element, new_text_node, Node},
+};
+
+/// Context value marking a tree that is being attached to server-rendered nodes.
+pub(crate) struct Hydrating;
+
+/// The nodes seen by the running hydration pass.
+///
+/// A pass lasts until the components have rendered everything they can right away.
+/// When it ends, server-rendered nodes no component adopted are removed,
+/// and so are the attributes of adopted elements that no component set.
+/// Components rendering later create new nodes.
+#[derive(Default)]
+struct Pass {
+    /// The containers being hydrated.
+    roots: Vec<Node>,
+    /// Nodes adopted by a component that has not added them yet.
+    claimed: Vec<Node>,
+    /// Server-rendered nodes that are now part of a component. Their children are checked too.
+    adopted: NodeSet,
+    /// Nodes created during the pass. They have no server-rendered children.
+    created: NodeSet,
+    /// Claimed elements, with the server-rendered attributes no component has set yet.
+    unbound: Vec<(Node, Vec<String>)>,
+    /// Where each claimed element is in `unbound`.
+    unbound_index: NodeMap,
+}
+
+thread_local! {
+    static PASS: RefCell<Option<Pass>> = const { RefCell::new(None) };
+}
+
+/// Begin hydrating the nodes inside `root`.
+pub(crate) fn start(root: Node) {
+    let new_pass = PASS.with(|pass| {
+        let mut pass = pass.borrow_mut();
+        let new_pass = pass.is_none();
+        pass.get_or_insert_with(Default::default).roots.push(root);
+        new_pass
+    });
+    if new_pass {
+        // Idle tasks only run once no other task is ready.
+        spawn_local_with_priority(Priority::Idle, async { finish() }).detach();
+    }
+}
+
+fn finish() {
+    let Some(pass) = PASS.with(|pass| pass.borrow_mut().take()) else {
+        return;
+    };
+    // Claimed nodes that were never added are not adopted, so they go too.
+    for root in pass.roots.iter() {
+        remove_extra_nodes(root, &pass);
+    }
+    for (node, names) in pass.unbound.iter() {
+        if !pass.adopted.contains(node) {
+            continue;
+        }
+        for name in names {
+            // Kept in sync through DOM properties, which leave the attribute as it was.
+            if PROPERTY_ATTRIBUTES.contains(&name.as_str()) {
+                continue;
+            }
+            report_mismatch(&format!(
+                "removing attribute {name:?} from {}",
+                describe(node)
+            ));
+            node.remove_attribute(name);
+        }
+    }
+}
+
+/// Attributes that components bind through the DOM property of the same name.
+const PROPERTY_ATTRIBUTES: [&str; 3] = ["value", "checked", "selected"];
+
+fn remove_extra_nodes(parent: &Node, pass: &Pass) {
+    let mut child = parent.first_child();
+    while let Some(node) = child {
+        child = node.next_sibling();
+        if pass.adopted.contains(&node) {
+            remove_extra_nodes(&node, pass);
+        } else if !pass.created.contains(&node) {
+            report_mismatch(&format!("removing extra {}", describe(&node)));
+            parent.remove_child(&node);
+        }
+    }
+}
+
+fn with_pass(f: impl FnOnce(&mut Pass)) {
+    PASS.with(|pass| {
+        if let Some(pass) = pass.borrow_mut().as_mut() {
+            f(pass);
+        }
+    })
+}
+
+/// Keep `node` when the pass ends, without checking its children.
+pub(crate) fn created(node: &Node) {
+    with_pass(|pass| pass.created.insert(node));
+}
+
+/// Keep the server-rendered `node` when the pass ends. Its children are checked too.
+pub(crate) fn adopt(node: &Node) {
+    with_pass(|pass| pass.adopted.insert(node));
+}
+
+/// Tell the hydration pass, if any, that a component set attribute `name` of `node`,
+/// so the server-rendered one should stay.
+pub(crate) fn bound(node: &Node, name: &str) {
+    with_pass(|pass| {
+        if let Some(index) = pass.unbound_index.get(node) {
+            pass.unbound[index]
+                .1
+                .retain(|n| !n.eq_ignore_ascii_case(name));
+        }
+    });
+}
+
+/// Identity set of nodes.
+#[derive(Default)]
+struct NodeSet {
+    web: Option<js_sys::Set>,
+    #[cfg(feature = "ssr")]
+    server: HashSet<ServerNode>,
+}
+impl NodeSet {
+    fn insert(&mut self, node: &Node) {
+        match node {
+            Node::Web(node) => {
+                self.web
+                    .get_or_insert_with(|| js_sys::Set::new(&JsValue::UNDEFINED))
+                    .add(node);
+            }
+            #[cfg(feature = "ssr")]
+            Node::Server(node) => {
+                self.server.insert(node.clone());
+            }
+        }
+    }
+    fn contains(&self, node: &Node) -> bool {
+        match node {
+            Node::Web(node) => self.web.as_ref().is_some_and(|set| set.has(node)),
+            #[cfg(feature = "ssr")]
+            Node::Server(node) => self.server.contains(node),
+        }
+    }
+}
+
+/// Identity map from nodes to indices.
+#[derive(Default)]
+struct NodeMap {
+    web: Option<js_sys::Map>,
+    #[cfg(feature = "ssr")]
+    server: HashMap<ServerNode, usize>,
+}
+impl NodeMap {
+    fn insert(&mut self, node: &Node, index: usize) {
+        match node {
+            Node::Web(node) => {
+                self.web
+                    .get_or_insert_with(js_sys::Map::new)
+                    .set(node, &JsValue::from(index as u32));
+            }
+            #[cfg(feature = "ssr")]
+            Node::Server(node) => {
+                self.server.insert(node.clone(), index);
+            }
+        }
+    }
+    fn get(&self, node: &Node) -> Option<usize> {
+        match node {
+            Node::Web(node) => self
+                .web
+                .as_ref()?
+                .get(node)
+                .as_f64()
+                .map(|index| index as usize),
+            #[cfg(feature = "ssr")]
+            Node::Server(node) => self.server.get(node).copied(),
+        }
+    }
+}
+
+pub(crate) enum Expected<'a> {
+    Element(&'a str),
+    Text(&'a str),
+}
+
+pub(crate) fn is_hydrating() -> bool {
+    PASS.with(|pass| pass.borrow().is_some())
+        && Backend::get_vnode_key().is_set()
+        && try_get_context::<Backend, Hydrating>().is_some()
+}
+
+/// Adopt the existing node where the component being created will be added.
+///
+/// Claiming goes in the order components are created,
+/// so a component that shows up later on the client than on the server may take the wrong node.
+pub(crate) fn claim(expected: Expected<'_>) -> Option<Node> {
+    if !is_hydrating() {
+        return None;
+    }
+    let mut claimed = None;
+    Backend::get_vnode_key().with(|vn| {
+        vn.with_child_slot(Default::default(), &mut |parent, after, before| {
+            claimed = claim_between(parent, after, before, &expected);
+        })
+    });
+    if let Some(node) = claimed.as_ref() {
+        let names = attribute_names(node);
+        with_pass(|pass| {
+            pass.claimed.push(node.clone());
+            if !names.is_empty() && pass.unbound_index.get(node).is_none() {
+                pass.unbound_index.insert(node, pass.unbound.len());
+                pass.unbound.push((node.clone(), names));
+            }
+        });
+    }
+    claimed
+}
+
+/// Whether `node` was adopted and so is already in place.
+pub(crate) fn take_claimed(node: &Node) -> bool {
+    let mut found = false;
+    with_pass(|pass| {
+        if let Some(index) = pass.claimed.iter().position(|n| n == node) {
+            pass.claimed.swap_remove(index);
+            pass.adopted.insert(node);
+            found = true;
+        }
+    });
+    found
+}
+
+/// The existing node where the new one goes, if it fits.
+/// If it doesn't, a new node is put before it, so later components can still adopt the nodes that follow.
+fn claim_between(
+    parent: &Node,
+    after: Option<&Node>,
+    before: Option<&Node>,
+    expected: &Expected<'_>,
+) -> Option<Node> {
+    let candidate = match (after, before) {
+        (Some(after), _) => after.next_sibling(),
+        (None, Some(before)) => Some(run_start(before)),
+        (None, None) => parent.first_child(),
+    }
+    .filter(|c| Some(c) != before)?;
+    let new_node = match expected {
+        Expected::Element(tag) => match element_tag(&candidate) {
+            Some(found) if found.eq_ignore_ascii_case(tag) => return Some(candidate),
+            _ => {
+                report_mismatch(&format!("expected <{tag}>, found {}", describe(&candidate)));
+                new_element(tag)
+            }
+        },
+        Expected::Text(data) => match text_data(&candidate) {
+            Some(existing) if existing == *data => return Some(candidate),
+            // The HTML parser joins adjacent text nodes, so take our part and leave the rest.
+            Some(existing) if existing.starts_with(data) => {
+                split_text(&candidate, data);
+                return Some(candidate);
+            }
+            // Most likely the same text with a different value, so replace it.
+            Some(existing) => {
+                report_mismatch(&format!("expected text {data:?}, found {existing:?}"));
+                let node = new_text_node(data);
+                parent.insert_before(&node, Some(&candidate));
+                parent.remove_child(&candidate);
+                return Some(node);
+            }
+            // Empty text renders as nothing on the server.
+            None if data.is_empty() => new_text_node(data),
+            None => {
+                report_mismatch(&format!(
+                    "expected text {data:?}, found {}",
+                    describe(&candidate)
+                ));
+                new_text_node(data)
+            }
+        },
+    };
+    parent.insert_before(&new_node, Some(&candidate));
+    Some(new_node)
+}
+
+/// The first node of the run of nodes ending at `node`.
+/// Runs are delimited by the empty comments `list` puts after each item.
+fn run_start(node: &Node) -> Node {
+    let mut start = node.clone();
+    while let Some(prev) = start.previous_sibling() {
+        if is_list_marker(&prev) {
+            break;
+        }
+        start = prev;
+    }
+    start
+}
+
+/// The empty comments `list` put after each item, in order.
+pub(crate) fn list_markers(container: &Node) -> Vec<Node> {
+    let mut markers = Vec::new();
+    let mut child = container.first_child();
+    while let Some(node) = child {
+        child = node.next_sibling();
+        if is_list_marker(&node) {
+            markers.push(node);
+        }
+    }
+    markers
+}
+
+fn is_list_marker(node: &Node) -> bool {
+    match node {
+        Node::Web(node) => node
+            .dyn_ref::<web_sys::Comment>()
+            .is_some_and(|c| c.data().is_empty()),
+        #[cfg(feature = "ssr")]
+        Node::Server(node) => {
+            matches!(&*node.kind(), NodeKind::Comment(d) if d.is_empty())
+        }
+    }
+}
+
+fn element_tag(node: &Node) -> Option<String> {
+    match node {
+        Node::Web(node) => node.dyn_ref::<web_sys::Element>().map(|e| e.tag_name()),
+        #[cfg(feature = "ssr")]
+        Node::Server(node) => node.tag(),
+    }
+}
+
+fn attribute_names(node: &Node) -> Vec<String> {
+    match node {
+        Node::Web(node) => node
+            .dyn_ref::<web_sys::Element>()
+            .map(|e| {
+                e.get_attribute_names()
+                    .iter()
+                    .filter_map(|name| name.as_string())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        #[cfg(feature = "ssr")]
+        Node::Server(node) => match &*node.kind() {
+            NodeKind::Element { attributes, .. } => attributes.keys().cloned().collect(),
+            _ => Vec::new(),
+        },
+    }
+}
+
+fn text_data(node: &Node) -> Option<String> {
+    match node {
+        Node::Web(node) => node.dyn_ref::<web_sys::Text>().map(|t| t.data()),
+        #[cfg(feature = "ssr")]
+        Node::Server(node) => match &*node.kind() {
+            NodeKind::Text(data) => Some(data.to_owned()),
+            _ => None,
+        },
+    }
+}
+
+/// Cut `node` so that it holds only `keep`, moving the rest of its text to a new node after it.
+fn split_text(node: &Node, keep: &str) {
+    match node {
+        Node::Web(node) => {
+            let offset = keep.encode_utf16().count() as u32;
+            node.unchecked_ref::<web_sys::Text>()
+                .split_text(offset)
+                .expect("split text failed");
+        }
+        #[cfg(feature = "ssr")]
+        Node::Server(node) => {
+            let rest = match &*node.kind() {
+                NodeKind::Text(data) => data[keep.len()..].to_owned(),
+                _ => unreachable!(),
+            };
+            node.set_data(keep);
+            let parent = node.parent().expect("split text failed");
+            parent.insert_before(&ServerNode::new_text(&rest), node.next_sibling().as_ref());
+        }
+    }
+}
+
+fn describe(node: &Node) -> String {
+    match node {
+        Node::Web(node) => node.node_name(),
+        #[cfg(feature = "ssr")]
+        Node::Server(node) => match &*node.kind() {
+            NodeKind::Element { tag, .. } => tag.to_owned(),
+            NodeKind::Text(_) => "#text".into(),
+            NodeKind::Comment(_) => "#comment".into(),
+        },
+    }
+}
+
+pub(crate) fn report_mismatch(message: &str) {
+    #[cfg(feature = "ssr")]
+    if crate::dom::is_rendering_on_server() {
+        eprintln!("Async UI hydration mismatch: {message}");
+        return;
+    }
+    web_sys::console::warn_1(&format!("Async UI hydration mismatch: {message}").into());
+}
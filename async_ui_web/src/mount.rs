@@ -7,16 +7,22 @@ use async_ui_core::{
         WithVNode,
     },
 };
-use web_sys::Node;
 
-use crate::backend::Backend;
+use crate::{
+    backend::Backend,
+    dom::Node,
+    hydrate::{self, Hydrating},
+    with_context,
+};
 
-pub fn mount_at<F: IntoFuture<Output = ()> + 'static>(root: F, node: Node) {
+pub fn mount_at<F: IntoFuture<Output = ()> + 'static>(root: F, node: web_sys::Node) {
     let fut = WithVNode::new(
         root.into_future(),
         Rc::new(
             ConcreteNodeVNode::new(
-                RefNode::<Backend>::Parent { parent: node },
+                RefNode::<Backend>::Parent {
+                    parent: node.into(),
+                },
                 Default::default(),
             )
             .into(),
@@ -34,3 +40,22 @@ pub fn mount<F: IntoFuture<Output = ()> + 'static>(root: F) {
         .unwrap();
     mount_at(root, node.into())
 }
+
+/// Like [mount_at], but adopt the nodes already inside `node` (rendered on the server) instead of creating new ones.
+///
+/// Only what components render right away adopts nodes. Existing nodes no component adopted are then removed.
+/// Mismatches between the existing nodes and what components render are reported to the console.
+pub fn hydrate_at<F: IntoFuture<Output = ()> + 'static>(root: F, node: web_sys::Node) {
+    hydrate::start(Node::Web(node.clone()));
+    mount_at(with_context(root, Rc::new(Hydrating)), node)
+}
+
+pub fn hydrate<F: IntoFuture<Output = ()> + 'static>(root: F) {
+    let node = web_sys::window()
+        .unwrap()
+        .document()
+        .unwrap()
+        .body()
+        .unwrap();
+    hydrate_at(root, node.into())
+}
@@ -0,0 +1,144 @@
+//! Render different components depending on the URL, without reloading the page.
+//!
+//! Wrap the app in [router], then put [route_outlet]s where the content for the current path should go.
+//! [link][crate::components::link]s inside a router navigate through it.
+//!
+//! On the server, there is no URL bar to follow.
+//! Provide a [Router::new_in_memory] with [with_context][crate::with_context] instead of using [router].
+use std::{
+    future::IntoFuture,
+    rc::{Rc, Weak},
+};
+
+use async_ui_core::router as base;
+pub use async_ui_core::router::{History, MemoryHistory, Params, RoutePattern, Router};
+use wasm_bindgen::{prelude::Closure, JsCast, JsValue};
+use web_sys::HtmlAnchorElement;
+
+use crate::{backend::Backend, executor::schedule_input, with_context, WINDOW};
+
+pub type Route<'c> = base::Route<'c, Backend>;
+
+/// Records navigation in the browser's session history.
+struct BrowserHistory;
+impl History for BrowserHistory {
+    fn push(&self, location: &str) {
+        WINDOW.with(|win| {
+            win.history()
+                .expect("no history")
+                .push_state_with_url(&JsValue::NULL, "", Some(location))
+                .expect("push state failed")
+        });
+    }
+    fn replace(&self, location: &str) {
+        WINDOW.with(|win| {
+            win.history()
+                .expect("no history")
+                .replace_state_with_url(&JsValue::NULL, "", Some(location))
+                .expect("replace state failed")
+        });
+    }
+    fn back(&self) -> Option<String> {
+        WINDOW.with(|win| {
+            win.history()
+                .expect("no history")
+                .back()
+                .expect("back failed")
+        });
+        // The new location comes with the popstate event.
+        None
+    }
+}
+
+/// Updates the router when the user goes back or forward.
+struct PopstateListener(Closure<dyn Fn()>);
+impl PopstateListener {
+    fn new(router: Weak<Router>) -> Self {
+        let on_popstate = Closure::new(move || {
+            if let Some(router) = router.upgrade() {
+                router.set_location(&browser_location());
+                schedule_input();
+            }
+        });
+        WINDOW.with(|win| {
+            win.add_event_listener_with_callback("popstate", on_popstate.as_ref().unchecked_ref())
+                .expect("add popstate listener failed")
+        });
+        Self(on_popstate)
+    }
+}
+impl Drop for PopstateListener {
+    fn drop(&mut self) {
+        WINDOW.with(|win| {
+            let _ = win
+                .remove_event_listener_with_callback("popstate", self.0.as_ref().unchecked_ref());
+        });
+    }
+}
+
+fn browser_location() -> String {
+    WINDOW.with(|win| {
+        let location = win.location();
+        let part = |r: Result<String, JsValue>| r.unwrap_or_default();
+        format!(
+            "{}{}{}",
+            part(location.pathname()),
+            part(location.search()),
+            part(location.hash())
+        )
+    })
+}
+
+/// Navigate through `router` instead of loading a new page, if `anchor` links within this site.
+pub(crate) fn intercept(router: &Router, anchor: &HtmlAnchorElement, event: &web_sys::MouseEvent) {
+    let modified = event.ctrl_key() || event.meta_key() || event.shift_key() || event.alt_key();
+    let same_tab = matches!(anchor.target().as_str(), "" | "_self");
+    let same_origin = WINDOW.with(|win| win.location().origin().ok()) == Some(anchor.origin());
+    if event.default_prevented()
+        || event.button() != 0
+        || modified
+        || !same_tab
+        || !same_origin
+        || anchor.has_attribute("download")
+    {
+        return;
+    }
+    event.prevent_default();
+    router.navigate(&format!(
+        "{}{}{}",
+        anchor.pathname(),
+        anchor.search(),
+        anchor.hash()
+    ));
+}
+
+/// Make the browser location available to [route_outlet], [navigate], and links inside `children`.
+pub async fn router<I: IntoFuture>(children: I) -> I::Output {
+    let router = Rc::new(Router::new(&browser_location(), Box::new(BrowserHistory)));
+    let _listener = PopstateListener::new(Rc::downgrade(&router));
+    with_context(children, router).await
+}
+
+/// The [Router] of the enclosing [router].
+pub fn get_router() -> Rc<Router> {
+    base::get_router::<Backend>()
+}
+
+/// Go to `to` using the enclosing [router].
+pub fn navigate(to: &str) {
+    get_router().navigate(to)
+}
+
+/// The params matched by the enclosing routes, including those of outer outlets.
+pub fn route_params() -> Params {
+    base::route_params::<Backend>()
+}
+
+/// Render the first route in `routes` that matches the current path.
+///
+/// The rendered content is dropped and replaced when the path starts matching a different route
+/// or the same route with different params.
+/// A route ending in `*rest` leaves the rest of the path to the outlets inside it.
+pub async fn route_outlet<'c>(routes: &[Route<'c>]) {
+    base::route_outlet(routes).await
+}
@@ -0,0 +1,89 @@
+use std::{
+    future::IntoFuture,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use async_ui_core::{
+    executor::{run_on_this_thread, spawn_local},
+    vnode::{
+        node_concrete::{ConcreteNodeVNode, RefNode},
+        WithVNode,
+    },
+};
+pub use async_ui_headless::Node as ServerNode;
+use scoped_async_spawn::GiveUnforgettableScope;
+
+use crate::{
+    backend::Backend,
+    dom::{set_rendering_on_server, Node},
+    hydrate::{self, Hydrating},
+    with_context,
+};
+
+pub enum RenderUntil {
+    /// Stop as soon as no component has anything left to do.
+    Idle,
+    /// Keep running components as they get woken (by finished requests, timers, ...)
+    /// until the duration has passed.
+    Timeout(Duration),
+}
+
+/// Render components to HTML without a browser.
+///
+/// Components create in-memory nodes instead of DOM nodes while this runs,
+/// and no event handler is attached.
+/// The components are dropped before this function returns.
+pub fn render_to_string<F: IntoFuture<Output = ()> + 'static>(
+    root: F,
+    until: RenderUntil,
+) -> String {
+    render_into(root, &ServerNode::new_element("body"), until)
+}
+
+/// Like [render_to_string], but adopt the nodes already inside `container`,
+/// the way `hydrate_at` does in the browser, and return the resulting HTML.
+///
+/// This checks what hydrating server-rendered HTML does without a browser.
+/// Mismatches are printed to stderr.
+pub fn hydrate_to_string<F: IntoFuture<Output = ()> + 'static>(
+    root: F,
+    container: &ServerNode,
+    until: RenderUntil,
+) -> String {
+    hydrate::start(Node::Server(container.clone()));
+    render_into(with_context(root, Rc::new(Hydrating)), container, until)
+}
+
+fn render_into<F: IntoFuture<Output = ()> + 'static>(
+    root: F,
+    container: &ServerNode,
+    until: RenderUntil,
+) -> String {
+    let deadline = match until {
+        RenderUntil::Idle => None,
+        RenderUntil::Timeout(duration) => Some(Instant::now() + duration),
+    };
+    set_rendering_on_server(true);
+    let _guard = scopeguard::guard((), |_| set_rendering_on_server(false));
+
+    let root = WithVNode::new(
+        root.into_future(),
+        Rc::new(
+            ConcreteNodeVNode::new(
+                RefNode::<Backend>::Parent {
+                    parent: Node::Server(container.clone()),
+                },
+                Default::default(),
+            )
+            .into(),
+        ),
+    );
+    let task = spawn_local(GiveUnforgettableScope::new_static(root));
+    run_on_this_thread(deadline);
+    let html = container.inner_html();
+    // Cancelled tasks are dropped by the executor, so run it once more.
+    drop(task);
+    run_on_this_thread(None);
+    html
+}
@@ -0,0 +1,228 @@
+//! Timers: [sleep], [interval], and [Debounced] and [Throttled] observables.
+//!
+//! In the browser they use `setTimeout`. When rendering on the server (with the `ssr` feature),
+//! one shared thread wakes them, so `RenderUntil::Timeout` sees them fire.
+//!
+//! Search as you type:
+//! ```ignore
+//! let query = Rc::new(ReactiveCell::new(String::new()));
+//! let settled = Debounced::<str>::new(query.as_observable_rc(), Duration::from_millis(300));
+//! text_input(TextInputProps {
+//!     on_change_text: Some(&mut |e| query.set(e.get_text())),
+//!     ..Default::default()
+//! })
+//! .or(search_results(&settled))
+//! .await;
+//! ```
+use std::{future::Future, pin::Pin, rc::Rc, time::Duration};
+
+use async_ui_core::time as base;
+use observables::{cell::ReactiveCell, Observable, ObservableAsExt};
+use wasm_bindgen::{closure::Closure, JsCast};
+
+use crate::{backend::Backend, WINDOW};
+
+pub type Debounced<T> = base::Debounced<Backend, T>;
+pub type Throttled<T> = base::Throttled<Backend, T>;
+
+/// Complete after `duration`.
+pub async fn sleep(duration: Duration) {
+    base::sleep::<Backend>(duration).await
+}
+
+/// Call `tick` every `period`. Never completes.
+pub async fn interval<F: FnMut()>(period: Duration, tick: F) {
+    base::interval::<Backend, F>(period, tick).await
+}
+
+pub(crate) fn sleep_future(duration: Duration) -> Pin<Box<dyn Future<Output = ()>>> {
+    #[cfg(feature = "ssr")]
+    if crate::dom::is_rendering_on_server() {
+        return Box::pin(server::ThreadSleep::new(duration));
+    }
+    Box::pin(timeout(duration))
+}
+
+async fn timeout(duration: Duration) {
+    let done = Rc::new(ReactiveCell::new(false));
+    let done_1 = done.clone();
+    let callback = Closure::once(move || done_1.set(true));
+    let handle = WINDOW.with(|win| {
+        win.set_timeout_with_callback_and_timeout_and_arguments_0(
+            callback.as_ref().unchecked_ref(),
+            duration.as_millis().try_into().unwrap_or(i32::MAX),
+        )
+        .expect("set timeout failed")
+    });
+    // Clear the timeout if dropped before it fires.
+    let _guard = scopeguard::guard(handle, |handle| {
+        WINDOW.with(|win| win.clear_timeout_with_handle(handle))
+    });
+    while !*done.as_observable().borrow_observable() {
+        done.as_observable().until_change().await;
+    }
+}
+
+#[cfg(feature = "ssr")]
+mod server {
+    use std::{
+        collections::BTreeMap,
+        future::Future,
+        pin::Pin,
+        sync::{Condvar, Mutex, OnceLock},
+        task::{Context, Poll, Waker},
+        thread,
+        time::{Duration, Instant},
+    };
+
+    /// The one thread that wakes every [ThreadSleep] when it is due.
+    struct TimerThread {
+        state: Mutex<TimerState>,
+        /// Notified when a sleep comes due earlier than the thread is waiting for.
+        changed: Condvar,
+    }
+    #[derive(Default)]
+    struct TimerState {
+        next_id: u64,
+        /// Pending sleeps by deadline, then id.
+        pending: BTreeMap<(Instant, u64), Waker>,
+    }
+
+    fn timer_thread() -> &'static TimerThread {
+        static TIMER: OnceLock<TimerThread> = OnceLock::new();
+        TIMER.get_or_init(|| {
+            thread::Builder::new()
+                .name("async_ui_web timers".into())
+                .spawn(run_timers)
+                .expect("spawn timer thread failed");
+            TimerThread {
+                state: Mutex::new(TimerState::default()),
+                changed: Condvar::new(),
+            }
+        })
+    }
+
+    fn run_timers() {
+        let timer = timer_thread();
+        let mut state = timer.state.lock().unwrap();
+        loop {
+            let now = Instant::now();
+            let due = match state.pending.keys().next() {
+                Some(&(deadline, _)) if deadline <= now => {
+                    let later = state.pending.split_off(&(now, u64::MAX));
+                    std::mem::replace(&mut state.pending, later)
+                }
+                Some(&(deadline, _)) => {
+                    state = timer.changed.wait_timeout(state, deadline - now).unwrap().0;
+                    continue;
+                }
+                None => {
+                    state = timer.changed.wait(state).unwrap();
+                    continue;
+                }
+            };
+            drop(state);
+            due.into_values().for_each(Waker::wake);
+            state = timer.state.lock().unwrap();
+        }
+    }
+
+    /// Sleeps on the timer thread, for the server where there is no `setTimeout`.
+    ///
+    /// Dropping it takes it off the thread's list, so cancelled sleeps cost nothing.
+    pub(super) struct ThreadSleep {
+        deadline: Instant,
+        /// Set once the sleep is on the timer thread's list.
+        id: Option<u64>,
+    }
+    impl ThreadSleep {
+        pub(super) fn new(duration: Duration) -> Self {
+            Self {
+                deadline: Instant::now() + duration,
+                id: None,
+            }
+        }
+    }
+    impl Future for ThreadSleep {
+        type Output = ();
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            if Instant::now() >= self.deadline {
+                return Poll::Ready(());
+            }
+            let timer = timer_thread();
+            let mut state = timer.state.lock().unwrap();
+            let id = *self.id.get_or_insert_with(|| {
+                state.next_id += 1;
+                state.next_id
+            });
+            let key = (self.deadline, id);
+            state.pending.insert(key, cx.waker().to_owned());
+            // The thread waits for the earliest deadline, so tell it about an earlier one.
+            if state.pending.keys().next() == Some(&key) {
+                timer.changed.notify_one();
+            }
+            Poll::Pending
+        }
+    }
+    impl Drop for ThreadSleep {
+        fn drop(&mut self) {
+            if let Some(id) = self.id {
+                let mut state = timer_thread().state.lock().unwrap();
+                state.pending.remove(&(self.deadline, id));
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::{
+            sync::{
+                atomic::{AtomicBool, Ordering},
+                Arc,
+            },
+            task::Wake,
+        };
+
+        use super::*;
+
+        struct Flag(AtomicBool);
+        impl Wake for Flag {
+            fn wake(self: Arc<Self>) {
+                self.0.store(true, Ordering::SeqCst);
+            }
+        }
+
+        fn poll(sleep: &mut ThreadSleep, flag: &Arc<Flag>) -> Poll<()> {
+            let waker = Waker::from(flag.clone());
+            Pin::new(sleep).poll(&mut Context::from_waker(&waker))
+        }
+
+        #[test]
+        fn sleeps_share_one_thread_and_dropped_ones_are_removed() {
+            let flag = Arc::new(Flag(AtomicBool::new(false)));
+            let mut short = ThreadSleep::new(Duration::from_millis(20));
+            let mut long = ThreadSleep::new(Duration::from_secs(3600));
+            assert!(poll(&mut short, &flag).is_pending());
+            assert!(poll(&mut long, &flag).is_pending());
+            let long_key = (long.deadline, long.id.unwrap());
+            assert!(timer_thread()
+                .state
+                .lock()
+                .unwrap()
+                .pending
+                .contains_key(&long_key));
+            drop(long);
+            assert!(!timer_thread()
+                .state
+                .lock()
+                .unwrap()
+                .pending
+                .contains_key(&long_key));
+
+            while !flag.0.load(Ordering::SeqCst) {
+                thread::sleep(Duration::from_millis(5));
+            }
+            assert!(poll(&mut short, &flag).is_ready());
+        }
+    }
+}
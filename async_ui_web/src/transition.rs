@@ -0,0 +1,133 @@
+//! Animate components as they mount and unmount, with CSS classes.
+//!
+//! Nodes added inside a [transition] go through these classes, named after [TransitionProps::name]:
+//! - `{name}-enter-from` and `{name}-enter-active` as they are added,
+//! - then `{name}-enter-to` (replacing `-from`) from the next frame until the CSS transitions and animations are over.
+//!
+//! Nodes removed (when the components that made them are dropped) stay in place while they go through
+//! `{name}-exit-from`, `{name}-exit-active` and `{name}-exit-to` the same way, and are removed after.
+//!
+//! ```css
+//! .fade-enter-active, .fade-exit-active { transition: opacity 0.3s; }
+//! .fade-enter-from, .fade-exit-to { opacity: 0; }
+//! ```
+use std::{future::IntoFuture, rc::Rc};
+
+use async_ui_core::{
+    executor::spawn_local,
+    vnode::node_transition::{TransitionHooks, WithTransition},
+};
+use wasm_bindgen::JsCast;
+use web_sys::Element;
+
+use crate::{animation::Animator, backend::Backend, dom::Node, Fragment, WINDOW};
+
+pub struct TransitionProps<'c> {
+    pub children: Fragment<'c>,
+    /// The prefix of the classes. Defaults to `"transition"`.
+    pub name: &'c str,
+}
+impl<'c> Default for TransitionProps<'c> {
+    fn default() -> Self {
+        Self {
+            children: Default::default(),
+            name: "transition",
+        }
+    }
+}
+
+/// Render `children`, animating the elements they add and remove. See the [module docs][self].
+pub async fn transition<'c>(TransitionProps { children, name }: TransitionProps<'c>) {
+    let hooks = Rc::new(CssTransition {
+        name: name.to_owned(),
+    });
+    WithTransition::<Backend, _>::new(children.into_future(), hooks).await
+}
+
+#[derive(Clone)]
+struct CssTransition {
+    name: String,
+}
+
+impl CssTransition {
+    fn class(&self, stage: &str) -> String {
+        format!("{}-{stage}", self.name)
+    }
+    /// Go from `{phase}-from` to `{phase}-to`, with `{phase}-active` throughout, and wait for the CSS to finish.
+    async fn run(&self, element: &Element, phase: &str) {
+        let [from, active, to] =
+            ["from", "active", "to"].map(|stage| self.class(&format!("{phase}-{stage}")));
+        let classes = element.class_list();
+        let _ = classes.add_2(&from, &active);
+        let animator = Animator::new();
+        let start = animator.next_frame().await;
+        let _ = classes.remove_1(&from);
+        let _ = classes.add_1(&to);
+        let duration = css_duration(element);
+        while animator.next_frame().await - start < duration {}
+        let _ = classes.remove_2(&active, &to);
+    }
+}
+
+impl TransitionHooks<Backend> for CssTransition {
+    fn enter(&self, node: &Node) {
+        if let Some(element) = as_element(node) {
+            let this = self.clone();
+            spawn_local(async move { this.run(&element, "enter").await }).detach();
+        }
+    }
+
+    fn exit(&self, node: &Node, parent: &Node, next: Option<&Node>) {
+        if let Some(element) = as_element(node) {
+            parent.insert_before(node, next);
+            let this = self.clone();
+            spawn_local(async move {
+                this.run(&element, "exit").await;
+                element.remove();
+            })
+            .detach();
+        }
+    }
+}
+
+/// Only elements have classes to animate with. Text is added and removed right away.
+fn as_element(node: &Node) -> Option<Element> {
+    node.web()?.dyn_ref::<Element>().cloned()
+}
+
+/// The longest time, in milliseconds, that the element's CSS transitions and animations take.
+fn css_duration(element: &Element) -> f64 {
+    let style = match WINDOW.with(|win| win.get_computed_style(element)) {
+        Ok(Some(style)) => style,
+        _ => return 0.0,
+    };
+    let get = |property: &str| parse_times(&style.get_property_value(property).unwrap_or_default());
+    ["transition", "animation"]
+        .iter()
+        .map(|kind| {
+            let durations = get(&format!("{kind}-duration"));
+            let delays = get(&format!("{kind}-delay"));
+            durations
+                .iter()
+                .enumerate()
+                .map(|(i, duration)| duration + delays.get(i % delays.len().max(1)).unwrap_or(&0.0))
+                .fold(0.0, f64::max)
+        })
+        .fold(0.0, f64::max)
+}
+
+/// Parse a CSS list of times like `0.3s, 150ms` into milliseconds.
+fn parse_times(list: &str) -> Vec<f64> {
+    list.split(',')
+        .map(|time| {
+            let time = time.trim();
+            if let Some(ms) = time.strip_suffix("ms") {
+                ms.parse().unwrap_or(0.0)
+            } else if let Some(s) = time.strip_suffix('s') {
+                s.parse::<f64>().unwrap_or(0.0) * 1000.0
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
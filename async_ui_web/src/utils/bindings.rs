@@ -0,0 +1,84 @@
+use std::future::pending;
+
+use futures_lite::FutureExt;
+use observables::{ObservableAs, ObservableAsExt};
+
+use crate::dom::Node;
+
+/// Keeps part of an element in sync with an observable.
+///
+/// Give a list of these to a component, for things it has no prop for:
+/// ```ignore
+/// view(ViewProps {
+///     attributes: &[
+///         Binding::Attr("id", &"sidebar"),
+///         Binding::Style("width", &width),
+///         Binding::Flag("hidden", &collapsed),
+///     ],
+///     ..Default::default()
+/// })
+/// ```
+pub enum Binding<'c> {
+    /// An attribute, like `id`, `title`, `aria-*`, or `data-*`.
+    Attr(&'c str, &'c dyn ObservableAs<str>),
+    /// An attribute that is removed while the value is `None`.
+    OptionalAttr(&'c str, &'c dyn ObservableAs<Option<String>>),
+    /// A boolean attribute, like `disabled` or `hidden`, present while the value is `true`.
+    Flag(&'c str, &'c dyn ObservableAs<bool>),
+    /// A property of the inline style. It is removed while the value is empty.
+    Style(&'c str, &'c dyn ObservableAs<str>),
+    /// A numeric property of the inline style, with its unit, like `("left", &x, "px")` or `("opacity", &fade, "")`.
+    ///
+    /// Animation [Tween][crate::animation::Tween]s and [Spring][crate::animation::Spring]s can be bound this way.
+    StyleNumber(&'c str, &'c dyn ObservableAs<f64>, &'c str),
+}
+
+impl<'c> Binding<'c> {
+    fn apply(&self, elem: &Node) {
+        match self {
+            Binding::Attr(name, value) => elem.set_attribute(name, &value.borrow_observable_as()),
+            Binding::OptionalAttr(name, value) => match &*value.borrow_observable_as() {
+                Some(v) => elem.set_attribute(name, v),
+                None => elem.remove_attribute(name),
+            },
+            Binding::Flag(name, value) => {
+                if *value.borrow_observable_as() {
+                    elem.set_attribute(name, "");
+                } else {
+                    elem.remove_attribute(name);
+                }
+            }
+            Binding::Style(name, value) => elem.set_style(name, &value.borrow_observable_as()),
+            Binding::StyleNumber(name, value, unit) => {
+                elem.set_style(name, &format!("{}{unit}", *value.borrow_observable_as()))
+            }
+        }
+    }
+    async fn until_change(&self) {
+        match self {
+            Binding::Attr(_, value) | Binding::Style(_, value) => value.until_change().await,
+            Binding::OptionalAttr(_, value) => value.until_change().await,
+            Binding::Flag(_, value) => value.until_change().await,
+            Binding::StyleNumber(_, value, _) => value.until_change().await,
+        }
+    }
+    async fn sync(&self, elem: &Node) {
+        loop {
+            self.apply(elem);
+            self.until_change().await;
+        }
+    }
+}
+
+/// Keep each of `bindings` in sync with `elem`, each in its own loop. Never completes.
+pub(crate) async fn sync_bindings(elem: &Node, bindings: &[Binding<'_>]) {
+    match bindings {
+        [] => pending().await,
+        [binding, rest @ ..] => {
+            binding
+                .sync(elem)
+                .or(Box::pin(sync_bindings(elem, rest)))
+                .await
+        }
+    }
+}
@@ -1,8 +1,13 @@
 use std::{borrow::Cow, cell::RefCell, collections::HashSet, hash::Hash};
 
+#[cfg(feature = "ssr")]
+use async_ui_headless::Node as ServerNode;
 use smallvec::SmallVec;
+use wasm_bindgen::JsCast;
 use web_sys::DomTokenList;
 
+use crate::{dom::Node, hydrate};
+
 pub struct ClassList<'a> {
     inner: RefCell<Inner<'a>>,
 }
@@ -12,7 +17,51 @@ struct Inner<'a> {
 }
 enum DomEnum {
     None,
-    Inserted(SmallVec<[DomTokenList; 1]>),
+    Inserted(SmallVec<[ClassTarget; 1]>),
+}
+enum ClassTarget {
+    Web(DomTokenList),
+    #[cfg(feature = "ssr")]
+    Server(ServerNode),
+}
+impl ClassTarget {
+    fn add_1(&self, class_name: &str) {
+        match self {
+            ClassTarget::Web(dom) => dom.add_1(class_name).expect("ClassList add failed"),
+            #[cfg(feature = "ssr")]
+            ClassTarget::Server(node) => {
+                let mut classes = server_classes(node);
+                if !classes.iter().any(|c| c == class_name) {
+                    classes.push(class_name.to_owned());
+                    node.set_attribute("class", &classes.join(" "));
+                }
+            }
+        }
+    }
+    fn remove_1(&self, class_name: &str) {
+        match self {
+            ClassTarget::Web(dom) => dom.remove_1(class_name).expect("ClassList remove failed"),
+            #[cfg(feature = "ssr")]
+            ClassTarget::Server(node) => {
+                let mut classes = server_classes(node);
+                classes.retain(|c| c != class_name);
+                node.set_attribute("class", &classes.join(" "));
+            }
+        }
+    }
+    fn clear(&self) {
+        match self {
+            ClassTarget::Web(dom) => dom.set_value(""),
+            #[cfg(feature = "ssr")]
+            ClassTarget::Server(node) => node.set_attribute("class", ""),
+        }
+    }
+}
+#[cfg(feature = "ssr")]
+fn server_classes(node: &ServerNode) -> Vec<String> {
+    node.attribute("class")
+        .map(|s| s.split_ascii_whitespace().map(ToOwned::to_owned).collect())
+        .unwrap_or_default()
 }
 
 impl<'a> ClassList<'a> {
@@ -28,8 +77,7 @@ impl<'a> ClassList<'a> {
         let mut bm = self.inner.borrow_mut();
         let v = class_name.into();
         if let DomEnum::Inserted(dom) = &bm.dom {
-            dom.iter()
-                .for_each(|dom| dom.add_1(&*v).expect("ClassList add failed"));
+            dom.iter().for_each(|dom| dom.add_1(&v));
         }
         bm.rust.insert(v);
     }
@@ -37,8 +85,7 @@ impl<'a> ClassList<'a> {
         let mut bm = self.inner.borrow_mut();
         let v = class_name.into();
         if let DomEnum::Inserted(dom) = &bm.dom {
-            dom.iter()
-                .for_each(|dom| dom.remove_1(&*v).expect("ClassList remove failed"));
+            dom.iter().for_each(|dom| dom.remove_1(&v));
         }
         bm.rust.remove(&v);
     }
@@ -50,13 +97,11 @@ impl<'a> ClassList<'a> {
         let v = class_name.into();
         if bm.rust.remove(&v) {
             if let DomEnum::Inserted(dom) = &bm.dom {
-                dom.iter()
-                    .for_each(|dom| dom.remove_1(&*v).expect("ClassList remove failed"));
+                dom.iter().for_each(|dom| dom.remove_1(&v));
             }
         } else {
             if let DomEnum::Inserted(dom) = &bm.dom {
-                dom.iter()
-                    .for_each(|dom| dom.add_1(&*v).expect("ClassList add failed"));
+                dom.iter().for_each(|dom| dom.add_1(&v));
             }
             bm.rust.insert(v);
         }
@@ -71,15 +116,23 @@ impl<'a> ClassList<'a> {
         let mut bm = self.inner.borrow_mut();
         if let DomEnum::Inserted(doms) = &bm.dom {
             for dom in doms.iter() {
-                dom.set_value("");
+                dom.clear();
             }
         }
         bm.rust = SmallSet::new();
     }
-    pub(crate) fn set_dom(&self, dom: DomTokenList) {
+    pub(crate) fn set_dom(&self, node: &Node) {
+        hydrate::bound(node, "class");
+        let dom = match node {
+            Node::Web(node) => {
+                ClassTarget::Web(node.unchecked_ref::<web_sys::Element>().class_list())
+            }
+            #[cfg(feature = "ssr")]
+            Node::Server(node) => ClassTarget::Server(node.clone()),
+        };
         let mut bm = self.inner.borrow_mut();
         bm.rust.for_each(|item| {
-            dom.add_1(&*item).expect("ClassList add failed");
+            dom.add_1(item);
         });
         if let DomEnum::Inserted(lst) = &mut bm.dom {
             lst.push(dom);
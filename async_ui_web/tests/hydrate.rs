@@ -0,0 +1,155 @@
+use std::{cell::Cell, future::Future, rc::Rc, time::Duration};
+
+use async_ui_web::{
+    components::{text, view, ViewProps},
+    fragment,
+    ssr::{hydrate_to_string, RenderUntil, ServerNode},
+    time::sleep,
+    utils::bindings::Binding,
+};
+
+fn element(tag: &str, children: &[&ServerNode]) -> ServerNode {
+    let node = ServerNode::new_element(tag);
+    for child in children {
+        node.append_child(child);
+    }
+    node
+}
+
+/// Hydrate `root` into `body`, and tell whether `node` was adopted:
+/// still in `body` once the hydration pass is over.
+fn hydrate_keeping<F: Future<Output = ()> + 'static>(
+    root: F,
+    body: &ServerNode,
+    node: &ServerNode,
+) -> (String, bool) {
+    let kept = Rc::new(Cell::new(false));
+    let (kept_1, body_1, node_1) = (kept.clone(), body.clone(), node.clone());
+    let html = hydrate_to_string(
+        fragment((root, async move {
+            // The components are dropped before hydrate_to_string returns, so look while they run.
+            sleep(Duration::from_millis(1)).await;
+            kept_1.set(node_1.parent() == Some(body_1));
+        })),
+        body,
+        RenderUntil::Timeout(Duration::from_millis(20)),
+    );
+    (html, kept.get())
+}
+
+async fn paragraph(content: &'static str) {
+    view(ViewProps {
+        children: fragment((text(&content),)),
+        element_tag: "p",
+        ..Default::default()
+    })
+    .await;
+}
+
+#[test]
+fn adopts_matching_nodes() {
+    let p = element("p", &[&ServerNode::new_text("hello")]);
+    let body = element("body", &[&p]);
+    let (html, kept) = hydrate_keeping(paragraph("hello"), &body, &p);
+    assert_eq!(html, "<p>hello</p>");
+    assert!(kept);
+}
+
+#[test]
+fn replaces_mismatched_text() {
+    let p = element("p", &[&ServerNode::new_text("old")]);
+    let body = element("body", &[&p]);
+    let (html, kept) = hydrate_keeping(paragraph("new"), &body, &p);
+    assert_eq!(html, "<p>new</p>");
+    assert!(kept);
+}
+
+#[test]
+fn splits_joined_text() {
+    let p = element("p", &[&ServerNode::new_text("ab")]);
+    let body = element("body", &[&p]);
+    let (html, kept) = hydrate_keeping(
+        view(ViewProps {
+            children: fragment((text(&"a"), text(&"b"))),
+            element_tag: "p",
+            ..Default::default()
+        }),
+        &body,
+        &p,
+    );
+    assert_eq!(html, "<p>ab</p>");
+    assert!(kept);
+}
+
+#[test]
+fn removes_extra_nodes() {
+    let p = element("p", &[&ServerNode::new_text("a")]);
+    let extra = element("div", &[&ServerNode::new_text("stale")]);
+    let body = element("body", &[&p, &extra]);
+    let (html, kept) = hydrate_keeping(paragraph("a"), &body, &p);
+    assert_eq!(html, "<p>a</p>");
+    assert!(kept);
+}
+
+#[test]
+fn creates_missing_nodes() {
+    let p = element("p", &[&ServerNode::new_text("b")]);
+    let body = element("body", &[&p]);
+    let (html, kept) = hydrate_keeping(
+        fragment((
+            view(ViewProps {
+                children: fragment((text(&"a"),)),
+                element_tag: "h1",
+                ..Default::default()
+            }),
+            paragraph("b"),
+        )),
+        &body,
+        &p,
+    );
+    assert_eq!(html, "<h1>a</h1><p>b</p>");
+    assert!(kept);
+}
+
+#[test]
+fn stops_adopting_after_first_pass() {
+    let p = element("p", &[&ServerNode::new_text("late")]);
+    let body = element("body", &[&p]);
+    let html = hydrate_to_string(
+        async {
+            sleep(Duration::from_millis(10)).await;
+            paragraph("late").await;
+        },
+        &body,
+        RenderUntil::Timeout(Duration::from_millis(100)),
+    );
+    assert_eq!(html, "<p>late</p>");
+}
+
+#[test]
+fn removes_unbound_attributes() {
+    let p = element("p", &[&ServerNode::new_text("a")]);
+    p.set_attribute("title", "old");
+    p.set_attribute("hidden", "");
+    p.set_attribute("data-stale", "x");
+    let body = element("body", &[&p]);
+    let (html, kept) = hydrate_keeping(
+        async {
+            view(ViewProps {
+                children: fragment((text(&"a"),)),
+                element_tag: "p",
+                attributes: &[
+                    Binding::Attr("title", &"new"),
+                    Binding::Flag("hidden", &false),
+                    Binding::Style("color", &"red"),
+                ],
+                ..Default::default()
+            })
+            .await
+        },
+        &body,
+        &p,
+    );
+    assert_eq!(html, "<p style=\"color: red;\" title=\"new\">a</p>");
+    assert!(kept);
+}
@@ -0,0 +1,18 @@
+use async_ui_web::router::RoutePattern;
+
+#[test]
+fn route_patterns() {
+    let user = RoutePattern::parse("/users/:id");
+    assert_eq!(user.matches("/users/42").unwrap().get("id"), Some("42"));
+    assert_eq!(user.matches("/users/42/").unwrap().get("id"), Some("42"));
+    assert!(user.matches("/users").is_none());
+    assert!(user.matches("/users/42/posts").is_none());
+    assert!(user.matches("/groups/42").is_none());
+
+    let files = RoutePattern::parse("/files/*path");
+    assert_eq!(files.matches("/files/a/b/c").unwrap().get("path"), Some("a/b/c"));
+    assert_eq!(files.matches("/files").unwrap().get("path"), Some(""));
+
+    assert!(RoutePattern::parse("/").matches("/").is_some());
+    assert!(RoutePattern::parse("/").matches("/about").is_none());
+}
@@ -0,0 +1,311 @@
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+use async_ui_web::{
+    components::{
+        button, checkbox, link, list, multi_select, number_input, progress, select, slider, text,
+        text_input, view, virtual_list, ButtonProps, CheckboxProps, ItemHeight, LinkProps,
+        ListModel, ListProps, MultiSelectProps, NumberInputProps, ProgressProps, SelectOptions,
+        SelectProps, SliderProps, TextInputProps, ViewProps, VirtualListProps,
+    },
+    fragment,
+    futures_lite::{future::yield_now, FutureExt},
+    ssr::{render_to_string, RenderUntil},
+    utils::{bindings::Binding, class_list::ClassList},
+};
+use observables::cell::ReactiveCell;
+
+#[test]
+fn components() {
+    let html = render_to_string(
+        async {
+            let class = ClassList::new(["a", "b"]);
+            fragment((
+                view(ViewProps {
+                    children: fragment((text(&"Hello <world>"),)),
+                    class: Some(&class),
+                    element_tag: "section",
+                    ..Default::default()
+                }),
+                button(ButtonProps {
+                    children: fragment((text(&"Press"),)),
+                    ..Default::default()
+                }),
+                link(LinkProps {
+                    children: fragment((text(&"Home"),)),
+                    href: Some(&"/"),
+                    ..Default::default()
+                }),
+                checkbox(CheckboxProps {
+                    value: Some(&true),
+                    ..Default::default()
+                }),
+            ))
+            .await;
+        },
+        RenderUntil::Idle,
+    );
+    assert_eq!(
+        html,
+        "<section class=\"a b\">Hello &lt;world&gt;</section>\
+        <button>Press</button>\
+        <a href=\"/\">Home</a>\
+        <input checked=\"\" type=\"checkbox\">"
+    );
+}
+
+#[test]
+fn attribute_bindings() {
+    let title = Rc::new(ReactiveCell::new(String::from("first")));
+    let title_1 = title.clone();
+    let html = render_to_string(
+        async move {
+            let label = ReactiveCell::new(Some(String::from("menu")));
+            let description = ReactiveCell::new(None);
+            view(ViewProps {
+                attributes: &[
+                    Binding::Attr("id", &"main"),
+                    Binding::Attr("title", &title_1.as_observable()),
+                    Binding::OptionalAttr("aria-label", &label.as_observable()),
+                    Binding::OptionalAttr("aria-description", &description.as_observable()),
+                    Binding::Flag("hidden", &true),
+                    Binding::Style("color", &"red"),
+                    Binding::Style("margin-top", &"4px"),
+                    Binding::StyleNumber("opacity", &0.5, ""),
+                ],
+                ..Default::default()
+            })
+            .or(async {
+                yield_now().await;
+                title_1.set(String::from("second"));
+                std::future::pending().await
+            })
+            .await;
+        },
+        RenderUntil::Idle,
+    );
+    assert_eq!(
+        html,
+        "<div aria-label=\"menu\" hidden=\"\" id=\"main\" style=\"color: red; margin-top: 4px; opacity: 0.5;\" title=\"second\"></div>"
+    );
+}
+
+#[test]
+fn form_controls() {
+    let html = render_to_string(
+        async {
+            let options = [(1u8, "one"), (2, "two"), (3, "three")];
+            let picked = ReactiveCell::new(vec![1u8, 3]);
+            fragment((
+                select(SelectProps {
+                    options: SelectOptions::Slice(&options),
+                    value: Some(&2u8),
+                    ..Default::default()
+                }),
+                multi_select(MultiSelectProps {
+                    options: SelectOptions::Slice(&options),
+                    value: Some(&picked.as_observable()),
+                    ..Default::default()
+                }),
+                slider(SliderProps {
+                    value: Some(&0.5),
+                    min: Some(0.0),
+                    max: Some(1.0),
+                    step: Some(0.25),
+                    ..Default::default()
+                }),
+                number_input(NumberInputProps {
+                    value: Some(&3.0),
+                    ..Default::default()
+                }),
+                progress(ProgressProps {
+                    value: Some(&0.25),
+                    ..Default::default()
+                }),
+            ))
+            .await;
+        },
+        RenderUntil::Idle,
+    );
+    assert_eq!(
+        html,
+        "<select><option value=\"0\">one</option><option selected=\"\" value=\"1\">two</option>\
+        <option value=\"2\">three</option></select>\
+        <select multiple=\"\"><option selected=\"\" value=\"0\">one</option>\
+        <option value=\"1\">two</option><option selected=\"\" value=\"2\">three</option></select>\
+        <input max=\"1\" min=\"0\" step=\"0.25\" type=\"range\" value=\"0.5\">\
+        <input placeholder=\"\" type=\"number\" value=\"3\">\
+        <progress value=\"0.25\"></progress>"
+    );
+}
+
+#[test]
+fn text_input_props() {
+    let html = render_to_string(
+        text_input(TextInputProps {
+            text: Some(&"secret"),
+            input_type: Some("password"),
+            max_length: Some(8),
+            readonly: Some(&true),
+            ..Default::default()
+        }),
+        RenderUntil::Idle,
+    );
+    assert_eq!(
+        html,
+        "<input maxlength=\"8\" placeholder=\"\" readonly=\"\" type=\"password\" value=\"secret\">"
+    );
+}
+
+#[test]
+fn list_with_timeout() {
+    let items = Rc::new(ReactiveCell::new(ListModel::from_iter(
+        ["x", "y"].into_iter(),
+    )));
+    let items_1 = items.clone();
+    let html = render_to_string(
+        async move {
+            list(ListProps {
+                data: Some(&items_1.as_observable()),
+                render: Some(&|item: &'static str| async move { text(&item).await }),
+                ..Default::default()
+            })
+            .await;
+        },
+        RenderUntil::Timeout(Duration::from_millis(10)),
+    );
+    assert_eq!(html, "<div>x<!---->y<!----></div>");
+}
+
+#[test]
+fn keyed_list_moves_items() {
+    let items = Rc::new(ReactiveCell::new(ListModel::from_iter(
+        ["a", "b", "c", "d"].into_iter(),
+    )));
+    let renders = Rc::new(Cell::new(0));
+    let (items_1, renders_1) = (items.clone(), renders.clone());
+    let html = render_to_string(
+        async move {
+            let render = |item: &'static str| {
+                renders_1.set(renders_1.get() + 1);
+                async move { fragment((text(&item), text(&"!"))).await }
+            };
+            let reorder = async {
+                yield_now().await;
+                items_1.borrow_mut().set_keyed(["d", "b", "a", "e"], |s| *s);
+            };
+            fragment((
+                list(ListProps {
+                    data: Some(&items_1.as_observable()),
+                    render: Some(&render),
+                    ..Default::default()
+                }),
+                reorder,
+            ))
+            .await;
+        },
+        RenderUntil::Idle,
+    );
+    assert_eq!(html, "<div>d!<!---->b!<!---->a!<!---->e!<!----></div>");
+    assert_eq!(renders.get(), 5);
+}
+
+#[test]
+fn select_updates_only_changed_options() {
+    let options = Rc::new(ReactiveCell::new(ListModel::from_iter(
+        [(1u8, "a"), (2, "b"), (3, "c")]
+            .into_iter()
+            .map(|(value, label)| (value, label.to_string())),
+    )));
+    let options_1 = options.clone();
+    let html = render_to_string(
+        async move {
+            let change = async {
+                yield_now().await;
+                let mut model = options_1.borrow_mut();
+                model.remove(0);
+                model.push((4, "d".to_string()));
+                model.move_item(2, 0);
+            };
+            fragment((
+                select(SelectProps {
+                    options: SelectOptions::List(&options_1.as_observable()),
+                    value: Some(&2u8),
+                    ..Default::default()
+                }),
+                change,
+            ))
+            .await;
+        },
+        RenderUntil::Idle,
+    );
+    // Options that stayed keep the value they were rendered with.
+    assert_eq!(
+        html,
+        "<select><option value=\"3\">d</option><option selected=\"\" value=\"1\">b</option>\
+        <option value=\"2\">c</option></select>"
+    );
+}
+
+#[test]
+fn list_resyncs_after_log_overflow() {
+    let mut model = ListModel::from_iter(["a", "b"].into_iter());
+    model.set_log_capacity(1);
+    let items = Rc::new(ReactiveCell::new(model));
+    let items_1 = items.clone();
+    let renders = Rc::new(Cell::new(0));
+    let renders_1 = renders.clone();
+    let html = render_to_string(
+        async move {
+            let change = async {
+                yield_now().await;
+                let mut model = items_1.borrow_mut();
+                model.push("c");
+                model.push("d");
+                model.remove(0);
+            };
+            let render = |item: &'static str| {
+                renders_1.set(renders_1.get() + 1);
+                async move { text(&item).await }
+            };
+            fragment((
+                list(ListProps {
+                    data: Some(&items_1.as_observable()),
+                    render: Some(&render),
+                    ..Default::default()
+                }),
+                change,
+            ))
+            .await;
+        },
+        RenderUntil::Idle,
+    );
+    assert_eq!(html, "<div>b<!---->c<!---->d<!----></div>");
+    // "b" keeps its row; only "c" and "d" are rendered after the first two.
+    assert_eq!(renders.get(), 4);
+}
+
+#[test]
+fn virtual_list_renders_only_overscan_on_server() {
+    let items = Rc::new(ReactiveCell::new(ListModel::from_iter(0..50_000)));
+    let items_1 = items.clone();
+    let html = render_to_string(
+        async move {
+            virtual_list(VirtualListProps {
+                data: Some(&items_1.as_observable()),
+                render: Some(&|item: i32| async move { text(&item.to_string()).await }),
+                item_height: ItemHeight::Fixed(10.0),
+                overscan: 2,
+                ..Default::default()
+            })
+            .await;
+        },
+        RenderUntil::Idle,
+    );
+    assert_eq!(
+        html,
+        "<div style=\"overflow-y: auto;\"><div style=\"position: relative; height: 500000px;\">\
+        <div style=\"position: absolute; left: 0; right: 0; top: 0px; height: 10px;\">0</div>\
+        <div style=\"position: absolute; left: 0; right: 0; top: 10px; height: 10px;\">1</div>\
+        </div></div>"
+    );
+}
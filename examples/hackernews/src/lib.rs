@@ -7,7 +7,7 @@ use async_ui_web as async_ui;
 
 use async_ui::{
     components::{button, list, text, view, ButtonProps, ListModel, ListProps, ViewProps},
-    fragment, mount,
+    error_boundary, fragment, mount,
 };
 #[cfg(feature = "web")]
 use async_ui_web::components::{link, LinkProps};
@@ -75,8 +75,7 @@ pub async fn root() {
                 .await;
                 Ok(())
             }
-            item_fallible(client, story_id).await;
-            pending::<()>().await;
+            error_boundary(item_fallible(client, story_id), |_e| pending::<()>()).await;
         }
         fragment((
             list(ListProps {
@@ -100,8 +99,7 @@ pub async fn root() {
         .await;
         Ok(())
     }
-    root_fallible().await;
-    text(&"error").await;
+    error_boundary(root_fallible(), |_e| text(&"error")).await;
 }
 
 #[derive(serde::Deserialize)]
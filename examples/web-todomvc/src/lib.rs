@@ -4,8 +4,8 @@ use async_ui_web::components::{radio_button, radio_group, RadioGroupProps, Radio
 use async_ui_web::futures_lite::FutureExt;
 use async_ui_web::{
     components::{
-        button, list, text, text_input, view, ButtonProps, ListModel, ListProps, TextInputProps,
-        ViewProps,
+        button, list, text, text_input, view, ButtonProps, ListModel, ListObservableExt, ListProps,
+        TextInputProps, ViewProps,
     },
     fragment, mount,
     utils::class_list::ClassList,
@@ -277,7 +277,6 @@ async fn list_content(store: &Store<State>) {
             }
         };
         let done_classes = ClassList::new(["done-button"]);
-        let view_classes = ClassList::new(["list-item"]);
         let input_classes = ClassList::new(["item-input"]);
         view(ViewProps {
             children: fragment((
@@ -303,37 +302,51 @@ async fn list_content(store: &Store<State>) {
                     ..Default::default()
                 }),
             )),
-            class: Some(&view_classes),
+            class: Some(&"list-item".into()),
             ..Default::default()
         })
         .or(async {
             let done_obs = handle.done.as_observable();
-            let filter_obs = store.filter.as_observable();
             loop {
                 let v = *done_obs.borrow_observable();
-                let f = *filter_obs.borrow_observable();
                 done_classes.set("done-button-done", v);
                 input_classes.set("item-input-done", v);
-                let visible = match (f, v) {
-                    (DisplayFilter::All, _) => true,
-                    (DisplayFilter::Active, false) => true,
-                    (DisplayFilter::Complete, true) => true,
-                    _ => false,
-                };
-                view_classes.set("hidden", !visible);
-                done_obs.until_change().or(filter_obs.until_change()).await;
+                done_obs.until_change().await;
             }
         })
         .await;
     }
 
+    let shown = store.todos_list.as_observable().filtered(|id: &TodoId| {
+        let done = match store.todos_map.borrow().get(id) {
+            Some(todo) => *todo.done.borrow(),
+            None => return false,
+        };
+        match *store.filter.borrow() {
+            DisplayFilter::All => true,
+            DisplayFilter::Active => !done,
+            DisplayFilter::Complete => done,
+        }
+    });
     let render = &|id| list_item(store, id);
     list(ListProps {
-        data: Some(&store.todos_list.as_observable()),
+        data: Some(&shown),
         render: Some(render),
         class: Some(&"list-content".into()),
         ..Default::default()
     })
+    .or(async {
+        // Any todo being marked done or not also updates the counts.
+        loop {
+            store
+                .filter
+                .as_observable()
+                .until_change()
+                .or(store.counts.as_observable().until_change())
+                .await;
+            shown.refresh_all();
+        }
+    })
     .await;
 }
 
@@ -391,6 +404,7 @@ async fn bottom_part(store: &Store<State>) {
                 children: fragment((radio_button(RadioProps { value: filter }), text(&label))),
                 class: Some(&classes),
                 element_tag: "label",
+                ..Default::default()
             })
             .or(async {
                 loop {
@@ -3,6 +3,7 @@ use std::{
     borrow::Borrow,
     cell::{Ref, RefCell},
     marker::PhantomData,
+    rc::Rc,
     task::Waker,
 };
 
@@ -45,6 +46,13 @@ impl<T> ReactiveCell<T> {
             _phantom: PhantomData,
         }
     }
+    /// Like [as_observable][Self::as_observable], but keeping the cell alive through an `Rc`.
+    pub fn as_observable_rc(self: &Rc<Self>) -> ReactiveCellObservable<T, Rc<Self>> {
+        ReactiveCellObservable {
+            inner: self.clone(),
+            _phantom: PhantomData,
+        }
+    }
 }
 pub struct ReactiveCellObservable<T, A: Borrow<ReactiveCell<T>>> {
     pub(crate) inner: A,